@@ -1,12 +1,14 @@
 use std::fmt::Debug;
 
 use crate::{Texture, VertexBuffer};
-use patutil::{mesh::Mesh};
+use patutil::{mesh::Mesh, Color, Size};
 
 pub struct GLMesh {
     vbo: Option<VertexBuffer>,
     texture: Option<Texture>,
     vertices: Vec<f32>,
+    // size and rgba pixels of the texture, uploaded together with the vertices
+    pixels: Option<(Size, Vec<Color>)>,
 }
 
 impl GLMesh {
@@ -39,7 +41,8 @@ impl GLMesh {
         Self {
             vbo: None,
             texture: None,
-            vertices
+            vertices,
+            pixels: None,
         }
     }
 
@@ -48,7 +51,18 @@ impl GLMesh {
         Self {
             vbo: None,
             texture: None,
-            vertices
+            vertices,
+            pixels: None,
+        }
+    }
+
+    // the texture is multiplied with the model color, transparent pixels are not drawn
+    pub fn with_texture(vertices: Vec<f32>, size: Size, pixels: Vec<Color>) -> Self {
+        Self {
+            vbo: None,
+            texture: None,
+            vertices,
+            pixels: Some((size, pixels)),
         }
     }
 
@@ -65,7 +79,8 @@ impl GLMesh {
         Self {
             vbo: None,
             texture: None,
-            vertices
+            vertices,
+            pixels: None,
         }
     }
 
@@ -73,20 +88,21 @@ impl GLMesh {
         // vertex buffer
         let vbo = VertexBuffer::from_vertices_3d(&self.vertices).unwrap();
 
-        // create texture
-        // let image = image::open("testfiles/viking_room.png")
-        //     .expect("Failed to open img: testfiles/viking_room.png")
-        //     .into_rgba8();
-        // let texture = Texture::new(
-        //     [image.width(), image.height()].into(),
-        //     gl::RGBA,
-        //     gl::RGBA,
-        //     gl::UNSIGNED_BYTE,
-        //     image.as_bytes().as_ptr() as *const _,
-        // );
+        // create texture, meshes without one get a white pixel so only the model color is used
+        let (size, pixels) = self
+            .pixels
+            .take()
+            .unwrap_or(([1, 1].into(), vec![Color::new(255, 255, 255, 255)]));
+        let texture = Texture::new(
+            size,
+            gl::RGBA,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_ptr() as *const _,
+        );
 
         self.vbo = Some(vbo);
-        // self.texture = Some(texture);
+        self.texture = Some(texture);
     }
 }
 
@@ -142,7 +142,7 @@ impl MeshRender {
 
         // calc projection
         let m: nalgebra_glm::Mat4 =
-            nalgebra_glm::perspective_lh(rect.w as f32 / rect.h as f32, 3.14 / 2.0, 0.1, 1000.0);
+            nalgebra_glm::perspective_lh(rect.w as f32 / rect.h as f32, crate::FIELD_OF_VIEW, 0.1, 1000.0);
         proj.data = m.data.as_slice().try_into().unwrap();
         proj.data.copy_from_slice(m.data.as_slice());
 
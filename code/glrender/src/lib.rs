@@ -22,6 +22,9 @@ pub use gl;
 
 use patutil::{csg::SceneRef, text::Text, Color, Pos, Rect, Render, Size, Line};
 
+// vertical field of view of the mesh and point projection, in radians
+pub const FIELD_OF_VIEW: f32 = 3.14 / 2.;
+
 pub fn gl_init(loader: impl FnMut(&'static str) -> *const c_void) {
     gl::load_with(loader);
 
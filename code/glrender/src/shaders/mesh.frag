@@ -14,6 +14,12 @@ void main()
 	// gl_FragColor = vec4(vTexCoord,0.,1.0);
 	// gl_FragColor = vec4(abs(vNormal), 1);
 	if (uColor.w > 0.){
+		// transparent parts of the texture are cut out
+		vec4 texel = texture(uTexture, vTexCoord);
+		if (texel.w < 0.5){
+			discard;
+		}
+
 		vec3 lightColor = vec3(1.,1.,1.);
 		vec3 lightDir = normalize(-vec3(0.,-1.,1.));
 
@@ -27,7 +33,7 @@ void main()
 		vec3 diffuse = diff * lightColor;
 
 		// calc result
-		vec3 result = (ambient + diffuse) * vec3(uColor);
+		vec3 result = (ambient + diffuse) * vec3(uColor) * vec3(texel);
 		gl_FragColor = vec4(result, 1.0);
 	}
 	else{
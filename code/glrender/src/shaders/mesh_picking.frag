@@ -5,11 +5,16 @@ in vec2 vTexCoord;
 in vec3 vNormal;
 in vec3 vFragPos;
 
+uniform sampler2D uTexture;
 uniform uint uID;
 
 out uint oID;
 
 void main()
 {
+	// transparent parts of the texture cannot be picked
+	if (texture(uTexture, vTexCoord).w < 0.5){
+		discard;
+	}
 	oID = uID;
 }
\ No newline at end of file
@@ -2,11 +2,16 @@ use std::time::Instant;
 
 use crate::{
     camera::{Camera, CameraBase},
+    Pos, Vecf4,
+};
+#[cfg(feature = "window")]
+use crate::{
     window::{MouseButton, MouseButtonState, KeyCode, KeyState},
-    Pos, Rect, Vecf2, Vecf4,
+    Rect, Vecf2,
 };
 
 #[derive(Debug)]
+#[cfg_attr(not(feature = "window"), allow(dead_code))]
 pub struct FollowCam {
     base: CameraBase,
     move_vec: Vecf4,
@@ -28,6 +33,7 @@ impl Camera for FollowCam {
         self.update()
     }
 
+    #[cfg(feature = "window")]
     fn update(&mut self, window_state: &crate::window::WindowState, rect: Rect) {
         let cursor_pos = window_state.cursor().pos();
         if self.focus {
@@ -132,5 +138,6 @@ impl FollowCam {
         false
     }
 
+    #[cfg(feature = "window")]
     const MOVE_VAL: f32 = 10.;
 }
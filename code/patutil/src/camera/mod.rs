@@ -1,10 +1,8 @@
 use std::{sync::{Mutex, Arc, MutexGuard}, fmt::Debug};
 
-use crate::{
-    {Vecf2, Vecf4},
-    window::WindowState,
-    Rect,
-};
+use crate::{Vecf2, Vecf4};
+#[cfg(feature = "window")]
+use crate::{window::WindowState, Rect};
 
 const DEFAULT_RIGHT: Vecf4 = Vecf4::new(1., 0., 0., 0.);
 const DEFAULT_UP: Vecf4 = Vecf4::new(0., 1., 0., 0.);
@@ -60,6 +58,7 @@ pub trait Camera: Send + Sync + Debug {
         self.base_mut().rotate_cam(v);
     }
 
+    #[cfg(feature = "window")]
     fn update(&mut self, _window_state: &WindowState, _rect: Rect) {}
 }
 #[derive(Debug, Clone)]
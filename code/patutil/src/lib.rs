@@ -10,6 +10,7 @@ mod vec;
 pub use vec::*;
 mod color;
 pub use color::*;
+#[cfg(feature = "window")]
 pub mod window;
 mod angle;
 pub use angle::*;
@@ -12,4 +12,11 @@ fn test_norm(){
     assert_eq!(Vecf3::new(0.,0.5,0.).norm(), Vecf3::new(0., 1., 0.));
     assert_eq!(Vecf3::new(0.,-0.5,0.).norm(), Vecf3::new(0., -1., 0.));
     assert_eq!(Vecf3::new(0.,0.,0.).norm(), Vecf3::new(0., 0., 0.));
-}
\ No newline at end of file
+}
+#[test]
+fn test_vecf3_string(){
+    let v = Vecf3::new(0.1, -2.5, 1.0e-7);
+    let s = v.to_string();
+    assert_eq!(s, "[{0.1},{-2.5},{0.0000001}]");
+    assert_eq!(s.parse::<Vecf3>(), Ok(v));
+}
@@ -1,3 +1,10 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+};
+
+use patfile::{pscan, pwrite};
+
 
 
 #[derive(Clone, Copy, PartialEq, PartialOrd, Ord, Eq, Hash)]
@@ -218,6 +225,33 @@ impl From<Vector3<f32>> for Vector3<i32>{
 }
 
 
+impl<T> FromStr for Vector3<T>
+where
+    T: FromStr + Display + Default,
+    <T as FromStr>::Err: ::std::fmt::Debug,
+{
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut x = T::default();
+        let mut y = T::default();
+        let mut z = T::default();
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => "[{},{},{}]", x,y,z).map_err(|_| ())?;
+
+        Ok(Vector3::new(x,y,z))
+    }
+}
+
+impl<T: Display> Display for Vector3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!("[{},{},{}]" => writer, &self.x, &self.y, &self.z).unwrap();
+        Ok(())
+    }
+}
+
 impl<T: Default> Default for Vector3<T>{
     fn default() -> Self {
         Self { x: T::default(), y: T::default(), z: T::default() }
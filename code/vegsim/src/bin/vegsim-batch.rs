@@ -0,0 +1,411 @@
+use std::{env, fs::File, path::Path, process};
+
+use patfile::pwrite;
+use vegsim::{
+    export::{
+        gltf::export_glb,
+        obj::{export_obj, ObjOptions},
+        skeleton::{export_skeleton_csv, export_skeleton_json, import_skeleton, skeleton_to_metamer},
+    },
+    parameters,
+    profile::ParameterProfile,
+    tree::{
+        budfate::BudState,
+        calendar::Calendar,
+        pruning_module::{
+            hedge::PruningVolume,
+            rules::PruningProtocol,
+            strategy::{AutoPruning, PruningSchedule, PruningSeason, StrategyRegistry},
+            PruneOperation,
+        },
+        snapshot::Snapshot,
+        Simulation,
+    },
+    treeparameter::TreeParameter,
+};
+
+const USAGE: &str = "usage: vegsim-batch [--iterations N] [--seed N] [--profile PATH] [--save-profile PATH] [--prune OPERATION] [--rules PATH] [--hedge PATH] [--prune-every N] [--spalier] [--strategy NAME] [--strategy-param NAME=VALUE] [--strategy-schedule SCHEDULE] [--strategy-season SEASON] [--season N] [--dormancy N] [--prune-season SEASON] [--load SNAPSHOT] [--snapshot SNAPSHOT] [--obj PATH] [--separate-metamers] [--glb PATH] [--pruning-history PATH] [--fruit-stats PATH] [--season-stats PATH] [--skeleton PATH] [--import-skeleton PATH] [--output PATH]";
+
+// settings for a single headless simulation run
+struct BatchSettings {
+    iterations: u32,
+    seed: u64,
+    profile: Option<String>,
+    save_profile: Option<String>,
+    prune: Option<PruneOperation>,
+    rules: Option<String>,
+    hedge: Option<String>,
+    prune_every: u32,
+    strategy: Option<String>,
+    strategy_params: Vec<String>,
+    strategy_schedule: PruningSchedule,
+    strategy_season: Option<PruningSeason>,
+    // growing and dormant iterations of a year, no calendar without a season length
+    season: Option<u32>,
+    dormancy: u32,
+    prune_season: PruningSeason,
+    load: Option<String>,
+    snapshot: Option<String>,
+    obj: Option<String>,
+    merge_metamers: bool,
+    glb: Option<String>,
+    pruning_history: Option<String>,
+    fruit_stats: Option<String>,
+    season_stats: Option<String>,
+    skeleton: Option<String>,
+    import_skeleton: Option<String>,
+    output: String,
+}
+
+impl BatchSettings {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut settings = Self {
+            iterations: 10,
+            seed: parameters::SEED,
+            profile: None,
+            save_profile: None,
+            prune: None,
+            rules: None,
+            hedge: None,
+            prune_every: 1,
+            strategy: None,
+            strategy_params: vec![],
+            strategy_schedule: PruningSchedule::EveryIteration,
+            strategy_season: None,
+            season: None,
+            dormancy: 1,
+            prune_season: PruningSeason::Any,
+            load: None,
+            snapshot: None,
+            obj: None,
+            merge_metamers: true,
+            glb: None,
+            pruning_history: None,
+            fruit_stats: None,
+            season_stats: None,
+            skeleton: None,
+            import_skeleton: None,
+            output: "tree.txt".to_string(),
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--iterations" => settings.iterations = Self::value(&arg, args.next())?,
+                "--seed" => settings.seed = Self::value(&arg, args.next())?,
+                "--profile" => settings.profile = Some(Self::value(&arg, args.next())?),
+                "--save-profile" => settings.save_profile = Some(Self::value(&arg, args.next())?),
+                "--prune" => settings.prune = Some(Self::value(&arg, args.next())?),
+                "--rules" => settings.rules = Some(Self::value(&arg, args.next())?),
+                "--hedge" => settings.hedge = Some(Self::value(&arg, args.next())?),
+                "--prune-every" => settings.prune_every = Self::value(&arg, args.next())?,
+                "--spalier" => settings.strategy = Some("spalier".to_string()),
+                "--strategy" => settings.strategy = Some(Self::value(&arg, args.next())?),
+                "--strategy-param" => settings.strategy_params.push(Self::value(&arg, args.next())?),
+                "--strategy-schedule" => settings.strategy_schedule = Self::value(&arg, args.next())?,
+                "--strategy-season" => settings.strategy_season = Some(Self::value(&arg, args.next())?),
+                "--season" => settings.season = Some(Self::value(&arg, args.next())?),
+                "--dormancy" => settings.dormancy = Self::value(&arg, args.next())?,
+                "--prune-season" => settings.prune_season = Self::value(&arg, args.next())?,
+                "--load" => settings.load = Some(Self::value(&arg, args.next())?),
+                "--snapshot" => settings.snapshot = Some(Self::value(&arg, args.next())?),
+                "--obj" => settings.obj = Some(Self::value(&arg, args.next())?),
+                "--separate-metamers" => settings.merge_metamers = false,
+                "--glb" => settings.glb = Some(Self::value(&arg, args.next())?),
+                "--pruning-history" => settings.pruning_history = Some(Self::value(&arg, args.next())?),
+                "--fruit-stats" => settings.fruit_stats = Some(Self::value(&arg, args.next())?),
+                "--season-stats" => settings.season_stats = Some(Self::value(&arg, args.next())?),
+                "--skeleton" => settings.skeleton = Some(Self::value(&arg, args.next())?),
+                "--import-skeleton" => settings.import_skeleton = Some(Self::value(&arg, args.next())?),
+                "--output" => settings.output = Self::value(&arg, args.next())?,
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+
+        if settings.prune_every == 0 {
+            return Err("--prune-every must be at least 1".to_string());
+        }
+        if settings.profile.is_some() && settings.load.is_some() {
+            // a snapshot contains the parameters it was grown with
+            return Err("--profile can not be combined with --load".to_string());
+        }
+        if settings.strategy.is_none() && !settings.strategy_params.is_empty() {
+            return Err("--strategy-param needs a --strategy".to_string());
+        }
+        if settings.strategy.is_none() && settings.strategy_season.is_some() {
+            return Err("--strategy-season needs a --strategy".to_string());
+        }
+        if settings.season == Some(0) || settings.dormancy == 0 {
+            return Err("--season and --dormancy must be at least 1".to_string());
+        }
+        Ok(settings)
+    }
+
+    // strategy with its parameters set, the one of a loaded snapshot is kept when none is given
+    fn auto_pruning(&self) -> Result<Option<AutoPruning>, String> {
+        let name = match &self.strategy {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        let mut strategy = StrategyRegistry::default().create(name).map_err(|err| err.to_string())?;
+        for param in &self.strategy_params {
+            let (key, value) = param
+                .split_once('=')
+                .ok_or(format!("expected NAME=VALUE for --strategy-param, found {}", param))?;
+            let value = value
+                .parse()
+                .map_err(|_| format!("invalid value {} for {}", value, key))?;
+            strategy.set_parameter(key, value).map_err(|err| err.to_string())?;
+        }
+        let mut auto_pruning = AutoPruning::new(strategy, self.strategy_schedule).map_err(|err| err.to_string())?;
+        if let Some(season) = self.strategy_season {
+            auto_pruning.set_season(season).map_err(|err| err.to_string())?;
+        }
+        Ok(Some(auto_pruning))
+    }
+
+    // the calendar of a loaded snapshot is kept when no season is given
+    fn calendar(&self) -> Option<Calendar> {
+        self.season.map(|season| Calendar::new(season, self.dormancy))
+    }
+
+    fn value<T: std::str::FromStr>(arg: &str, value: Option<String>) -> Result<T, String> {
+        let value = value.ok_or(format!("missing value for {}", arg))?;
+        value
+            .parse()
+            .map_err(|_| format!("invalid value {} for {}", value, arg))
+    }
+}
+
+fn main() {
+    let settings = match BatchSettings::from_args(env::args().skip(1)) {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            process::exit(1);
+        }
+    };
+
+    // continue from a snapshot or start a new plant
+    let mut simulation = match &settings.load {
+        Some(path) => match Snapshot::load(Path::new(path)) {
+            Ok(snapshot) => Simulation::from_snapshot(snapshot),
+            Err(err) => {
+                eprintln!("Failed to load {}: {}", path, err);
+                process::exit(1);
+            }
+        },
+        None => {
+            let profile = match &settings.profile {
+                Some(path) => match ParameterProfile::load(Path::new(path)) {
+                    Ok(profile) => profile,
+                    Err(err) => {
+                        eprintln!("Failed to load {}: {}", path, err);
+                        process::exit(1);
+                    }
+                },
+                None => ParameterProfile::default(),
+            };
+            Simulation::with_profile(profile, settings.seed)
+        }
+    };
+    if let Some(path) = &settings.import_skeleton {
+        let genetics = simulation.shared_plant_genetics();
+        let root = import_skeleton(Path::new(path))
+            .and_then(|nodes| skeleton_to_metamer(&nodes, genetics, simulation.plant_mut().ids_mut()));
+        match root {
+            Ok(root) => simulation.import_plant(root),
+            Err(err) => {
+                eprintln!("Failed to import {}: {}", path, err);
+                process::exit(1);
+            }
+        }
+    }
+    let protocol = settings.rules.as_ref().map(|path| match PruningProtocol::load(Path::new(path)) {
+        Ok(protocol) => protocol,
+        Err(err) => {
+            eprintln!("Failed to load {}: {}", path, err);
+            process::exit(1);
+        }
+    });
+    let volume = settings.hedge.as_ref().map(|path| match PruningVolume::load(Path::new(path)) {
+        Ok(volume) => volume,
+        Err(err) => {
+            eprintln!("Failed to load {}: {}", path, err);
+            process::exit(1);
+        }
+    });
+    match settings.auto_pruning() {
+        Ok(Some(auto_pruning)) => simulation.update_tree_param(TreeParameter::AutoPruning(Some(auto_pruning))),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+
+    if let Some(calendar) = settings.calendar() {
+        simulation.update_tree_param(TreeParameter::Calendar(Some(calendar)));
+    }
+
+    for it in 1..=settings.iterations {
+        simulation.perform_growth_iteration();
+
+        // prune between growth iterations, only in the chosen season when following a calendar
+        let in_season = simulation
+            .last_season()
+            .is_none_or(|season| settings.prune_season.allows(season));
+        if it % settings.prune_every == 0 && in_season {
+            if let Some(op) = &settings.prune {
+                simulation.prune_by_rule(op.clone());
+            }
+            if let Some(protocol) = &protocol {
+                simulation.prune_by_protocol(protocol);
+            }
+            if let Some(volume) = &volume {
+                simulation.trim_to_volume(volume);
+            }
+        }
+    }
+
+    let root = simulation.plant().root();
+    println!(
+        "Buds: {} active, {} dormant, {} dead, {} flower",
+        root.count_buds(BudState::Active),
+        root.count_buds(BudState::Dormant),
+        root.count_buds(BudState::Dead),
+        root.count_buds(BudState::Flower)
+    );
+    let leaves = root.collect_leaves();
+    if !leaves.is_empty() {
+        let area: f32 = leaves.iter().map(|leaf| leaf.blade_area()).sum();
+        let light: f32 = leaves.iter().map(|leaf| leaf.last_light_generated).sum();
+        println!(
+            "Leaves: {}, {:.3} m2 blade area, {:.2} of {:.2} light gathered by leaves",
+            leaves.len(),
+            area,
+            light,
+            root.last_light_generated()
+        );
+    }
+    for summary in simulation.season_history().summaries(simulation.pruning_history(), simulation.fruit_history()) {
+        let record = &summary.record;
+        println!(
+            "Year {} {}: {} iterations, metamers {} -> {}, light {:.1}, {} leaves fallen, {} prunes removed {} metamers, {} fruits {:.2} kg harvested",
+            record.year,
+            record.season,
+            record.iterations,
+            record.metamers_start,
+            record.metamers_end,
+            record.light,
+            record.leaves_fallen,
+            summary.prunes,
+            summary.pruned_metamers,
+            summary.harvested,
+            summary.harvested_mass
+        );
+    }
+    let fruit_history = simulation.fruit_history();
+    if let Some(load) = fruit_history.last() {
+        let (harvested, harvested_mass) = fruit_history.total_yield();
+        println!(
+            "Fruit: {} flowers, {} fruits with {:.2} kg on the plant, {} fruits with {:.2} kg harvested",
+            load.flowers, load.fruits, load.mass, harvested, harvested_mass
+        );
+    }
+
+    if let Err(err) = save_branches(&simulation, &settings.output) {
+        eprintln!("Failed to write {}: {}", settings.output, err);
+        process::exit(1);
+    }
+    println!("Tree written to {}", settings.output);
+
+    if let Some(path) = &settings.obj {
+        let options = ObjOptions {
+            merge_metamers: settings.merge_metamers,
+            ..Default::default()
+        };
+        if let Err(err) = export_obj(simulation.plant(), Path::new(path), &options) {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+        println!("OBJ written to {}", path);
+    }
+
+    if let Some(path) = &settings.glb {
+        if let Err(err) = export_glb(simulation.plant(), Path::new(path), ObjOptions::default().sides) {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+        println!("glTF written to {}", path);
+    }
+
+    if let Some(path) = &settings.pruning_history {
+        if let Err(err) = simulation.pruning_history().save_csv(Path::new(path)) {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+        println!("Pruning history written to {}", path);
+    }
+
+    if let Some(path) = &settings.fruit_stats {
+        if let Err(err) = simulation.fruit_history().save_csv(Path::new(path)) {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+        println!("Fruit stats written to {}", path);
+    }
+
+    if let Some(path) = &settings.season_stats {
+        let history = simulation.season_history();
+        if let Err(err) = history.save_csv(Path::new(path), simulation.pruning_history(), simulation.fruit_history()) {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+        println!("Season stats written to {}", path);
+    }
+
+    if let Some(path) = &settings.skeleton {
+        let path = Path::new(path);
+        let result = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => export_skeleton_json(simulation.plant().root(), path),
+            _ => export_skeleton_csv(simulation.plant().root(), path),
+        };
+        if let Err(err) = result {
+            eprintln!("Failed to write {}: {}", path.display(), err);
+            process::exit(1);
+        }
+        println!("Skeleton written to {}", path.display());
+    }
+
+    if let Some(path) = &settings.save_profile {
+        if let Err(err) = simulation.profile().save(Path::new(path)) {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+        println!("Profile written to {}", path);
+    }
+
+    if let Some(path) = &settings.snapshot {
+        if let Err(err) = simulation.snapshot().save(Path::new(path)) {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+        println!("Snapshot written to {}", path);
+    }
+}
+
+// write every branch part of the plant as a line to the output file
+fn save_branches(simulation: &Simulation, path: &str) -> Result<(), String> {
+    let mut file = File::create(path).map_err(|err| err.to_string())?;
+    for data in simulation.plant().collect_branchdata() {
+        pwrite!(&mut file, "branch id{} start{} end{} width{} {}\n",
+            data.id(),
+            data.start_point(),
+            data.end_point(),
+            data.start_width(),
+            data.end_width()
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
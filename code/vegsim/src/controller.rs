@@ -1,10 +1,27 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
-use crate::{
-    tree::{metamer::Metamer, TreeApp, pruning_module::PruneOperation},
+use patutil::Vecf3;
+use vegsim::{
+    export::skeleton::SkeletonError,
+    profile::{ParameterProfile, ProfileError},
+    tree::{
+        metamer::Metamer,
+        pruning_module::{
+            hedge::{PruningVolume, VolumeError},
+            rules::{PruningProtocol, RuleError},
+            PruneOperation,
+        },
+        snapshot::SnapshotError,
+        timeline::TimelineBranch,
+    },
     treeparameter::TreeParameter,
 };
 
+use crate::treeapp::TreeApp;
+
 pub struct Controller {
     treedata: Arc<Mutex<TreeApp>>,
     selected_metamer: Arc<Mutex<Option<Metamer>>>,
@@ -21,6 +38,18 @@ impl Controller {
     pub fn perform_prune(&mut self, prune_index: PruneOperation) {
         self.treedata.lock().unwrap().prune_by_rule(prune_index);
     }
+    pub fn prune_by_rules(&mut self, path: &Path) -> Result<(), RuleError> {
+        let protocol = PruningProtocol::load(path)?;
+        self.treedata.lock().unwrap().prune_by_protocol(&protocol);
+        Ok(())
+    }
+    // the volume is named after the file
+    pub fn trim_to_volume(&mut self, path: &Path) -> Result<(), VolumeError> {
+        let volume = PruningVolume::load(path)?;
+        let name = path.file_stem().and_then(|name| name.to_str()).unwrap_or("volume");
+        self.treedata.lock().unwrap().trim_to_volume(name, &volume);
+        Ok(())
+    }
     pub fn reset_plants(&mut self) {
         self.treedata.lock().unwrap().reset_plants();
     }
@@ -28,6 +57,71 @@ impl Controller {
         self.treedata.lock().unwrap().recalculate_plants();
     }
 
+    pub fn save_snapshot(&self, path: &Path) -> Result<(), SnapshotError> {
+        self.treedata.lock().unwrap().save_snapshot(path)
+    }
+    pub fn load_snapshot(&mut self, path: &Path) -> Result<(), SnapshotError> {
+        self.treedata.lock().unwrap().load_snapshot(path)?;
+        // metamers of the previous plant no longer exist
+        *self.selected_metamer.lock().unwrap() = None;
+        Ok(())
+    }
+
+    pub fn import_skeleton(&mut self, path: &Path) -> Result<(), SkeletonError> {
+        self.treedata.lock().unwrap().import_skeleton(path)?;
+        *self.selected_metamer.lock().unwrap() = None;
+        Ok(())
+    }
+
+    pub fn profile_name(&self) -> String {
+        self.treedata.lock().unwrap().profile_name().to_string()
+    }
+    pub fn save_profile(&self, name: &str, path: &Path) -> Result<(), ProfileError> {
+        self.treedata.lock().unwrap().save_profile(name, path)
+    }
+    pub fn load_profile(&mut self, path: &Path) -> Result<(), ProfileError> {
+        let profile = ParameterProfile::load(path)?;
+        self.treedata.lock().unwrap().apply_profile(profile);
+        *self.selected_metamer.lock().unwrap() = None;
+        Ok(())
+    }
+
+    pub fn undo(&mut self) {
+        self.treedata.lock().unwrap().undo();
+        *self.selected_metamer.lock().unwrap() = None;
+    }
+    pub fn redo(&mut self) {
+        self.treedata.lock().unwrap().redo();
+        *self.selected_metamer.lock().unwrap() = None;
+    }
+    pub fn scrub(&mut self, position: usize) {
+        self.treedata.lock().unwrap().scrub(position);
+        *self.selected_metamer.lock().unwrap() = None;
+    }
+    pub fn switch_branch(&mut self, branch: &TimelineBranch) {
+        self.treedata.lock().unwrap().switch_branch(branch);
+        *self.selected_metamer.lock().unwrap() = None;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.treedata.lock().unwrap().seed()
+    }
+    pub fn set_seed(&mut self, seed: u64) {
+        self.treedata.lock().unwrap().set_seed(seed);
+    }
+
+    pub fn export_obj(&self, path: &Path) -> std::io::Result<()> {
+        self.treedata.lock().unwrap().export_obj(path)
+    }
+
+    pub fn export_pruning_history(&self, path: &Path) -> std::io::Result<()> {
+        self.treedata.lock().unwrap().pruning_history().save_csv(path)
+    }
+
+    pub fn export_glb(&self, path: &Path) -> std::io::Result<()> {
+        self.treedata.lock().unwrap().export_glb(path)
+    }
+
     pub fn selected_metamer(&self) -> &Mutex<Option<Metamer>> {
         self.selected_metamer.as_ref()
     }
@@ -36,6 +130,10 @@ impl Controller {
         self.treedata.lock().unwrap().prune_id(id);
     }
 
+    pub fn cut_at_ray(&mut self, id: u32, origin: Vecf3, dir: Vecf3) {
+        self.treedata.lock().unwrap().cut_at_ray(id, origin, dir);
+    }
+
     pub fn perform_growth_iteration(&self) {
         self.treedata.lock().unwrap().perform_growth_iteration();
     }
@@ -57,6 +155,10 @@ impl Controller {
         self.treedata.lock().unwrap().update_tree_param(param);
     }
 
+    pub fn reset_tree_params(&self) {
+        self.treedata.lock().unwrap().reset_tree_params();
+    }
+
     pub fn get_tree_param(&self, param: TreeParameter) -> TreeParameter{
         self.treedata.lock().unwrap().get_tree_param(param)
     }
@@ -0,0 +1,321 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    tree::{branchdata::BranchData, budfate::BudState, metamer::Metamer, plant::Plant, support_pole::SupportPole},
+    util::json::JsonValue,
+};
+
+use super::tube::{PartGroup, Tube};
+
+const GLB_MAGIC: u32 = 0x46546C67;
+const GLB_VERSION: u32 = 2;
+const CHUNK_JSON: u32 = 0x4E4F534A;
+const CHUNK_BIN: u32 = 0x004E4942;
+
+const COMPONENT_FLOAT: u32 = 5126;
+const COMPONENT_UNSIGNED_INT: u32 = 5125;
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/**
+ * Write the plant as binary glTF, every metamer is a node with its simulation state in the extras
+ */
+pub fn export_glb(plant: &Plant, path: &Path, sides: u32) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_glb(plant.root(), sides, &mut out)?;
+    out.flush()
+}
+
+// write the metamer and everything growing from it
+pub fn write_glb(root: &Metamer, sides: u32, out: &mut dyn Write) -> io::Result<()> {
+    let mut builder = GlbBuilder::new(sides);
+    let root = builder.add_metamer(root, 0);
+    let (json, bin) = builder.finish(root);
+
+    // chunks are padded to 4 bytes, json with spaces and binary data with zeros
+    let mut json = json.to_string().into_bytes();
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+    let mut bin = bin;
+    while bin.len() % 4 != 0 {
+        bin.push(0);
+    }
+
+    let length = 12 + 8 + json.len() + 8 + bin.len();
+    for value in [GLB_MAGIC, GLB_VERSION, length as u32] {
+        out.write_all(&value.to_le_bytes())?;
+    }
+    out.write_all(&(json.len() as u32).to_le_bytes())?;
+    out.write_all(&CHUNK_JSON.to_le_bytes())?;
+    out.write_all(&json)?;
+    out.write_all(&(bin.len() as u32).to_le_bytes())?;
+    out.write_all(&CHUNK_BIN.to_le_bytes())?;
+    out.write_all(&bin)
+}
+
+struct GlbBuilder {
+    sides: u32,
+    buffer: Vec<u8>,
+    buffer_views: Vec<JsonValue>,
+    accessors: Vec<JsonValue>,
+    meshes: Vec<JsonValue>,
+    nodes: Vec<JsonValue>,
+    poles: HashSet<u32>,
+}
+
+impl GlbBuilder {
+    fn new(sides: u32) -> Self {
+        Self {
+            sides,
+            buffer: vec![],
+            buffer_views: vec![],
+            accessors: vec![],
+            meshes: vec![],
+            nodes: vec![],
+            poles: HashSet::new(),
+        }
+    }
+
+    // @returns the json document and binary buffer with the given root node
+    fn finish(self, root: usize) -> (JsonValue, Vec<u8>) {
+        let materials: Vec<JsonValue> = PartGroup::ALL
+            .iter()
+            .map(|group| {
+                let color = group.color().to_float();
+                JsonValue::object(vec![
+                    ("name", group.name().into()),
+                    (
+                        "pbrMetallicRoughness",
+                        JsonValue::object(vec![
+                            ("baseColorFactor", color.to_vec().into()),
+                            ("metallicFactor", 0f32.into()),
+                            ("roughnessFactor", 1f32.into()),
+                        ]),
+                    ),
+                ])
+            })
+            .collect();
+
+        let json = JsonValue::object(vec![
+            (
+                "asset",
+                JsonValue::object(vec![("version", "2.0".into()), ("generator", "vegsim".into())]),
+            ),
+            ("scene", 0u32.into()),
+            (
+                "scenes",
+                JsonValue::Array(vec![JsonValue::object(vec![("nodes", vec![root].into())])]),
+            ),
+            ("nodes", JsonValue::Array(self.nodes)),
+            ("meshes", JsonValue::Array(self.meshes)),
+            ("materials", JsonValue::Array(materials)),
+            ("accessors", JsonValue::Array(self.accessors)),
+            ("bufferViews", JsonValue::Array(self.buffer_views)),
+            (
+                "buffers",
+                JsonValue::Array(vec![JsonValue::object(vec![("byteLength", self.buffer.len().into())])]),
+            ),
+        ]);
+        (json, self.buffer)
+    }
+
+    // add a metamer node with nodes for everything growing from it
+    // @returns the index of the node
+    fn add_metamer(&mut self, metamer: &Metamer, branch_order: u32) -> usize {
+        let mut children = vec![];
+
+        if let Some(terminal) = metamer.terminal_metamer() {
+            children.push(self.add_metamer(terminal, branch_order));
+        } else {
+            children.push(self.add_bud(
+                &metamer.terminal_bud_data,
+                "terminal",
+                metamer.terminal_bud_damage,
+                metamer.terminal_bud_fate.state(),
+            ));
+        }
+
+        if let Some(auxillary) = metamer.auxillary_metamer() {
+            children.push(self.add_metamer(auxillary, branch_order + 1));
+        } else {
+            children.push(self.add_bud(
+                &metamer.aux_bud_data,
+                "auxillary",
+                metamer.auxillary_bud_damage,
+                metamer.aux_bud_fate.state(),
+            ));
+            children.extend(self.add_pole(metamer.aux_support_pole.as_ref()));
+        }
+        for bud in metamer.extra_buds() {
+            children.push(match bud.metamer() {
+                Some(auxillary) => self.add_metamer(auxillary, branch_order + 1),
+                None => self.add_bud(&bud.bud_data, "auxillary", bud.damage, bud.fate.state()),
+            });
+        }
+        children.extend(self.add_pole(metamer.support_pole.as_ref()));
+
+        let group = if branch_order == 0 {
+            PartGroup::Trunk
+        } else {
+            PartGroup::Branches
+        };
+        let extras = JsonValue::object(vec![
+            ("id", metamer.id().into()),
+            ("type", "metamer".into()),
+            ("branch_order", branch_order.into()),
+            ("light", metamer.last_light_generated().into()),
+            ("terminal_light", metamer.last_terminal_light_generated.into()),
+            ("auxillary_light", metamer.last_aux_light_generated.into()),
+            ("terminal_resources", metamer.last_terminal_resources.into()),
+            ("auxillary_resources", metamer.last_aux_resources.into()),
+            ("terminal_bud_damage", metamer.terminal_bud_damage.into()),
+            ("auxillary_bud_damage", metamer.auxillary_bud_damage.into()),
+            ("auxin", metamer.auxin.into()),
+        ]);
+        self.add_node(format!("metamer {}", metamer.id()), metamer, group, extras, children)
+    }
+
+    fn add_bud(&mut self, data: &BranchData, position: &str, damage: f32, state: BudState) -> usize {
+        let extras = JsonValue::object(vec![
+            ("id", data.id().into()),
+            ("type", format!("{} bud", position).into()),
+            ("damage", damage.into()),
+            ("state", state.to_string().into()),
+        ]);
+        self.add_node(format!("{} bud {}", position, data.id()), data, PartGroup::Buds, extras, vec![])
+    }
+
+    // poles are shared by multiple metamers, only add each pole once
+    fn add_pole(&mut self, pole: Option<&SupportPole>) -> Option<usize> {
+        let pole = pole.filter(|pole| pole.visible())?;
+        let model = pole.model();
+        if !self.poles.insert(model.id()) {
+            return None;
+        }
+        let extras = JsonValue::object(vec![("id", model.id().into()), ("type", "pole".into())]);
+        Some(self.add_node(format!("pole {}", model.id()), model, PartGroup::Poles, extras, vec![]))
+    }
+
+    fn add_node(
+        &mut self,
+        name: String,
+        data: &BranchData,
+        group: PartGroup,
+        extras: JsonValue,
+        children: Vec<usize>,
+    ) -> usize {
+        let mut fields = vec![("name", name.into())];
+        if let Some(mesh) = self.add_mesh(&Tube::from_branchdata(data, group)) {
+            fields.push(("mesh", mesh.into()));
+        }
+        if !children.is_empty() {
+            fields.push(("children", children.into()));
+        }
+        fields.push(("extras", extras));
+
+        self.nodes.push(JsonValue::object(fields));
+        self.nodes.len() - 1
+    }
+
+    // @returns the mesh index, none if the tube has no geometry
+    fn add_mesh(&mut self, tube: &Tube) -> Option<usize> {
+        let (positions, normals, indices) = tube.mesh(self.sides).single_index();
+        if indices.is_empty() {
+            return None;
+        }
+
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for p in &positions {
+            for (i, v) in [p.x, p.y, p.z].into_iter().enumerate() {
+                min[i] = min[i].min(v);
+                max[i] = max[i].max(v);
+            }
+        }
+
+        let position_data: Vec<f32> = positions.iter().flat_map(|p| [p.x, p.y, p.z]).collect();
+        let normal_data: Vec<f32> = normals.iter().flat_map(|n| [n.x, n.y, n.z]).collect();
+
+        let position = self.add_accessor(
+            &Self::f32_bytes(&position_data),
+            TARGET_ARRAY_BUFFER,
+            COMPONENT_FLOAT,
+            positions.len(),
+            "VEC3",
+            Some((min, max)),
+        );
+        let normal = self.add_accessor(
+            &Self::f32_bytes(&normal_data),
+            TARGET_ARRAY_BUFFER,
+            COMPONENT_FLOAT,
+            normals.len(),
+            "VEC3",
+            None,
+        );
+        let index_bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let index = self.add_accessor(
+            &index_bytes,
+            TARGET_ELEMENT_ARRAY_BUFFER,
+            COMPONENT_UNSIGNED_INT,
+            indices.len(),
+            "SCALAR",
+            None,
+        );
+
+        let material = PartGroup::ALL.iter().position(|group| *group == tube.group).unwrap();
+        let primitive = JsonValue::object(vec![
+            (
+                "attributes",
+                JsonValue::object(vec![("POSITION", position.into()), ("NORMAL", normal.into())]),
+            ),
+            ("indices", index.into()),
+            ("material", material.into()),
+        ]);
+        self.meshes
+            .push(JsonValue::object(vec![("primitives", JsonValue::Array(vec![primitive]))]));
+        Some(self.meshes.len() - 1)
+    }
+
+    // store the data in its own buffer view
+    // @returns the index of the accessor
+    fn add_accessor(
+        &mut self,
+        data: &[u8],
+        target: u32,
+        component_type: u32,
+        count: usize,
+        accessor_type: &str,
+        bounds: Option<([f32; 3], [f32; 3])>,
+    ) -> usize {
+        self.buffer_views.push(JsonValue::object(vec![
+            ("buffer", 0u32.into()),
+            ("byteOffset", self.buffer.len().into()),
+            ("byteLength", data.len().into()),
+            ("target", target.into()),
+        ]));
+        self.buffer.extend_from_slice(data);
+
+        let mut fields = vec![
+            ("bufferView", (self.buffer_views.len() - 1).into()),
+            ("componentType", component_type.into()),
+            ("count", count.into()),
+            ("type", accessor_type.into()),
+        ];
+        if let Some((min, max)) = bounds {
+            fields.push(("min", min.to_vec().into()));
+            fields.push(("max", max.to_vec().into()));
+        }
+        self.accessors.push(JsonValue::object(fields));
+        self.accessors.len() - 1
+    }
+
+    fn f32_bytes(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+}
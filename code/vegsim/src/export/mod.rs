@@ -0,0 +1,5 @@
+pub mod gltf;
+pub mod obj;
+pub mod skeleton;
+mod tests;
+pub mod tube;
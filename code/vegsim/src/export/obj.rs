@@ -0,0 +1,99 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::tree::plant::Plant;
+
+use super::tube::{collect_tubes, PartGroup, Tube};
+
+pub struct ObjOptions {
+    // join consecutive metamers of a branch into one smooth tube
+    pub merge_metamers: bool,
+    // amount of sides around every tube
+    pub sides: u32,
+}
+
+impl Default for ObjOptions {
+    fn default() -> Self {
+        Self {
+            merge_metamers: true,
+            sides: 8,
+        }
+    }
+}
+
+/**
+ * Write the plant to an OBJ file, the materials are written next to it with the mtl extension
+ */
+pub fn export_obj(plant: &Plant, path: &Path, options: &ObjOptions) -> io::Result<()> {
+    let mtl_path = path.with_extension("mtl");
+    let mtl_name = mtl_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("tree.mtl");
+
+    let tubes = collect_tubes(plant, options.merge_metamers);
+
+    let mut obj = BufWriter::new(File::create(path)?);
+    write_obj(&tubes, options.sides, mtl_name, &mut obj)?;
+    obj.flush()?;
+
+    let mut mtl = BufWriter::new(File::create(&mtl_path)?);
+    write_mtl(&mut mtl)?;
+    mtl.flush()
+}
+
+// write the tubes with one group and material per part group
+pub fn write_obj(tubes: &[Tube], sides: u32, mtl_name: &str, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "# vegsim tree")?;
+    writeln!(out, "mtllib {}", mtl_name)?;
+
+    // obj indices start at 1 and count over the whole file
+    let mut position_offset = 1;
+    let mut normal_offset = 1;
+
+    for group in PartGroup::ALL {
+        let mut group_tubes = tubes.iter().filter(|tube| tube.group == group).peekable();
+        if group_tubes.peek().is_none() {
+            continue;
+        }
+        writeln!(out, "g {}", group.name())?;
+        writeln!(out, "usemtl {}", group.name())?;
+
+        for tube in group_tubes {
+            let mesh = tube.mesh(sides);
+            for p in &mesh.positions {
+                writeln!(out, "v {} {} {}", p.x, p.y, p.z)?;
+            }
+            for n in &mesh.normals {
+                writeln!(out, "vn {} {} {}", n.x, n.y, n.z)?;
+            }
+            for face in &mesh.faces {
+                write!(out, "f")?;
+                for (position, normal) in face {
+                    write!(out, " {}//{}", position + position_offset, normal + normal_offset)?;
+                }
+                writeln!(out)?;
+            }
+            position_offset += mesh.positions.len() as u32;
+            normal_offset += mesh.normals.len() as u32;
+        }
+    }
+    Ok(())
+}
+
+pub fn write_mtl(out: &mut dyn Write) -> io::Result<()> {
+    for group in PartGroup::ALL {
+        let color = group.color().to_float();
+        writeln!(out, "newmtl {}", group.name())?;
+        writeln!(out, "Kd {} {} {}", color[0], color[1], color[2])?;
+        writeln!(out, "Ka 0 0 0")?;
+        writeln!(out, "Ks 0 0 0")?;
+        writeln!(out, "d 1")?;
+        writeln!(out, "illum 1")?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,406 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use patutil::Vecf3;
+
+use crate::{
+    tree::{
+        metamer::{IdCounter, Metamer},
+        plantgenetics::PlantGenetics,
+    },
+    util::{json::JsonValue, radius_to_width, rot_vec_around_axis, width_to_radius},
+};
+
+const CSV_HEADER: &str = "id,parent_id,position,start_x,start_y,start_z,end_x,end_y,end_z,start_radius,end_radius,branch_order,depth";
+
+// angle between the axillary buds of consecutive imported metamers
+const IMPORT_PHYLLOTAXIS_ANGLE: f32 = 137.5;
+
+#[derive(Debug)]
+pub enum SkeletonError {
+    Io(io::Error),
+    Parse(String),
+    Structure(String),
+}
+
+impl std::error::Error for SkeletonError {}
+
+impl Display for SkeletonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkeletonError::Io(err) => write!(f, "could not access skeleton file: {}", err),
+            SkeletonError::Parse(err) => write!(f, "invalid skeleton file: {}", err),
+            SkeletonError::Structure(err) => write!(f, "invalid skeleton: {}", err),
+        }
+    }
+}
+
+impl From<io::Error> for SkeletonError {
+    fn from(err: io::Error) -> Self {
+        SkeletonError::Io(err)
+    }
+}
+
+/**
+ * Position of a metamer on its parent
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkeletonPosition {
+    Root,
+    Terminal,
+    Axillary,
+}
+
+impl Display for SkeletonPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkeletonPosition::Root => write!(f, "root"),
+            SkeletonPosition::Terminal => write!(f, "terminal"),
+            SkeletonPosition::Axillary => write!(f, "axillary"),
+        }
+    }
+}
+
+impl FromStr for SkeletonPosition {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "root" => Ok(SkeletonPosition::Root),
+            "terminal" => Ok(SkeletonPosition::Terminal),
+            "axillary" => Ok(SkeletonPosition::Axillary),
+            _ => Err(()),
+        }
+    }
+}
+
+/**
+ * One cylinder of the skeleton, radii are in the same unit as the points
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkeletonNode {
+    pub id: u32,
+    pub parent_id: Option<u32>,
+    pub position: SkeletonPosition,
+    pub start: Vecf3,
+    pub end: Vecf3,
+    pub start_radius: f32,
+    pub end_radius: f32,
+    pub branch_order: u32,
+    pub depth: u32,
+}
+
+// @returns a node for every metamer, parents come before their children
+pub fn collect_skeleton(root: &Metamer) -> Vec<SkeletonNode> {
+    let mut nodes = vec![];
+    let mut stack = vec![(root, None, SkeletonPosition::Root, 0, 0)];
+    while let Some((metamer, parent_id, position, branch_order, depth)) = stack.pop() {
+        nodes.push(SkeletonNode {
+            id: metamer.id(),
+            parent_id,
+            position,
+            start: metamer.start_point(),
+            end: metamer.end_point(),
+            start_radius: width_to_radius(metamer.start_width()),
+            end_radius: width_to_radius(metamer.end_width()),
+            branch_order,
+            depth,
+        });
+
+        // pushed in reverse so the terminal metamer is visited first
+        for auxillary in metamer.extra_buds().iter().rev().filter_map(|bud| bud.metamer()) {
+            stack.push((auxillary, Some(metamer.id()), SkeletonPosition::Axillary, branch_order + 1, depth + 1));
+        }
+        if let Some(auxillary) = metamer.auxillary_metamer() {
+            stack.push((auxillary, Some(metamer.id()), SkeletonPosition::Axillary, branch_order + 1, depth + 1));
+        }
+        if let Some(terminal) = metamer.terminal_metamer() {
+            stack.push((terminal, Some(metamer.id()), SkeletonPosition::Terminal, branch_order, depth + 1));
+        }
+    }
+    nodes
+}
+
+pub fn export_skeleton_csv(root: &Metamer, path: &Path) -> Result<(), SkeletonError> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_csv(&collect_skeleton(root), &mut out)?;
+    Ok(out.flush()?)
+}
+
+pub fn export_skeleton_json(root: &Metamer, path: &Path) -> Result<(), SkeletonError> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_json(&collect_skeleton(root), &mut out)?;
+    Ok(out.flush()?)
+}
+
+// read a csv or json skeleton depending on the file extension
+pub fn import_skeleton(path: &Path) -> Result<Vec<SkeletonNode>, SkeletonError> {
+    let data = fs::read_to_string(path)?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => read_json(&data),
+        _ => read_csv(&data),
+    }
+}
+
+pub fn write_csv(nodes: &[SkeletonNode], out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{}", CSV_HEADER)?;
+    for node in nodes {
+        let parent_id = node.parent_id.map_or(String::new(), |id| id.to_string());
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            node.id,
+            parent_id,
+            node.position,
+            node.start.x,
+            node.start.y,
+            node.start.z,
+            node.end.x,
+            node.end.y,
+            node.end.z,
+            node.start_radius,
+            node.end_radius,
+            node.branch_order,
+            node.depth
+        )?;
+    }
+    Ok(())
+}
+
+pub fn read_csv(data: &str) -> Result<Vec<SkeletonNode>, SkeletonError> {
+    let mut nodes = vec![];
+    for (line_index, line) in data.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("id,") {
+            continue;
+        }
+        let error = |message: &str| SkeletonError::Parse(format!("line {}: {}", line_index + 1, message));
+
+        let columns: Vec<&str> = line.split(',').map(|column| column.trim()).collect();
+        if columns.len() != 13 {
+            return Err(error(&format!("expected 13 columns, found {}", columns.len())));
+        }
+        let float = |i: usize| columns[i].parse::<f32>().map_err(|_| error(&format!("invalid number '{}'", columns[i])));
+        let int = |i: usize| columns[i].parse::<u32>().map_err(|_| error(&format!("invalid integer '{}'", columns[i])));
+
+        nodes.push(SkeletonNode {
+            id: int(0)?,
+            parent_id: if columns[1].is_empty() { None } else { Some(int(1)?) },
+            position: columns[2]
+                .parse()
+                .map_err(|_| error(&format!("invalid position '{}'", columns[2])))?,
+            start: Vecf3::new(float(3)?, float(4)?, float(5)?),
+            end: Vecf3::new(float(6)?, float(7)?, float(8)?),
+            start_radius: float(9)?,
+            end_radius: float(10)?,
+            branch_order: int(11)?,
+            depth: int(12)?,
+        });
+    }
+    Ok(nodes)
+}
+
+pub fn write_json(nodes: &[SkeletonNode], out: &mut dyn Write) -> io::Result<()> {
+    let point = |p: Vecf3| JsonValue::from(vec![p.x, p.y, p.z]);
+    let cylinders: Vec<JsonValue> = nodes
+        .iter()
+        .map(|node| {
+            JsonValue::object(vec![
+                ("id", node.id.into()),
+                ("parent_id", node.parent_id.map_or(JsonValue::Null, |id| id.into())),
+                ("position", node.position.to_string().into()),
+                ("start", point(node.start)),
+                ("end", point(node.end)),
+                ("start_radius", node.start_radius.into()),
+                ("end_radius", node.end_radius.into()),
+                ("branch_order", node.branch_order.into()),
+                ("depth", node.depth.into()),
+            ])
+        })
+        .collect();
+    let json = JsonValue::object(vec![("cylinders", JsonValue::Array(cylinders))]);
+    writeln!(out, "{}", json)
+}
+
+pub fn read_json(data: &str) -> Result<Vec<SkeletonNode>, SkeletonError> {
+    let json: JsonValue = data.parse().map_err(SkeletonError::Parse)?;
+    let cylinders = json
+        .get("cylinders")
+        .and_then(|cylinders| cylinders.as_array())
+        .ok_or_else(|| SkeletonError::Parse("missing cylinders array".to_string()))?;
+
+    let mut nodes = vec![];
+    for (index, cylinder) in cylinders.iter().enumerate() {
+        let error = |key: &str| SkeletonError::Parse(format!("cylinder {}: invalid or missing {}", index, key));
+        let number = |key: &str| cylinder.get(key).and_then(|v| v.as_f64()).ok_or_else(|| error(key));
+        let point = |key: &str| {
+            let values: Vec<f64> = cylinder
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|values| values.iter().filter_map(|v| v.as_f64()).collect())
+                .unwrap_or_default();
+            if values.len() != 3 {
+                return Err(error(key));
+            }
+            Ok(Vecf3::new(values[0] as f32, values[1] as f32, values[2] as f32))
+        };
+
+        nodes.push(SkeletonNode {
+            id: number("id")? as u32,
+            parent_id: match cylinder.get("parent_id") {
+                None | Some(JsonValue::Null) => None,
+                Some(_) => Some(number("parent_id")? as u32),
+            },
+            position: cylinder
+                .get("position")
+                .and_then(|v| v.as_str())
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| error("position"))?,
+            start: point("start")?,
+            end: point("end")?,
+            start_radius: number("start_radius")? as f32,
+            end_radius: number("end_radius")? as f32,
+            branch_order: number("branch_order")? as u32,
+            depth: number("depth")? as u32,
+        });
+    }
+    Ok(nodes)
+}
+
+/**
+ * Build a metamer tree from skeleton nodes, buds are placed at the end of every metamer
+ * @param ids counter of the plant the tree is imported into, the buds get their ids from it
+ */
+pub fn skeleton_to_metamer(
+    nodes: &[SkeletonNode],
+    genetics: Arc<Mutex<PlantGenetics>>,
+    ids: &mut IdCounter,
+) -> Result<Metamer, SkeletonError> {
+    let structure_error = |message: String| Err(SkeletonError::Structure(message));
+
+    let mut indices = HashMap::new();
+    for (index, node) in nodes.iter().enumerate() {
+        if indices.insert(node.id, index).is_some() {
+            return structure_error(format!("duplicate id {}", node.id));
+        }
+    }
+
+    // find the terminal and axillary children of every node, a node can have several axillary children
+    let mut root = None;
+    let mut children: Vec<SkeletonChildren> = vec![SkeletonChildren::default(); nodes.len()];
+    for (index, node) in nodes.iter().enumerate() {
+        let parent_id = match node.parent_id {
+            Some(parent_id) => parent_id,
+            None => {
+                if root.replace(index).is_some() {
+                    return structure_error("more than one root".to_string());
+                }
+                continue;
+            }
+        };
+        let parent = match indices.get(&parent_id) {
+            Some(parent) => *parent,
+            None => return structure_error(format!("unknown parent {} of {}", parent_id, node.id)),
+        };
+        match node.position {
+            SkeletonPosition::Terminal => {
+                if children[parent].terminal.replace(index).is_some() {
+                    return structure_error(format!("{} has more than one {} child", parent_id, node.position));
+                }
+            }
+            SkeletonPosition::Axillary => children[parent].axillary.push(index),
+            SkeletonPosition::Root => return structure_error(format!("root {} has a parent", node.id)),
+        }
+    }
+    let root = match root {
+        Some(root) => root,
+        None => return structure_error("missing root".to_string()),
+    };
+
+    // nodes in a parent loop can not be reached from the root
+    let mut reached = 0;
+    let mut stack = vec![root];
+    while let Some(index) = stack.pop() {
+        reached += 1;
+        stack.extend(children[index].terminal.iter().chain(children[index].axillary.iter()));
+    }
+    if reached != nodes.len() {
+        return structure_error(format!("{} nodes are not connected to the root", nodes.len() - reached));
+    }
+
+    // buds get new ids, keep them clear of the imported ids
+    let max_id = nodes.iter().map(|node| node.id).max().unwrap_or(0);
+    if ids.next_id() <= max_id {
+        ids.set_next_id(max_id + 1);
+    }
+
+    Ok(build_metamer(nodes, &children, root, 0, &genetics, ids))
+}
+
+#[derive(Debug, Clone, Default)]
+struct SkeletonChildren {
+    terminal: Option<usize>,
+    axillary: Vec<usize>,
+}
+
+fn build_metamer(
+    nodes: &[SkeletonNode],
+    children: &[SkeletonChildren],
+    index: usize,
+    depth: u32,
+    genetics: &Arc<Mutex<PlantGenetics>>,
+    ids: &mut IdCounter,
+) -> Metamer {
+    let node = &nodes[index];
+    let terminal = children[index].terminal;
+    let axillary = children[index].axillary.first().copied();
+
+    // axillary bud points to the axillary branch, otherwise buds spread around the axis
+    let dir = (node.end - node.start).norm();
+    let auxillary_direction = match axillary {
+        Some(axillary) => (nodes[axillary].end - nodes[axillary].start).norm(),
+        None => {
+            let angle = genetics.lock().unwrap().axillary_perturbation_angle();
+            let mut side = dir.cross(Vecf3::new(1., 0., 0.));
+            if side.length() < 1e-6 {
+                side = dir.cross(Vecf3::new(0., 0., 1.));
+            }
+            let side = rot_vec_around_axis(
+                &side.norm(),
+                &dir,
+                patutil::radians(IMPORT_PHYLLOTAXIS_ANGLE * depth as f32),
+            );
+            (dir * angle.cos() + side * angle.sin()).norm()
+        }
+    };
+
+    let mut metamer = Metamer::with_auxillary_direction(
+        node.start,
+        node.end,
+        genetics.clone(),
+        node.id,
+        None,
+        auxillary_direction,
+        ids,
+    );
+    metamer.set_start_width(radius_to_width(node.start_radius));
+    metamer.set_end_width(radius_to_width(node.end_radius));
+
+    metamer.set_terminal_metamer(terminal.map(|terminal| build_metamer(nodes, children, terminal, depth + 1, genetics, ids)));
+    metamer.set_auxillary_metamer(axillary.map(|axillary| build_metamer(nodes, children, axillary, depth + 1, genetics, ids)));
+
+    // the other axillary children of the node become extra buds
+    for &axillary in children[index].axillary.iter().skip(1) {
+        let direction = (nodes[axillary].end - nodes[axillary].start).norm();
+        let shoot = build_metamer(nodes, children, axillary, depth + 1, genetics, ids);
+        metamer.add_extra_bud(direction, ids).set_metamer(Some(shoot));
+    }
+    metamer
+}
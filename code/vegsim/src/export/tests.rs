@@ -0,0 +1,184 @@
+#![cfg(test)]
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use patutil::Vecf3;
+
+use crate::tree::{
+    metamer::{IdCounter, Metamer}, plantgenetics::PlantGenetics,
+};
+
+use super::{
+    gltf::write_glb,
+    obj::{write_mtl, write_obj},
+    skeleton::{
+        collect_skeleton, read_csv, read_json, skeleton_to_metamer, write_csv, write_json, SkeletonError,
+        SkeletonPosition,
+    },
+    tube::{PartGroup, Tube, TubeRing},
+};
+
+fn ring(y: f32, radius: f32) -> TubeRing {
+    TubeRing {
+        center: Vecf3::new(0., y, 0.),
+        radius,
+    }
+}
+
+fn test_tubes() -> Vec<Tube> {
+    vec![
+        Tube {
+            group: PartGroup::Trunk,
+            ids: vec![1, 2],
+            rings: vec![ring(0., 1.), ring(1., 0.8), ring(2., 0.5)],
+        },
+        Tube {
+            group: PartGroup::Buds,
+            ids: vec![3],
+            rings: vec![ring(2., 0.01), ring(2.05, 0.01)],
+        },
+    ]
+}
+
+#[test]
+fn test_tube_mesh_closed() {
+    let sides = 6;
+    let mesh = test_tubes()[0].mesh(sides);
+    assert_eq!(mesh.positions.len(), 3 * 6 + 2);
+
+    // every edge of a closed mesh is used by exactly two faces
+    let mut edges = HashMap::new();
+    for face in &mesh.faces {
+        for i in 0..3 {
+            let a = face[i].0;
+            let b = face[(i + 1) % 3].0;
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    assert!(edges.values().all(|count| *count == 2));
+}
+
+// directory of its own for every test and test process, so test runs at the same time do not share files
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vegsim_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_obj_round_trip() {
+    let dir = test_dir("obj_round_trip");
+    let obj_path = dir.join("vegsim_export_test.obj");
+    let mtl_path = dir.join("vegsim_export_test.mtl");
+
+    let mut obj = vec![];
+    write_obj(&test_tubes(), 8, "vegsim_export_test.mtl", &mut obj).unwrap();
+    std::fs::write(&obj_path, obj).unwrap();
+    let mut mtl = vec![];
+    write_mtl(&mut mtl).unwrap();
+    std::fs::write(&mtl_path, mtl).unwrap();
+
+    let options = tobj::LoadOptions {
+        triangulate: true,
+        ..Default::default()
+    };
+    let (models, materials) = tobj::load_obj(&obj_path, &options).unwrap();
+    let materials = materials.unwrap();
+
+    let names: Vec<&str> = models.iter().map(|model| model.name.as_str()).collect();
+    assert_eq!(names, vec!["trunk", "buds"]);
+    assert_eq!(materials.len(), 4);
+
+    let trunk = &models[0].mesh;
+    assert_eq!(trunk.positions.len() / 3, 3 * 8 + 2);
+    assert_eq!(trunk.indices.len() / 3, 2 * 2 * 8 + 2 * 8);
+    assert_eq!(materials[trunk.material_id.unwrap()].name, "trunk");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// two metamers on top of each other, built without random numbers
+fn test_metamers() -> Metamer {
+    let genetics = Arc::new(Mutex::new(PlantGenetics::new()));
+    let mut ids = IdCounter::new();
+    ids.set_next_id(3);
+    let mut metamer = |id: u32, y: f32| {
+        let mut metamer = Metamer::with_auxillary_direction(
+            Vecf3::new(0., y, 0.),
+            Vecf3::new(0., y + 1., 0.),
+            genetics.clone(),
+            id,
+            None,
+            Vecf3::new(1., 0., 0.),
+            &mut ids,
+        );
+        metamer.set_start_width(0.01);
+        metamer.set_end_width(0.008);
+        metamer.last_light_generated = 0.5;
+        metamer.auxillary_bud_damage = 0.5;
+        metamer
+    };
+    let mut root = metamer(1, 0.);
+    root.set_terminal_metamer(Some(metamer(2, 1.)));
+    root
+}
+
+#[test]
+fn test_glb_round_trip() {
+    let metamers = test_metamers();
+    let mut glb = vec![];
+    write_glb(&metamers, 6, &mut glb).unwrap();
+    assert_eq!(glb.len() % 4, 0);
+
+    let (document, buffers, _) = gltf::import_slice(&glb).unwrap();
+    assert_eq!(buffers.len(), 1);
+
+    // both metamers, one terminal bud and two auxillary buds
+    assert_eq!(document.nodes().count(), 5);
+
+    let root = document.scenes().next().unwrap().nodes().next().unwrap();
+    assert_eq!(root.name(), Some("metamer 1"));
+    let extras = root.extras().as_ref().unwrap().get();
+    assert!(extras.contains("\"id\":1"));
+    assert!(extras.contains("\"branch_order\":0"));
+    assert!(extras.contains("\"auxillary_bud_damage\":0.5"));
+
+    let names: Vec<&str> = root.children().filter_map(|node| node.name()).collect();
+    let aux_bud = format!("auxillary bud {}", metamers.aux_bud_data.id());
+    assert_eq!(names, vec!["metamer 2", aux_bud.as_str()]);
+}
+
+#[test]
+fn test_skeleton_round_trip() {
+    let nodes = collect_skeleton(&test_metamers());
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[1].parent_id, Some(1));
+    assert_eq!(nodes[1].position, SkeletonPosition::Terminal);
+    assert_eq!(nodes[1].depth, 1);
+
+    let mut csv = vec![];
+    write_csv(&nodes, &mut csv).unwrap();
+    assert_eq!(read_csv(&String::from_utf8(csv).unwrap()).unwrap(), nodes);
+
+    let mut json = vec![];
+    write_json(&nodes, &mut json).unwrap();
+    assert_eq!(read_json(&String::from_utf8(json).unwrap()).unwrap(), nodes);
+
+    // importing gives the same skeleton back
+    let genetics = Arc::new(Mutex::new(PlantGenetics::new()));
+    let mut ids = IdCounter::new();
+    let metamer = skeleton_to_metamer(&nodes, genetics.clone(), &mut ids).unwrap();
+    assert!(ids.next_id() > nodes.iter().map(|node| node.id).max().unwrap());
+    assert_eq!(collect_skeleton(&metamer), nodes);
+
+    let mut orphan = nodes.clone();
+    orphan[1].parent_id = Some(7);
+    assert!(matches!(
+        skeleton_to_metamer(&orphan, genetics, &mut ids),
+        Err(SkeletonError::Structure(_))
+    ));
+}
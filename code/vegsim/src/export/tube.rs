@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+
+use patutil::{Color, Vecf3};
+
+use crate::{
+    tree::{branchdata::BranchData, metamer::Metamer, plant::Plant, support_pole::SupportPole},
+    util::width_to_radius,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartGroup {
+    Trunk,
+    Branches,
+    Buds,
+    Poles,
+}
+
+impl PartGroup {
+    pub const ALL: [PartGroup; 4] = [
+        PartGroup::Trunk,
+        PartGroup::Branches,
+        PartGroup::Buds,
+        PartGroup::Poles,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PartGroup::Trunk => "trunk",
+            PartGroup::Branches => "branches",
+            PartGroup::Buds => "buds",
+            PartGroup::Poles => "poles",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PartGroup::Trunk | PartGroup::Branches => Color::new(151, 111, 51, 255),
+            PartGroup::Buds => Color::new(255, 0, 0, 255),
+            PartGroup::Poles => Color::new(0, 255, 0, 255),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TubeRing {
+    pub center: Vecf3,
+    pub radius: f32,
+}
+
+/**
+ * Generalized cylinder through a list of rings, closed at both ends
+ */
+#[derive(Debug, Clone)]
+pub struct Tube {
+    pub group: PartGroup,
+    // ids of the parts the tube was made from
+    pub ids: Vec<u32>,
+    pub rings: Vec<TubeRing>,
+}
+
+/**
+ * Triangle mesh of a tube, every face corner has a position and a normal index
+ */
+#[derive(Debug, Clone, Default)]
+pub struct TubeMesh {
+    pub positions: Vec<Vecf3>,
+    pub normals: Vec<Vecf3>,
+    pub faces: Vec<[(u32, u32); 3]>,
+}
+
+impl TubeMesh {
+    // @returns positions, normals and triangle indices where every vertex has one position and normal
+    pub fn single_index(&self) -> (Vec<Vecf3>, Vec<Vecf3>, Vec<u32>) {
+        let mut vertices = HashMap::new();
+        let mut positions = vec![];
+        let mut normals = vec![];
+        let mut indices = vec![];
+        for face in &self.faces {
+            for corner in face {
+                let index = *vertices.entry(*corner).or_insert_with(|| {
+                    positions.push(self.positions[corner.0 as usize]);
+                    normals.push(self.normals[corner.1 as usize]);
+                    positions.len() as u32 - 1
+                });
+                indices.push(index);
+            }
+        }
+        (positions, normals, indices)
+    }
+}
+
+impl Tube {
+    pub fn from_branchdata(data: &BranchData, group: PartGroup) -> Self {
+        Self {
+            group,
+            ids: vec![data.id()],
+            rings: vec![
+                TubeRing {
+                    center: data.start_point(),
+                    radius: width_to_radius(data.start_width()),
+                },
+                TubeRing {
+                    center: data.end_point(),
+                    radius: width_to_radius(data.end_width()),
+                },
+            ],
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.rings
+            .windows(2)
+            .map(|rings| (rings[1].center - rings[0].center).length())
+            .sum()
+    }
+
+    // @returns a closed mesh with the given amount of sides around the tube
+    pub fn mesh(&self, sides: u32) -> TubeMesh {
+        let mut mesh = TubeMesh::default();
+        let sides = sides.max(3);
+        let ring_count = self.rings.len() as u32;
+        if ring_count < 2 || self.length() == 0. {
+            return mesh;
+        }
+
+        // direction of the tube at every ring
+        let mut tangents = vec![];
+        for i in 0..self.rings.len() {
+            let prev = self.rings[i.saturating_sub(1)].center;
+            let next = self.rings[(i + 1).min(self.rings.len() - 1)].center;
+            let tangent = next - prev;
+            if tangent.length() > 0. {
+                tangents.push(tangent.norm());
+            } else {
+                tangents.push(*tangents.last().unwrap_or(&Vecf3::new(0., 1., 0.)));
+            }
+        }
+
+        // transport the ring orientation along the tube so it does not twist
+        let mut normal = Self::perpendicular(tangents[0]);
+        for (ring, tangent) in self.rings.iter().zip(&tangents) {
+            let projected = normal - *tangent * (normal * *tangent);
+            if projected.length() > 1e-6 {
+                normal = projected.norm();
+            } else {
+                normal = Self::perpendicular(*tangent);
+            }
+            let binormal = tangent.cross(normal);
+
+            for side in 0..sides {
+                let angle = side as f32 / sides as f32 * 2. * std::f32::consts::PI;
+                let radial = normal * angle.cos() + binormal * angle.sin();
+                mesh.positions.push(ring.center + radial * ring.radius);
+                mesh.normals.push(radial);
+            }
+        }
+
+        // sides between consecutive rings
+        for i in 0..ring_count - 1 {
+            for side in 0..sides {
+                let next_side = (side + 1) % sides;
+                let a = i * sides + side;
+                let b = i * sides + next_side;
+                let c = (i + 1) * sides + next_side;
+                let d = (i + 1) * sides + side;
+                mesh.faces.push([(a, a), (b, b), (c, c)]);
+                mesh.faces.push([(a, a), (c, c), (d, d)]);
+            }
+        }
+
+        // caps at both ends
+        let start_center = mesh.positions.len() as u32;
+        mesh.positions.push(self.rings[0].center);
+        let start_normal = mesh.normals.len() as u32;
+        mesh.normals.push(-&tangents[0]);
+
+        let last = ring_count - 1;
+        let end_center = mesh.positions.len() as u32;
+        mesh.positions.push(self.rings[last as usize].center);
+        let end_normal = mesh.normals.len() as u32;
+        mesh.normals.push(tangents[last as usize]);
+
+        for side in 0..sides {
+            let next_side = (side + 1) % sides;
+            mesh.faces.push([
+                (start_center, start_normal),
+                (next_side, start_normal),
+                (side, start_normal),
+            ]);
+            mesh.faces.push([
+                (end_center, end_normal),
+                (last * sides + side, end_normal),
+                (last * sides + next_side, end_normal),
+            ]);
+        }
+
+        mesh
+    }
+
+    fn perpendicular(dir: Vecf3) -> Vecf3 {
+        let mut axis = Vecf3::new(1., 0., 0.);
+        if (dir * axis).abs() > 0.9 {
+            axis = Vecf3::new(0., 0., 1.);
+        }
+        dir.cross(axis).norm()
+    }
+}
+
+/**
+ * Split the plant into tubes, when merging, consecutive metamers of a branch become one tube
+ */
+pub fn collect_tubes(plant: &Plant, merge_metamers: bool) -> Vec<Tube> {
+    let mut tubes = vec![];
+    let mut poles = HashSet::new();
+    collect_axis(plant.root(), PartGroup::Trunk, merge_metamers, &mut tubes, &mut poles);
+    tubes
+}
+
+// collect the tubes of a chain of terminal metamers and everything growing from it
+fn collect_axis(
+    start: &Metamer,
+    group: PartGroup,
+    merge_metamers: bool,
+    tubes: &mut Vec<Tube>,
+    poles: &mut HashSet<u32>,
+) {
+    let mut tube = Tube::from_branchdata(start, group);
+    tube.rings.pop();
+
+    let mut current = start;
+    loop {
+        if let Some(metamer) = current.auxillary_metamer() {
+            collect_axis(metamer, PartGroup::Branches, merge_metamers, tubes, poles);
+        } else {
+            tubes.push(Tube::from_branchdata(&current.aux_bud_data, PartGroup::Buds));
+            add_pole(current.aux_support_pole.as_ref(), tubes, poles);
+        }
+        for bud in current.extra_buds() {
+            match bud.metamer() {
+                Some(metamer) => collect_axis(metamer, PartGroup::Branches, merge_metamers, tubes, poles),
+                None => tubes.push(Tube::from_branchdata(&bud.bud_data, PartGroup::Buds)),
+            }
+        }
+        add_pole(current.support_pole.as_ref(), tubes, poles);
+
+        // merged metamers share the ring between them
+        let next = current.terminal_metamer();
+        let end_radius = match next {
+            Some(next) if merge_metamers => width_to_radius(current.end_width().max(next.start_width())),
+            _ => width_to_radius(current.end_width()),
+        };
+        tube.rings.push(TubeRing {
+            center: current.end_point(),
+            radius: end_radius,
+        });
+
+        match next {
+            Some(next) => {
+                if !merge_metamers {
+                    tubes.push(tube);
+                    tube = Tube::from_branchdata(next, group);
+                    tube.rings.pop();
+                } else {
+                    tube.ids.push(next.id());
+                }
+                current = next;
+            }
+            None => {
+                tubes.push(Tube::from_branchdata(&current.terminal_bud_data, PartGroup::Buds));
+                break;
+            }
+        }
+    }
+    tubes.push(tube);
+}
+
+// poles are shared by multiple metamers, only add each pole once
+fn add_pole(pole: Option<&SupportPole>, tubes: &mut Vec<Tube>, poles: &mut HashSet<u32>) {
+    if let Some(pole) = pole {
+        if pole.visible() && poles.insert(pole.model().id()) {
+            tubes.push(Tube::from_branchdata(pole.model(), PartGroup::Poles));
+        }
+    }
+}
@@ -0,0 +1,6 @@
+pub mod tree;
+pub mod util;
+pub mod treeparameter;
+pub mod parameters;
+pub mod export;
+pub mod profile;
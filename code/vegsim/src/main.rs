@@ -1,12 +1,11 @@
 mod ui;
-mod tree;
-mod util;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 mod uirender;
 mod controller;
-mod treeparameter;
-mod parameters;
+mod treeapp;
+mod treerender;
 
 use glow::*;
 use glrender::window::window_state_event;
@@ -22,17 +21,28 @@ use patutil::window::WindowState;
 use patutil::{mesh, points, Rect, Render};
 
 use crate::controller::Controller;
+use crate::treeapp::TreeApp;
 use crate::uirender::UIRender;
+use vegsim::profile::ParameterProfile;
 
 const APP_RECT: Rect = Rect::new(0, 0, 1100u32, 800u32);
 const SCENE_RECT: Rect = patutil::Rect::new(100, 100, 700, 700);
 
 pub fn main() {
 
+    // parameters to start with, "--profile PATH" loads them from a profile file
+    let profile = match startup_profile(std::env::args().skip(1)) {
+        Ok(profile) => profile,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
     // create vegsim
     let scene: mesh::SceneRef = mesh::MeshScene::default().into();
     let point_scene: points::SceneRef = points::PointsScene::new(scene.lock().camera()).into();
-    let treedata = Arc::new(Mutex::new(tree::TreeApp::new(scene.clone(), point_scene.clone())));
+    let treedata = Arc::new(Mutex::new(TreeApp::new(scene.clone(), point_scene.clone(), profile)));
 
     // create controller
     let controller = Arc::new(Mutex::new(Controller::new(treedata.clone())));
@@ -89,6 +99,16 @@ pub fn main() {
                             == Some(iced_glutin::winit::event::VirtualKeyCode::E)
                     {
                         controller.lock().unwrap().perform_growth_iteration();
+                    } else if input.state == ElementState::Released
+                        && input.virtual_keycode
+                            == Some(iced_glutin::winit::event::VirtualKeyCode::Z)
+                    {
+                        controller.lock().unwrap().undo();
+                    } else if input.state == ElementState::Released
+                        && input.virtual_keycode
+                            == Some(iced_glutin::winit::event::VirtualKeyCode::Y)
+                    {
+                        controller.lock().unwrap().redo();
                     } else if input.state == ElementState::Released
                         && input.virtual_keycode
                             == Some(iced_glutin::winit::event::VirtualKeyCode::Escape)
@@ -106,12 +126,42 @@ pub fn main() {
                             glrender::FrameBuffer::save_viewport("output.png".to_string()).unwrap();
                         }
                         println!("Image saved");
+                    } else if input.state == ElementState::Released
+                        && input.virtual_keycode
+                            == Some(iced_glutin::winit::event::VirtualKeyCode::O)
+                    {
+                        match controller.lock().unwrap().export_obj(Path::new("output.obj")) {
+                            Ok(()) => println!("Tree saved to output.obj"),
+                            Err(err) => println!("Failed to save output.obj: {}", err),
+                        }
+                    } else if input.state == ElementState::Released
+                        && input.virtual_keycode
+                            == Some(iced_glutin::winit::event::VirtualKeyCode::G)
+                    {
+                        match controller.lock().unwrap().export_glb(Path::new("output.glb")) {
+                            Ok(()) => println!("Tree saved to output.glb"),
+                            Err(err) => println!("Failed to save output.glb: {}", err),
+                        }
                     }
                 }
                 WindowEvent::MouseInput { state, button, .. }=>{
                     if *button == MouseButton::Right && *state == ElementState::Pressed{
                         let picking_id = glrender.mesh_picking(window_state.cursor().pos(),patutil::Rect::new(100, 100, 700, 700), scene.clone());
-                        controller.lock().unwrap().update_selected(picking_id);
+                        if uirender.controls().cut_mode() {
+                            // cut the picked branch at the cursor
+                            if let Some(id) = picking_id {
+                                let mut camref = scene.lock().camera();
+                                let (origin, dir) = treerender::picking_ray(
+                                    &*camref.lock(),
+                                    window_state.cursor().pos(),
+                                    SCENE_RECT,
+                                    window_state.size(),
+                                );
+                                controller.lock().unwrap().cut_at_ray(id, origin, dir);
+                            }
+                        } else {
+                            controller.lock().unwrap().update_selected(picking_id);
+                        }
                     }
                 }
                 _ => (),
@@ -203,4 +253,19 @@ fn draw_scene(
         false,
     );
     glrender.draw_texture(([0, 0].into(), debug_size).into(), debug_texture, false);
-}
\ No newline at end of file
+}
+
+fn startup_profile(mut args: impl Iterator<Item = String>) -> Result<ParameterProfile, String> {
+    let mut profile = ParameterProfile::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--profile" => {
+                let path = args.next().ok_or("missing value for --profile")?;
+                profile = ParameterProfile::load(Path::new(&path))
+                    .map_err(|err| format!("Failed to load {}: {}", path, err))?;
+            }
+            _ => return Err(format!("unknown argument {}\nusage: vegsim [--profile PATH]", arg)),
+        }
+    }
+    Ok(profile)
+}
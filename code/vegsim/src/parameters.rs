@@ -1,9 +1,9 @@
 use std::f32::consts::PI;
 use crate::treeparameter::SpaceDividingMode;
 use crate::treeparameter::DistributionMode;
-use patutil::Vecf3;
 
-pub const SEED: u64 = 50365756705;                      // seed used for everything random
+// default values, everything except the seed can be overridden by a parameter profile (see profile/mod.rs)
+pub const SEED: u64 = 50365756705;                      // default seed used for everything random
 
 pub const RESOURCE_DISTRIBUTION_MODE: DistributionMode = DistributionMode::BorchertHonda;   // change between BorchertHonda and PriorityList for resource distribution
 pub const BORCHERT_HONDA_LAMBDA: f32 =  0.52;           // resource distribution ratio between auxillary bud and terminal when using BH-model
@@ -23,9 +23,6 @@ pub const AXILLARY_PERTURBATION_ANGLE: f32 = PI / 38.0; // max angle between ter
 pub const OPTIMAL_GROWTH_DIRECTION_WEIGHT: f32 = 0.2;   // weigth of the optimal growth direction used when choosing metamer direction
 pub const SHED_TRESHHOLD: f32 = 0.01;                   // min resources a branch needs before it is shed
 
-pub const TROPISM_START_WEIGTH: f32 = 0.1;              // starting weight for tropism vector on when choosing metamer direction
-pub const TROPISM_DIR: Vecf3 = Vecf3::new(0., -0., 0.); // direction of tropism
-pub const TROPISM_CHANGE_RATE: f32 = 1.01;              // rate tropism weight changes every iteration
 
 pub const BOUNDING_BOX_SIDE: f32 = 50.;                 // length of a bounding box side
 
@@ -42,4 +39,44 @@ pub const SHADOW_VOXEL_PIRAMID_LAYERS: i32 = 5;
 pub const WIDTH_GROW_EXPONENT: f32 = 1.9f32;            // change in width depending on metamers attached
 pub const WIDTH_MIN_VALUE: f32 = 1.0e-8f32;             // minimum width a metamer can have
 
-pub const BUD_RECOVERY_SPEED: f32 = 0.;                 // recovery speed a bud repairs from damge due to pruning, damage starts at 1, recovery speed default 0: bud wil never recover
\ No newline at end of file
+pub const ITERATIONS_PER_YEAR: u32 = 4;                 // growth iterations in one year, winter pruning happens after the last one
+
+pub const BUD_RECOVERY_SPEED: f32 = 0.;                 // recovery speed a bud repairs from damge due to pruning, damage starts at 1, a pruned bud is dead until it recovered, recovery speed default 0: bud wil never recover
+pub const BUD_DORMANCY_AGE: f32 = 0.;                   // iterations an axillary bud stays active without growing before it becomes dormant, 0: buds never become dormant
+pub const BUD_RELEASE_DISTANCE: f32 = 6.;               // dormant buds this many metamers or less below a pruned apex become active again, 0: pruning does not release buds
+pub const BUD_RELEASE_LIGHT: f32 = 0.;                  // light a dormant bud needs to become active again, 0: light does not release buds
+pub const BUD_LIFESPAN: f32 = 0.;                       // age of a metamer after which its dormant bud dies, 0: dormant buds never die
+
+pub const AUXIN_PRODUCTION: f32 = 0.;                   // auxin an active terminal bud produces every iteration, 0: no apical control by auxin
+pub const AUXIN_DECAY: f32 = 0.5;                       // part of the auxin left after flowing down through a metamer
+pub const AUXIN_THRESHOLD: f32 = 0.3;                   // auxin at a node from which the axillary bud does not break
+
+pub const BUDS_PER_NODE: f32 = 1.;                      // axillary buds at every node: 1 alternate or spiral, 2 opposite or decussate, more for whorls
+pub const DIVERGENCE_ANGLE: f32 = 137.5 * PI / 180.;    // rotation around the axis from one node to the next, 180° alternate, 137.5° spiral, 90° decussate, only used with a branching angle above 0
+pub const BRANCHING_ANGLE: f32 = 0.;                    // angle between the axis and its axillary buds, 0: buds are a random perturbation of the axis
+
+pub const LEAF_SIZE: f32 = 0.;                          // length of a full grown leaf like the metamer length, 0: no leaves
+pub const LEAF_ANGLE: f32 = PI / 3.;                    // angle between the axis and the leaf blade
+pub const LEAF_LIFESPAN: f32 = 0.;                      // growth iterations a leaf stays on the plant, 0: leaves do not age
+pub const LEAF_ABSCISSION_LIGHT: f32 = 0.;              // leaves gathering less light than this fall off, 0: shaded leaves stay
+pub const LEAF_EFFICIENCY: f32 = 0.5;                   // light a full grown leaf gathers compared to a bud in the same spot
+
+pub const SPUR_LENGTH: f32 = 0.;                        // longest lateral shoot in metamers that can flower on its tip, 0: no flowering
+pub const FLOWER_AGE: f32 = 2.;                         // growth iterations the tip metamer of a spur needs before its bud flowers
+pub const FLOWER_LIGHT: f32 = 0.5;                      // light a spur tip needs to flower
+pub const FRUIT_SINK: f32 = 2.;                         // resources drawn by flowers and fruits compared to a bud with the same light
+pub const FRUIT_SET_REQUIREMENT: f32 = 0.3;             // resources a flower needs to set a fruit, flowers getting less drop
+pub const FRUIT_GROWTH: f32 = 0.05;                     // fruit mass in kg grown from one unit of resources
+pub const FRUIT_MATURITY: f32 = 3.;                     // growth iterations from fruit set to harvest
+
+pub const GRAVITROPISM: f32 = 0.;                       // pull of the trunk towards its gravitropic setpoint angle, 0: no gravitropism
+pub const GRAVITROPISM_ANGLE: f32 = 0.;                 // setpoint angle of the trunk from the vertical, 0: orthotropic, PI/2: plagiotropic
+pub const LATERAL_GRAVITROPISM: f32 = 0.;               // pull of first order branches towards their setpoint angle
+pub const LATERAL_GRAVITROPISM_ANGLE: f32 = PI / 2.;    // setpoint angle of branches from the vertical
+pub const PHOTOTROPISM: f32 = 0.;                       // pull of the trunk towards the light, 0: no phototropism
+pub const LATERAL_PHOTOTROPISM: f32 = 0.;               // pull of first order branches towards the light
+pub const TROPISM_ORDER_FACTOR: f32 = 1.;               // tropisms of every further branch order compared to the order before, 1: same as first order branches
+
+pub const WOOD_STIFFNESS: f32 = 0.;                     // elastic modulus of the wood in GPa, 0: branches do not bend
+pub const WOOD_DENSITY: f32 = 800.;                     // mass of the wood in kg/m3
+pub const LEAF_MASS: f32 = 0.2;                         // mass of the leaves in kg per m2 of blade
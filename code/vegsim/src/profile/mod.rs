@@ -0,0 +1,223 @@
+mod tests;
+
+use std::{
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{
+    parameters,
+    tree::{environment::EnvironmentParameters, plantgenetics::PlantGenetics},
+    treeparameter::{DistributionMode, GeneticParameter},
+};
+
+// extension of profile files
+pub const PROFILE_EXTENSION: &str = "txt";
+
+#[derive(Debug)]
+pub enum ProfileError {
+    Io(io::Error),
+    Syntax { line: usize, text: String },
+    UnknownKey { line: usize, key: String },
+    DuplicateKey { line: usize, key: String },
+    InvalidValue { line: usize, key: String, value: String },
+    OutOfRange { key: String, value: f32, min: f32, max: f32 },
+}
+
+impl std::error::Error for ProfileError {}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::Io(err) => write!(f, "could not access profile file: {}", err),
+            ProfileError::Syntax { line, text } => {
+                write!(f, "line {}: expected 'key = value', found '{}'", line, text)
+            }
+            ProfileError::UnknownKey { line, key } => {
+                write!(f, "line {}: unknown parameter '{}'", line, key)
+            }
+            ProfileError::DuplicateKey { line, key } => {
+                write!(f, "line {}: parameter '{}' is set more than once", line, key)
+            }
+            ProfileError::InvalidValue { line, key, value } => {
+                write!(f, "line {}: invalid value '{}' for {}", line, value, key)
+            }
+            ProfileError::OutOfRange { key, value, min, max } => write!(
+                f,
+                "{} = {} is out of range, expected a value from {} to {}",
+                key, value, min, max
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for ProfileError {
+    fn from(err: io::Error) -> Self {
+        ProfileError::Io(err)
+    }
+}
+
+/**
+ * Every model parameter that can be changed without recompiling, stored as "key = value" lines
+ */
+#[derive(Debug, Clone)]
+pub struct ParameterProfile {
+    pub name: String,
+    pub distribution_mode: DistributionMode,
+    pub genetics: PlantGenetics,
+    pub environment: EnvironmentParameters,
+}
+
+impl Default for ParameterProfile {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            distribution_mode: parameters::RESOURCE_DISTRIBUTION_MODE,
+            genetics: PlantGenetics::new(),
+            environment: EnvironmentParameters::default(),
+        }
+    }
+}
+
+impl ParameterProfile {
+    pub fn load(path: &Path) -> Result<Self, ProfileError> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ProfileError> {
+        let mut file = File::create(path)?;
+        self.write(&mut file)?;
+        Ok(())
+    }
+
+    // parameters missing from the text keep their default value
+    pub fn parse(text: &str) -> Result<Self, ProfileError> {
+        let mut profile = Self::default();
+        let mut keys: Vec<String> = vec![];
+
+        for (index, line) in text.lines().enumerate() {
+            let line_nr = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or(ProfileError::Syntax {
+                line: line_nr,
+                text: line.to_string(),
+            })?;
+            let key = key.trim();
+            let value = value.trim();
+
+            if keys.iter().any(|known| known == key) {
+                return Err(ProfileError::DuplicateKey { line: line_nr, key: key.to_string() });
+            }
+            keys.push(key.to_string());
+
+            profile.set_value(line_nr, key, value)?;
+        }
+
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    fn set_value(&mut self, line: usize, key: &str, value: &str) -> Result<(), ProfileError> {
+        let parse_error = || ProfileError::InvalidValue {
+            line,
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        fn parse<T: FromStr>(value: &str, err: impl Fn() -> ProfileError) -> Result<T, ProfileError> {
+            value.parse().map_err(|_| err())
+        }
+
+        if let Some(param) = GeneticParameter::ALL.iter().find(|param| param.name() == key) {
+            let value = parse(value, parse_error)?;
+            self.genetics.update_param(param.with_value(value));
+            return Ok(());
+        }
+
+        let environment = &mut self.environment;
+        match key {
+            "name" => self.name = value.to_string(),
+            "resource_distribution_mode" => self.distribution_mode = parse(value, parse_error)?,
+            // the constant tropism direction was replaced by the tropisms of the genetics, older profiles still set it
+            "tropism_start_weight" | "tropism_dir" | "tropism_change_rate" => {}
+            "bounding_box_side" => environment.bounding_box_side = parse(value, parse_error)?,
+            "space_div_mode" => environment.space_div_mode = parse(value, parse_error)?,
+            "space_div_resolution" => environment.space_div_resolution = parse(value, parse_error)?,
+            "shadow_voxel_a" => environment.shadow_voxel_a = parse(value, parse_error)?,
+            "shadow_voxel_b" => environment.shadow_voxel_b = parse(value, parse_error)?,
+            "shadow_voxel_c" => environment.shadow_voxel_c = parse(value, parse_error)?,
+            "shadow_voxel_max_shadow" => environment.shadow_voxel_max_shadow = parse(value, parse_error)?,
+            "shadow_voxel_piramid_layers" => {
+                environment.shadow_voxel_piramid_layers = parse(value, parse_error)?
+            }
+            _ => return Err(ProfileError::UnknownKey { line, key: key.to_string() }),
+        }
+        Ok(())
+    }
+
+    // check every value against the range the model can work with
+    pub fn validate(&self) -> Result<(), ProfileError> {
+        for param in GeneticParameter::ALL {
+            let param = self.genetics.get_param(param);
+            let (min, max) = param.range();
+            check_range(param.name(), param.value(), min, max)?;
+        }
+
+        let environment = &self.environment;
+        check_range("bounding_box_side", environment.bounding_box_side, 1., 1000.)?;
+        // memory use grows with the cube of the resolution
+        check_range("space_div_resolution", environment.space_div_resolution as f32, 1., 300.)?;
+        check_range("shadow_voxel_a", environment.shadow_voxel_a, 0., 10.)?;
+        check_range("shadow_voxel_b", environment.shadow_voxel_b, 1., 10.)?;
+        check_range("shadow_voxel_c", environment.shadow_voxel_c, 0., 10.)?;
+        check_range("shadow_voxel_max_shadow", environment.shadow_voxel_max_shadow, 0., 100.)?;
+        check_range(
+            "shadow_voxel_piramid_layers",
+            environment.shadow_voxel_piramid_layers as f32,
+            0.,
+            50.,
+        )?;
+        Ok(())
+    }
+
+    pub fn write(&self, out: &mut dyn Write) -> io::Result<()> {
+        let environment = &self.environment;
+        writeln!(out, "# vegsim parameter profile")?;
+        writeln!(out, "name = {}", self.name)?;
+        writeln!(out, "resource_distribution_mode = {}", self.distribution_mode)?;
+
+        writeln!(out, "\n# plant genetics")?;
+        for param in GeneticParameter::ALL {
+            let param = self.genetics.get_param(param);
+            writeln!(out, "{} = {}", param.name(), param.value())?;
+        }
+
+        writeln!(out, "\n# environment")?;
+        writeln!(out, "bounding_box_side = {}", environment.bounding_box_side)?;
+        writeln!(out, "space_div_mode = {}", environment.space_div_mode)?;
+        writeln!(out, "space_div_resolution = {}", environment.space_div_resolution)?;
+
+        writeln!(out, "\n# shadow voxels")?;
+        writeln!(out, "shadow_voxel_a = {}", environment.shadow_voxel_a)?;
+        writeln!(out, "shadow_voxel_b = {}", environment.shadow_voxel_b)?;
+        writeln!(out, "shadow_voxel_c = {}", environment.shadow_voxel_c)?;
+        writeln!(out, "shadow_voxel_max_shadow = {}", environment.shadow_voxel_max_shadow)?;
+        writeln!(out, "shadow_voxel_piramid_layers = {}", environment.shadow_voxel_piramid_layers)?;
+        Ok(())
+    }
+}
+
+fn check_range(key: &str, value: f32, min: f32, max: f32) -> Result<(), ProfileError> {
+    // written this way round so NaN is out of range as well
+    if !(value >= min && value <= max) {
+        return Err(ProfileError::OutOfRange { key: key.to_string(), value, min, max });
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+#![cfg(test)]
+
+use crate::{
+    tree::Simulation,
+    treeparameter::{GeneticParameter, SpaceDividingMode, TreeParameter},
+};
+
+use super::{ParameterProfile, ProfileError};
+
+fn to_text(profile: &ParameterProfile) -> String {
+    let mut data = vec![];
+    profile.write(&mut data).unwrap();
+    String::from_utf8(data).unwrap()
+}
+
+#[test]
+fn test_profile_round_trip() {
+    let mut profile = ParameterProfile::default();
+    profile.name = "apple".to_string();
+    profile.genetics.update_param(GeneticParameter::WidthGrowExponent(2.5));
+    profile.environment.space_div_mode = SpaceDividingMode::Markers;
+    profile.environment.bounding_box_side = 80.;
+
+    let text = to_text(&profile);
+    let loaded = ParameterProfile::parse(&text).unwrap();
+    assert_eq!(loaded.name, "apple");
+    assert_eq!(loaded.environment, profile.environment);
+    assert_eq!(loaded.genetics.width_grow_exponent(), 2.5);
+    assert_eq!(to_text(&loaded), text);
+}
+
+#[test]
+fn test_profile_partial() {
+    let profile = ParameterProfile::parse("# only lambda\nborchert_honda_lambda = 0.6\n").unwrap();
+    let default = ParameterProfile::default();
+    assert_eq!(profile.genetics.borchert_honda_lambda(), 0.6);
+    assert_eq!(profile.genetics.borchert_honda_alpha(), default.genetics.borchert_honda_alpha());
+    assert_eq!(profile.environment, default.environment);
+
+    // keys of the constant tropism direction of older profiles are skipped
+    let profile = ParameterProfile::parse("tropism_start_weight = 0.1\ntropism_dir = 0, -0, 0\ntropism_change_rate = 1.01\n");
+    assert_eq!(profile.unwrap().environment, default.environment);
+}
+
+#[test]
+fn test_profile_errors() {
+    let parse = |text: &str| ParameterProfile::parse(text).unwrap_err();
+
+    assert!(matches!(parse("lambda 0.5"), ProfileError::Syntax { line: 1, .. }));
+    assert!(matches!(parse("\nlambda = 0.5"), ProfileError::UnknownKey { line: 2, .. }));
+    assert!(matches!(
+        parse("shadow_voxel_a = 0.1\nshadow_voxel_a = 0.2"),
+        ProfileError::DuplicateKey { line: 2, .. }
+    ));
+    assert!(matches!(parse("space_div_mode = Voxels"), ProfileError::InvalidValue { .. }));
+
+    let err = parse("borchert_honda_lambda = 1.5");
+    assert!(matches!(err, ProfileError::OutOfRange { max, .. } if max == 1.));
+    assert_eq!(
+        err.to_string(),
+        "borchert_honda_lambda = 1.5 is out of range, expected a value from 0 to 1"
+    );
+    assert!(matches!(parse("shadow_voxel_b = NaN"), ProfileError::OutOfRange { .. }));
+}
+
+#[test]
+fn test_genetic_parameters() {
+    let mut genetics = ParameterProfile::default().genetics;
+    for (index, param) in GeneticParameter::ALL.iter().enumerate() {
+        genetics.update_param(param.with_value(index as f32));
+    }
+    for (index, param) in GeneticParameter::ALL.iter().enumerate() {
+        assert_eq!(genetics.get_param(param.clone()).value(), index as f32, "{}", param.name());
+    }
+}
+
+#[test]
+fn test_reset_to_profile() {
+    let lambda = |simulation: &Simulation| {
+        match simulation.get_tree_param(TreeParameter::Genetic(GeneticParameter::BorchertHondaLambda(0.))) {
+            TreeParameter::Genetic(param) => param.value(),
+            _ => unreachable!(),
+        }
+    };
+
+    let mut profile = ParameterProfile::default();
+    profile.genetics.update_param(GeneticParameter::BorchertHondaLambda(0.45));
+    let mut simulation = Simulation::with_profile(profile, 1);
+    assert_eq!(lambda(&simulation), 0.45);
+
+    simulation.update_tree_param(TreeParameter::Genetic(GeneticParameter::BorchertHondaLambda(0.58)));
+    assert_eq!(lambda(&simulation), 0.58);
+
+    simulation.reset_tree_params();
+    assert_eq!(lambda(&simulation), 0.45);
+}
+
+#[test]
+fn test_current_profile() {
+    let mut simulation = Simulation::with_profile(ParameterProfile::default(), 1);
+    simulation.update_tree_param(TreeParameter::Genetic(GeneticParameter::BorchertHondaLambda(0.58)));
+    simulation.update_tree_param(TreeParameter::SpaceDividingMode(SpaceDividingMode::Markers));
+
+    let saved = ParameterProfile::parse(&to_text(&simulation.current_profile("tuned"))).unwrap();
+    assert_eq!(saved.name, "tuned");
+    assert_eq!(saved.genetics.borchert_honda_lambda(), 0.58);
+    assert_eq!(saved.environment.space_div_mode, SpaceDividingMode::Markers);
+    // the simulation keeps the profile it was started with
+    assert_eq!(simulation.profile().name, "default");
+}
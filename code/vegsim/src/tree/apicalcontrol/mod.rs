@@ -0,0 +1,58 @@
+/*
+ * Apical control by an auxin signal: every active terminal bud produces auxin that flows down to the base
+ * of the plant, losing part of it in every metamer. Branches joining at a node add their signal.
+ * An axillary bud does not break while the auxin passing its node is at or above the threshold,
+ * removing the buds above by pruning lowers the signal and lets the buds below grow.
+ */
+mod tests;
+
+use super::{metamer::Metamer, plantgenetics::PlantGenetics};
+
+pub struct ApicalControl {
+    // auxin produced by an active terminal bud, 0 switches apical control off
+    production: f32,
+    // part of the auxin that is left after flowing through a metamer
+    decay: f32,
+    threshold: f32,
+}
+
+impl ApicalControl {
+    pub fn new(genetics: &PlantGenetics) -> Self {
+        Self {
+            production: genetics.auxin_production(),
+            decay: genetics.auxin_decay(),
+            threshold: genetics.auxin_threshold(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.production > 0.
+    }
+
+    // @returns true when the given auxin level keeps an axillary bud from breaking
+    pub fn suppresses(&self, auxin: f32) -> bool {
+        self.enabled() && auxin >= self.threshold
+    }
+
+    /**
+     * Store the auxin passing the end of every metamer
+     * @returns the auxin leaving the metamer at its start
+     */
+    pub fn update(&self, metamer: &mut Metamer) -> f32 {
+        let mut node = 0.;
+        if let Some(m) = metamer.terminal_metamer_mut() {
+            node += self.update(m);
+        } else if metamer.terminal_bud_damage == 0. && metamer.terminal_bud_fate.can_grow() {
+            node += self.production;
+        }
+        if let Some(m) = metamer.auxillary_metamer_mut() {
+            node += self.update(m);
+        }
+        for m in metamer.extra_buds_mut().iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            node += self.update(m);
+        }
+
+        metamer.auxin = node;
+        node * self.decay
+    }
+}
@@ -0,0 +1,55 @@
+#![cfg(test)]
+
+use crate::{
+    tree::{metamer::Metamer, Simulation},
+    treeparameter::{GeneticParameter, TreeParameter},
+};
+
+use super::ApicalControl;
+
+// auxin of the trunk metamers from the base up
+fn trunk_auxin(root: &Metamer) -> Vec<f32> {
+    let mut auxin = vec![root.auxin];
+    let mut metamer = root;
+    while let Some(m) = metamer.terminal_metamer() {
+        auxin.push(m.auxin);
+        metamer = m;
+    }
+    auxin
+}
+
+#[test]
+fn test_apical_control() {
+    let mut simulation = Simulation::with_seed(3);
+    for param in [
+        GeneticParameter::AuxinProduction(1.),
+        GeneticParameter::AuxinDecay(0.8),
+        GeneticParameter::AuxinThreshold(0.5),
+    ] {
+        simulation.update_tree_param(TreeParameter::Genetic(param));
+    }
+    for _ in 0..8 {
+        simulation.perform_growth_iteration();
+    }
+
+    let control = ApicalControl::new(&simulation.plant_genetics().lock().unwrap());
+    control.update(simulation.plant_mut().root_mut());
+    let auxin = trunk_auxin(simulation.plant().root());
+
+    // the trunk tip holds the production of its own bud, buds below the tip are suppressed
+    let tip = auxin.len() - 1;
+    assert_eq!(auxin[tip], 1.);
+    assert!(control.suppresses(auxin[tip - 1]));
+    for pair in auxin.windows(2) {
+        assert!(pair[0] >= pair[1] * 0.8 - 1.0e-6);
+    }
+
+    // without the leader only the side branch of the root sends auxin to its node
+    let top = simulation.plant().root().terminal_metamer().unwrap().id();
+    simulation.prune_id(top);
+    control.update(simulation.plant_mut().root_mut());
+    let root = simulation.plant().root();
+    let side = root.auxillary_metamer().map_or(0., |m| m.auxin * 0.8);
+    assert!((root.auxin - side).abs() < 1.0e-6);
+    assert!(root.auxin < auxin[0]);
+}
@@ -0,0 +1,150 @@
+/*
+ * Branches bend under their own weight: the wood, leaves and fruits above a metamer pull on its start
+ * like a load on a cantilever. The bending moment divided by the stiffness of the cross section
+ * (elastic modulus times the second moment of area of the pipe model width) gives the curvature,
+ * the curvature times the length of the metamer the angle the metamer and everything above it turn.
+ * The bending is elastic, a branch rises again when it loses load, e.g. after the harvest or a prune.
+ * Metamers tied to a support pole hold their shape, and so do the metamers below them:
+ * a branch tied down further out does not sag between its base and the pole.
+ */
+mod tests;
+
+use std::f32::consts::PI;
+
+use patutil::Vecf3;
+
+use crate::util::{meter_to_real_length, rot_vec_around_axis, width_to_radius};
+
+use super::{metamer::Metamer, plantgenetics::PlantGenetics};
+
+// m/s2
+pub const GRAVITY: f32 = 9.81;
+// most a single metamer turns, keeps very thin shoots under a heavy load from folding over
+pub const MAX_BENDING_ANGLE: f32 = PI / 6.;
+
+/**
+ * Mass carried by a metamer and everything above it
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Load {
+    // kg
+    pub mass: f32,
+    // mass times position, divided by the mass it gives the center of mass
+    pub moment: Vecf3,
+    // largest bending of a metamer in radians
+    pub max_angle: f32,
+    // a metamer above is tied to a support pole, which holds the metamers below it
+    pub held: bool,
+}
+
+impl Load {
+    fn add(&mut self, mass: f32, position: Vecf3) {
+        self.mass += mass;
+        self.moment += position * mass;
+    }
+
+    fn merge(&mut self, other: Load) {
+        self.mass += other.mass;
+        self.moment += other.moment;
+        self.max_angle = self.max_angle.max(other.max_angle);
+        self.held |= other.held;
+    }
+}
+
+pub struct Bending {
+    // elastic modulus in Pa, 0 switches bending off
+    stiffness: f32,
+    wood_density: f32,
+    leaf_mass: f32,
+}
+
+impl Bending {
+    pub fn new(genetics: &PlantGenetics) -> Self {
+        Self {
+            stiffness: genetics.wood_stiffness() * 1.0e9,
+            wood_density: genetics.wood_density(),
+            leaf_mass: genetics.leaf_mass(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.stiffness > 0.
+    }
+
+    /**
+     * Bend every metamer to the load it carries, starting at the tips so every metamer
+     * bends with the shape the branches above it already have.
+     * Metamers held by a pole keep the bending they have, turning them would move the tied metamers off their pole.
+     * @returns the load of the metamer and everything above it
+     */
+    pub fn update(&self, metamer: &mut Metamer) -> Load {
+        let mut load = Load::default();
+        if let Some(m) = metamer.terminal_metamer_mut() {
+            load.merge(self.update(m));
+        }
+        if let Some(m) = metamer.auxillary_metamer_mut() {
+            load.merge(self.update(m));
+        }
+        for m in metamer.extra_buds_mut().iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            load.merge(self.update(m));
+        }
+
+        let meter = meter_to_real_length(1.);
+        load.add(self.wood_mass(metamer), (metamer.start_point() + metamer.end_point()) / 2.);
+        for leaf in metamer.leaves() {
+            load.add(leaf.blade_area() * self.leaf_mass, leaf.center());
+        }
+        if let Some(fruit) = &metamer.fruit {
+            load.add(fruit.mass(), fruit.data().begin_point());
+        }
+
+        if metamer.support_pole.is_some() {
+            // tied to a pole
+            load.held = true;
+            return load;
+        }
+        if load.held {
+            return load;
+        }
+
+        let pivot = metamer.start_point();
+        let mut torque = Vecf3::new(0., 0., 0.);
+        if load.mass > 0. {
+            let arm = (load.moment / load.mass - pivot) / meter;
+            torque = arm.cross(Vecf3::new(0., -load.mass * GRAVITY, 0.));
+        }
+        let radius = width_to_radius(metamer.start_width()) / meter;
+        let rigidity = self.stiffness * PI * radius.powi(4) / 4.;
+
+        let mut target = Vecf3::new(0., 0., 0.);
+        if torque.length() > 1.0e-9 {
+            let angle = if rigidity > 0. {
+                (torque.length() / rigidity * metamer.length() / meter).min(MAX_BENDING_ANGLE)
+            } else {
+                MAX_BENDING_ANGLE
+            };
+            target = torque.norm() * angle;
+        }
+
+        // turn by the difference to the bending the metamer already has
+        let change = target - metamer.bending;
+        if change.length() > 1.0e-6 {
+            let axis = change.norm();
+            let angle = change.length();
+            metamer.rotate(pivot, axis, angle);
+            let offset = load.moment - pivot * load.mass;
+            load.moment = rot_vec_around_axis(&offset, &axis, angle) + pivot * load.mass;
+        }
+        metamer.bending = target;
+        load.max_angle = load.max_angle.max(target.length());
+
+        load
+    }
+
+    // kg, length times the cross section at the average width
+    fn wood_mass(&self, metamer: &Metamer) -> f32 {
+        let meter = meter_to_real_length(1.);
+        let radius = width_to_radius((metamer.start_width() + metamer.end_width()) / 2.) / meter;
+        PI * radius * radius * metamer.length() / meter * self.wood_density
+    }
+}
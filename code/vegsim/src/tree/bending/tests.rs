@@ -0,0 +1,128 @@
+#![cfg(test)]
+
+use crate::{
+    profile::ParameterProfile,
+    tree::{metamer::Metamer, support_pole::SupportPole, Simulation},
+    treeparameter::GeneticParameter,
+};
+
+use super::{Bending, MAX_BENDING_ANGLE};
+
+// end points of all metamers
+fn end_points(metamer: &Metamer, points: &mut Vec<f32>) {
+    let end = metamer.end_point();
+    points.extend([end.x, end.y, end.z]);
+    if let Some(m) = metamer.terminal_metamer() {
+        end_points(m, points);
+    }
+    if let Some(m) = metamer.auxillary_metamer() {
+        end_points(m, points);
+    }
+    for m in metamer.extra_buds().iter().filter_map(|bud| bud.metamer()) {
+        end_points(m, points);
+    }
+}
+
+fn height(metamer: &Metamer) -> f32 {
+    let mut points = vec![];
+    end_points(metamer, &mut points);
+    points.iter().skip(1).step_by(3).sum()
+}
+
+// lowest branch on the trunk that is longer than one metamer
+fn first_branch(metamer: &mut Metamer) -> Option<&mut Metamer> {
+    if metamer.auxillary_metamer().is_some_and(|m| m.terminal_metamer().is_some()) {
+        return metamer.auxillary_metamer_mut();
+    }
+    first_branch(metamer.terminal_metamer_mut()?)
+}
+
+#[test]
+fn test_branches_bend_under_load() {
+    let mut simulation = Simulation::with_seed(4);
+    for _ in 0..10 {
+        simulation.perform_growth_iteration();
+    }
+    let original = simulation.plant().root().clone();
+    let mut genetics = simulation.plant_genetics().lock().unwrap().clone();
+    genetics.update_param(GeneticParameter::WoodStiffness(0.5));
+
+    let mut root = original.clone();
+    let load = Bending::new(&genetics).update(&mut root);
+    assert!(load.mass > 0.);
+    assert!(load.max_angle > 0. && load.max_angle <= MAX_BENDING_ANGLE);
+    assert!(height(&root) < height(&original));
+    // the trunk is tied to the pole
+    assert_eq!(root.end_point(), original.end_point());
+
+    // the bending is elastic, without load the branches rise to where they grew
+    genetics.update_param(GeneticParameter::WoodDensity(0.));
+    genetics.update_param(GeneticParameter::LeafMass(0.));
+    let load = Bending::new(&genetics).update(&mut root);
+    assert_eq!(load.max_angle, 0.);
+    let (mut bent, mut grown) = (vec![], vec![]);
+    end_points(&root, &mut bent);
+    end_points(&original, &mut grown);
+    for (a, b) in bent.iter().zip(&grown) {
+        assert!((a - b).abs() < 1.0e-3, "{} {}", a, b);
+    }
+}
+
+#[test]
+fn test_tied_branch_holds() {
+    let mut simulation = Simulation::with_seed(4);
+    for _ in 0..10 {
+        simulation.perform_growth_iteration();
+    }
+    let mut genetics = simulation.plant_genetics().lock().unwrap().clone();
+    genetics.update_param(GeneticParameter::WoodStiffness(0.5));
+
+    // the branch sags without a tie
+    let mut root = simulation.plant().root().clone();
+    let branch = first_branch(&mut root).unwrap();
+    let (start, end) = (branch.start_point(), branch.end_point());
+    let mut free = branch.clone();
+    Bending::new(&genetics).update(&mut free);
+    assert_ne!(free.end_point(), end);
+
+    // tied down further out it holds its shape up to the pole
+    let tied = branch.terminal_metamer_mut().unwrap();
+    tied.support_pole = Some(SupportPole::new(1., tied.start_point(), tied.direction(), false, 0));
+    let tied_end = tied.end_point();
+    let load = Bending::new(&genetics).update(&mut root);
+    assert!(load.held && load.max_angle > 0.);
+    let branch = first_branch(&mut root).unwrap();
+    assert_eq!((branch.start_point(), branch.end_point()), (start, end));
+    assert_eq!(branch.terminal_metamer().unwrap().end_point(), tied_end);
+}
+
+#[test]
+fn test_branches_rise_after_prune() {
+    let mut profile = ParameterProfile::default();
+    profile.genetics.update_param(GeneticParameter::WoodStiffness(0.5));
+    let mut simulation = Simulation::with_profile(profile, 4);
+    for _ in 0..10 {
+        simulation.perform_growth_iteration();
+    }
+
+    let mut root = simulation.plant().root().clone();
+    let branch = first_branch(&mut root).unwrap();
+    let (id, tip) = (branch.id(), branch.terminal_metamer().unwrap().id());
+    assert!(branch.bending.length() > 0.);
+
+    // the branch loses its load and rises right away, already bent to what is left
+    simulation.prune_id(tip);
+    let pruned = simulation.get_metamer_by_id(id).unwrap();
+    assert!(pruned.bending.length() < branch.bending.length());
+    assert!(pruned.end_point().y > branch.end_point().y);
+
+    let genetics = simulation.plant_genetics().lock().unwrap().clone();
+    let mut root = simulation.plant().root().clone();
+    Bending::new(&genetics).update(&mut root);
+    let (mut rebent, mut points) = (vec![], vec![]);
+    end_points(&root, &mut rebent);
+    end_points(simulation.plant().root(), &mut points);
+    for (a, b) in rebent.iter().zip(&points) {
+        assert!((a - b).abs() < 1.0e-3, "{} {}", a, b);
+    }
+}
@@ -1,8 +1,9 @@
-use patutil::{Vecf3, Color};
+use std::{fmt::{self, Display}, str::FromStr};
 
-use crate::util::BoundingVolume;
+use patfile::{pscan, pwrite};
+use patutil::{Vecf3, Color};
 
-use super::{partcreator::PartCreator, cone::Cone};
+use crate::util::{rot_vec_around_axis, BoundingVolume};
 
 #[derive(Debug, Clone)]
 pub struct BranchData {
@@ -10,10 +11,8 @@ pub struct BranchData {
     end_point: Vecf3,
     start_width: f32,
     end_width: f32,
-    cone: Option<Cone>,
     color: Color,
     id: u32,
-    selected: bool
 }
 
 impl BranchData {
@@ -23,37 +22,8 @@ impl BranchData {
             end_point,
             start_width,
             end_width,
-            cone: None,
             color,
             id,
-            selected: false
-        }
-    }
-
-    pub fn create_cone(&mut self, partcreator: &PartCreator) {
-        let mul = 100.;
-
-        let color = self.color();
-        let mut dir = self.end_point - self.start_point;
-        let length = dir.length();
-        dir = dir.norm();
-        let base_width = self.start_width()*mul;
-        let tip_width = self.end_width()*mul;
-
-        if self.cone.is_none(){
-            let mut cone = partcreator.new_cone(length, base_width, tip_width, color, self.id);
-            cone.set_dir(dir);
-            cone.set_pos(self.start_point);
-
-            self.cone = Some(cone);
-        } else {
-            let cone = self.cone.as_mut().unwrap();
-            cone.set_length(length);
-            cone.set_width_base(base_width);
-            cone.set_width_tip(tip_width);
-            cone.set_dir(dir);
-            cone.set_color(color);
- 
         }
     }
 
@@ -108,6 +78,12 @@ impl BranchData {
         self.end_width = end_width;
     }
 
+    // turn the branch around the pivot
+    pub fn rotate(&mut self, pivot: Vecf3, axis: Vecf3, radians: f32) {
+        self.start_point = pivot + rot_vec_around_axis(&(self.start_point - pivot), &axis, radians);
+        self.end_point = pivot + rot_vec_around_axis(&(self.end_point - pivot), &axis, radians);
+    }
+
     pub fn set_length(&mut self, length: f32){
         let dir = self.direction();
         self.end_point = self.begin_point() + dir*length;
@@ -118,17 +94,54 @@ impl BranchData {
     }
 
     pub fn color(&self) -> Color {
-        if self.selected{
-            return Color::new(0, 0, 255, 255);
-        }
         self.color
     }
 
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
     pub fn id(&self) -> u32 {
         self.id
     }
+}
+
+macro_rules! BRANCHDATA_STRING_FORMAT {
+    () => {
+        "branchdata id{} start{} end{} width{} {} color{}"
+    };
+}
+
+impl FromStr for BranchData {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut id: u32 = 0;
+        let mut start_point = Vecf3::default();
+        let mut end_point = Vecf3::default();
+        let mut start_width: f32 = 0.;
+        let mut end_width: f32 = 0.;
+        let mut color = Color::default();
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => BRANCHDATA_STRING_FORMAT!(), id, start_point, end_point, start_width, end_width, color)
+            .map_err(|_| ())?;
+
+        Ok(BranchData::new(start_point, end_point, start_width, end_width, color, id))
+    }
+}
 
-    pub fn set_selected(&mut self, selected: bool) {
-        self.selected = selected;
+impl Display for BranchData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(BRANCHDATA_STRING_FORMAT!() => writer,
+            self.id,
+            self.start_point,
+            self.end_point,
+            self.start_width,
+            self.end_width,
+            self.color
+        )
+        .map_err(|_| fmt::Error)
     }
 }
@@ -0,0 +1,197 @@
+/*
+ * Fate of buds that have not grown a shoot yet:
+ *
+ *     active   grows a shoot as soon as it gets enough resources
+ *     dormant  does not grow, released again when the apex above it is lost or it gets enough light
+ *     dead     never grows again
+ *     flower   grows flowers instead of a shoot
+ *
+ * Axillary buds that stay active without growing become dormant, dormant buds die when they get too old.
+ * Pruned buds are dead, they become active again once their damage recovered with the bud recovery speed.
+ */
+mod tests;
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use patfile::{pscan, pwrite};
+use patutil::Color;
+
+use super::plantgenetics::PlantGenetics;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudState {
+    Active,
+    Dormant,
+    Dead,
+    Flower,
+}
+
+impl BudState {
+    pub fn can_grow(&self) -> bool {
+        *self == BudState::Active
+    }
+
+    // color of the bud in the viewer
+    pub fn color(&self) -> Color {
+        match self {
+            BudState::Active => Color::new(255, 0, 0, 255),
+            BudState::Dormant => Color::new(120, 70, 40, 255),
+            BudState::Dead => Color::new(40, 40, 40, 255),
+            BudState::Flower => Color::new(255, 200, 230, 255),
+        }
+    }
+}
+
+impl Display for BudState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BudState::Active => "active",
+            BudState::Dormant => "dormant",
+            BudState::Dead => "dead",
+            BudState::Flower => "flower",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for BudState {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" => Ok(BudState::Active),
+            "dormant" => Ok(BudState::Dormant),
+            "dead" => Ok(BudState::Dead),
+            "flower" => Ok(BudState::Flower),
+            _ => Err(()),
+        }
+    }
+}
+
+/**
+ * What a bud went through in the last growth iteration
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct BudTriggers {
+    // growth iterations since the metamer of the bud was created
+    pub age: u32,
+    // light gathered by the bud
+    pub light: f32,
+    // metamers from the bud up to the end of its axis, 1 on the last metamer
+    pub tip_distance: u32,
+    // the axis above the bud lost its terminal bud by pruning or shedding
+    pub apex_lost: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BudFate {
+    state: BudState,
+    // growth iterations the bud is in its state
+    iterations: u32,
+}
+
+impl BudFate {
+    pub fn new() -> Self {
+        Self {
+            state: BudState::Active,
+            iterations: 0,
+        }
+    }
+
+    pub fn state(&self) -> BudState {
+        self.state
+    }
+
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    pub fn set_state(&mut self, state: BudState) {
+        if self.state != state {
+            self.state = state;
+            self.iterations = 0;
+        }
+    }
+
+    pub fn can_grow(&self) -> bool {
+        self.state.can_grow()
+    }
+
+    // a pruned bud is dead until it recovered from its damage
+    pub fn prune(&mut self) {
+        self.state = BudState::Dead;
+        self.iterations = 0;
+    }
+
+    /**
+     * Count a growth iteration of a pruned bud
+     * @returns true only in the first iteration after the prune
+     */
+    pub fn update_pruned(&mut self) -> bool {
+        self.iterations += 1;
+        self.iterations == 1
+    }
+
+    /**
+     * Move an axillary bud to its next state after a growth iteration
+     * @returns true when the state changed
+     */
+    pub fn update(&mut self, triggers: &BudTriggers, genetics: &PlantGenetics) -> bool {
+        self.iterations += 1;
+        let next = Self::next_state(self.state, self.iterations, triggers, genetics);
+        let changed = next != self.state;
+        self.set_state(next);
+        changed
+    }
+
+    // parameters of 0 switch their transition off
+    fn next_state(state: BudState, iterations: u32, triggers: &BudTriggers, genetics: &PlantGenetics) -> BudState {
+        let dormancy_age = genetics.bud_dormancy_age().round() as u32;
+        let lifespan = genetics.bud_lifespan().round() as u32;
+        let release_distance = genetics.bud_release_distance().round() as u32;
+        let release_light = genetics.bud_release_light();
+
+        match state {
+            BudState::Active if dormancy_age > 0 && iterations >= dormancy_age => BudState::Dormant,
+            BudState::Dormant if lifespan > 0 && triggers.age >= lifespan => BudState::Dead,
+            // apical dominance is lost when the axis above was pruned
+            BudState::Dormant if triggers.apex_lost && triggers.tip_distance <= release_distance => BudState::Active,
+            BudState::Dormant if release_light > 0. && triggers.light >= release_light => BudState::Active,
+            state => state,
+        }
+    }
+}
+
+impl Default for BudFate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+macro_rules! BUD_FATE_STRING_FORMAT {
+    () => {
+        "bud state{} iterations{}"
+    };
+}
+
+impl FromStr for BudFate {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fate = BudFate::new();
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => BUD_FATE_STRING_FORMAT!(), fate.state, fate.iterations).map_err(|_| ())?;
+        Ok(fate)
+    }
+}
+
+impl Display for BudFate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(BUD_FATE_STRING_FORMAT!() => writer, self.state, self.iterations).map_err(|_| fmt::Error)
+    }
+}
@@ -0,0 +1,136 @@
+#![cfg(test)]
+
+use crate::{
+    tree::{metamer::Metamer, plantgenetics::PlantGenetics, Simulation},
+    treeparameter::GeneticParameter,
+};
+
+use super::{BudFate, BudState, BudTriggers};
+
+fn genetics(dormancy_age: f32, release_distance: f32, release_light: f32, lifespan: f32) -> PlantGenetics {
+    let mut genetics = PlantGenetics::new();
+    genetics.update_param(GeneticParameter::BudDormancyAge(dormancy_age));
+    genetics.update_param(GeneticParameter::BudReleaseDistance(release_distance));
+    genetics.update_param(GeneticParameter::BudReleaseLight(release_light));
+    genetics.update_param(GeneticParameter::BudLifespan(lifespan));
+    genetics
+}
+
+fn triggers(age: u32, light: f32, tip_distance: u32, apex_lost: bool) -> BudTriggers {
+    BudTriggers {
+        age,
+        light,
+        tip_distance,
+        apex_lost,
+    }
+}
+
+#[test]
+fn test_bud_fate_transitions() {
+    let genetics = genetics(2., 3., 0.5, 10.);
+    let shaded = triggers(1, 0.1, 6, false);
+
+    // buds that do not grow become dormant
+    let mut fate = BudFate::new();
+    assert!(!fate.update(&shaded, &genetics));
+    assert!(fate.update(&shaded, &genetics));
+    assert_eq!(fate.state(), BudState::Dormant);
+    assert!(!fate.can_grow());
+
+    // pruning far above or an intact apex does not release the bud
+    assert!(!fate.update(&triggers(3, 0.1, 4, true), &genetics));
+    assert!(!fate.update(&triggers(3, 0.1, 2, false), &genetics));
+
+    // losing the apex close above releases the bud
+    assert!(fate.update(&triggers(4, 0.1, 3, true), &genetics));
+    assert_eq!(fate.state(), BudState::Active);
+    assert_eq!(fate.iterations(), 0);
+
+    // enough light releases a dormant bud as well
+    fate.set_state(BudState::Dormant);
+    assert!(fate.update(&triggers(5, 0.5, 6, false), &genetics));
+    assert_eq!(fate.state(), BudState::Active);
+
+    // old dormant buds die and stay dead
+    fate.set_state(BudState::Dormant);
+    assert!(fate.update(&triggers(10, 1., 1, true), &genetics));
+    assert_eq!(fate.state(), BudState::Dead);
+    assert!(!fate.update(&triggers(11, 1., 1, true), &genetics));
+
+    // a dormancy age of 0 keeps buds active
+    let mut fate = BudFate::new();
+    for _ in 0..10 {
+        fate.update(&shaded, &self::genetics(0., 3., 0., 0.));
+    }
+    assert_eq!(fate.state(), BudState::Active);
+}
+
+#[test]
+fn test_bud_fate_string() {
+    let mut fate = BudFate::new();
+    fate.set_state(BudState::Flower);
+    fate.update(&triggers(1, 0., 1, false), &genetics(2., 3., 0., 0.));
+    assert_eq!(fate.to_string(), "bud state{flower} iterations{1}");
+    assert_eq!(fate.to_string().parse(), Ok(fate));
+    assert!("bud state{sleeping} iterations{1}".parse::<BudFate>().is_err());
+}
+
+// first metamer on the trunk with a dormant axillary bud
+fn dormant_on_trunk(root: &Metamer) -> Option<u32> {
+    let mut metamer = root;
+    loop {
+        if metamer.auxillary_metamer().is_none() && metamer.aux_bud_fate.state() == BudState::Dormant {
+            return Some(metamer.id());
+        }
+        metamer = metamer.terminal_metamer()?;
+    }
+}
+
+#[test]
+fn test_pruning_releases_dormant_buds() {
+    let mut simulation = Simulation::with_params(&[GeneticParameter::BudDormancyAge(3.)], 3);
+    for _ in 0..10 {
+        simulation.perform_growth_iteration();
+    }
+    let id = dormant_on_trunk(simulation.plant().root()).expect("no dormant bud on the trunk");
+
+    // remove the leader right above the dormant bud
+    let metamer = simulation.get_metamer_by_id(id).unwrap();
+    simulation.prune_id(metamer.terminal_bud_data.id());
+
+    simulation.plant_mut().root_mut().update_bud_fate();
+    let metamer = simulation.get_metamer_by_id(id).unwrap();
+    assert_eq!(metamer.aux_bud_fate.state(), BudState::Active);
+
+    // the bud falls dormant again without growing and the old prune does not release it a second time
+    for _ in 0..6 {
+        simulation.plant_mut().root_mut().update_bud_fate();
+    }
+    let metamer = simulation.get_metamer_by_id(id).unwrap();
+    assert_eq!(metamer.aux_bud_fate.state(), BudState::Dormant);
+}
+
+#[test]
+fn test_pruned_bud_recovery() {
+    let mut simulation = Simulation::with_params(&[GeneticParameter::BudRecoverySpeed(0.5)], 3);
+    for _ in 0..6 {
+        simulation.perform_growth_iteration();
+    }
+    // a metamer on the trunk that keeps a side branch gathering light
+    let mut metamer = simulation.plant().root().terminal_metamer().unwrap();
+    while metamer.auxillary_metamer().is_none() {
+        metamer = metamer.terminal_metamer().unwrap();
+    }
+    let id = metamer.id();
+    simulation.prune_id(metamer.terminal_bud_data.id());
+    let metamer = simulation.get_metamer_by_id(id).unwrap();
+    assert_eq!(metamer.terminal_bud_fate.state(), BudState::Dead);
+
+    // the pruned bud is active again once its damage recovered
+    for _ in 0..4 {
+        simulation.perform_growth_iteration();
+    }
+    let metamer = simulation.get_metamer_by_id(id).unwrap();
+    assert_eq!(metamer.terminal_bud_damage, 0.);
+    assert!(metamer.terminal_metamer().is_some() || metamer.terminal_bud_fate.state() == BudState::Active);
+}
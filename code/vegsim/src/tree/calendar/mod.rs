@@ -0,0 +1,371 @@
+/*
+ * Optional calendar that turns growth iterations into years: every year starts with a growing season
+ * of several iterations followed by a dormant season. In a dormant iteration nothing grows,
+ * leaves fall and fruits left on the plant are picked. Without a calendar every iteration is a growth iteration.
+ * Pruning strategies can be limited to summer (the growing season) or winter (dormancy),
+ * what happened in every season is kept in the season history.
+ */
+mod tests;
+
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io,
+    path::Path,
+    str::FromStr,
+};
+
+use patfile::{pscan, pwrite};
+
+use super::{
+    flowering::FruitHistory,
+    metamer::Metamer,
+    pruning_module::history::PruningHistory,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    Growing,
+    Dormant,
+}
+
+impl Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Season::Growing => write!(f, "growing"),
+            Season::Dormant => write!(f, "dormant"),
+        }
+    }
+}
+
+impl FromStr for Season {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "growing" => Ok(Season::Growing),
+            "dormant" => Ok(Season::Dormant),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calendar {
+    // growth iterations in the growing season of a year
+    season_length: u32,
+    // iterations of dormancy at the end of a year
+    dormancy_length: u32,
+}
+
+impl Calendar {
+    // both seasons last at least one iteration
+    pub fn new(season_length: u32, dormancy_length: u32) -> Self {
+        Self {
+            season_length: season_length.max(1),
+            dormancy_length: dormancy_length.max(1),
+        }
+    }
+
+    pub fn season_length(&self) -> u32 {
+        self.season_length
+    }
+
+    pub fn dormancy_length(&self) -> u32 {
+        self.dormancy_length
+    }
+
+    pub fn year_length(&self) -> u32 {
+        self.season_length + self.dormancy_length
+    }
+
+    // @param iteration amount of iterations performed before the iteration, the first one is 0
+    pub fn season(&self, iteration: i32) -> Season {
+        if (iteration.max(0) as u32) % self.year_length() < self.season_length {
+            Season::Growing
+        } else {
+            Season::Dormant
+        }
+    }
+
+    // year of the iteration, starting at 1
+    pub fn year(&self, iteration: i32) -> u32 {
+        iteration.max(0) as u32 / self.year_length() + 1
+    }
+}
+
+// e.g. "3 growing 1 dormant"
+impl Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} growing {} dormant", self.season_length, self.dormancy_length)
+    }
+}
+
+impl FromStr for Calendar {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words[..] {
+            [season, "growing", dormancy, "dormant"] => {
+                match (season.parse::<u32>(), dormancy.parse::<u32>()) {
+                    (Ok(season), Ok(dormancy)) if season > 0 && dormancy > 0 => Ok(Self::new(season, dormancy)),
+                    _ => Err(()),
+                }
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+/**
+ * What happened to the plant in one season
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonRecord {
+    pub year: u32,
+    pub season: Season,
+    // iterations performed before the season started
+    pub first_iteration: i32,
+    pub iterations: u32,
+    pub metamers_start: u32,
+    pub metamers_end: u32,
+    // light gathered by the plant in the growth iterations of the season
+    pub light: f32,
+    pub leaves_fallen: u32,
+}
+
+impl Default for SeasonRecord {
+    fn default() -> Self {
+        Self {
+            year: 1,
+            season: Season::Growing,
+            first_iteration: 0,
+            iterations: 0,
+            metamers_start: 0,
+            metamers_end: 0,
+            light: 0.,
+            leaves_fallen: 0,
+        }
+    }
+}
+
+impl SeasonRecord {
+    // @returns true when a prune or fruit record of the given iteration happened in the season
+    pub fn contains(&self, iteration: i32) -> bool {
+        iteration > self.first_iteration && iteration <= self.first_iteration + self.iterations as i32
+    }
+}
+
+macro_rules! SEASON_RECORD_STRING_FORMAT {
+    () => {
+        "year{} season{} first{} iterations{} metamers{} to{} light{} fallen{}"
+    };
+}
+
+impl FromStr for SeasonRecord {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut record = Self::default();
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => SEASON_RECORD_STRING_FORMAT!(),
+            record.year,
+            record.season,
+            record.first_iteration,
+            record.iterations,
+            record.metamers_start,
+            record.metamers_end,
+            record.light,
+            record.leaves_fallen
+        )
+        .map_err(|_| ())?;
+        Ok(record)
+    }
+}
+
+impl Display for SeasonRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(SEASON_RECORD_STRING_FORMAT!() => writer,
+            self.year,
+            self.season,
+            self.first_iteration,
+            self.iterations,
+            self.metamers_start,
+            self.metamers_end,
+            self.light,
+            self.leaves_fallen
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
+
+/**
+ * Season record together with the prunes and the harvest of the season
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonSummary {
+    pub record: SeasonRecord,
+    pub prunes: u32,
+    pub pruned_metamers: u32,
+    // wood removed in m3
+    pub pruned_biomass: f32,
+    pub harvested: u32,
+    // kg of fruit harvested
+    pub harvested_mass: f32,
+}
+
+/**
+ * Every season of a simulation in order, the last one is still going on
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SeasonHistory {
+    records: Vec<SeasonRecord>,
+}
+
+impl SeasonHistory {
+    pub fn records(&self) -> &[SeasonRecord] {
+        &self.records
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /**
+     * Add an iteration that was just performed to its season, a new season is started when the season changed
+     * @param iteration amount of iterations performed before the iteration
+     * @param metamers_start metamers of the plant before the iteration
+     */
+    pub fn record(
+        &mut self,
+        calendar: &Calendar,
+        iteration: i32,
+        metamers_start: u32,
+        root: &Metamer,
+        leaves_fallen: u32,
+    ) {
+        let year = calendar.year(iteration);
+        let season = calendar.season(iteration);
+        let current = self
+            .records
+            .last()
+            .is_some_and(|record| record.year == year && record.season == season);
+        if !current {
+            self.records.push(SeasonRecord {
+                year,
+                season,
+                first_iteration: iteration,
+                metamers_start,
+                ..Default::default()
+            });
+        }
+
+        let record = self.records.last_mut().unwrap();
+        record.iterations += 1;
+        record.metamers_end = root.count_metamers();
+        record.leaves_fallen += leaves_fallen;
+        if season == Season::Growing {
+            record.light += root.last_light_generated();
+        }
+    }
+
+    // every season with the prunes and harvest that happened in it
+    pub fn summaries(&self, pruning: &PruningHistory, fruit: &FruitHistory) -> Vec<SeasonSummary> {
+        self.records
+            .iter()
+            .map(|record| {
+                let mut summary = SeasonSummary {
+                    record: record.clone(),
+                    prunes: 0,
+                    pruned_metamers: 0,
+                    pruned_biomass: 0.,
+                    harvested: 0,
+                    harvested_mass: 0.,
+                };
+                for prune in pruning.records().iter().filter(|prune| record.contains(prune.iteration)) {
+                    summary.prunes += 1;
+                    summary.pruned_metamers += prune.removed_ids.len() as u32;
+                    summary.pruned_biomass += prune.biomass;
+                }
+                for stats in fruit.records().iter().filter(|stats| record.contains(stats.iteration)) {
+                    summary.harvested += stats.harvested;
+                    summary.harvested_mass += stats.harvested_mass;
+                }
+                summary
+            })
+            .collect()
+    }
+
+    pub fn save_csv(&self, path: &Path, pruning: &PruningHistory, fruit: &FruitHistory) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        self.write_csv(&mut file, pruning, fruit)
+    }
+
+    // one line per season
+    pub fn write_csv(&self, out: &mut dyn io::Write, pruning: &PruningHistory, fruit: &FruitHistory) -> io::Result<()> {
+        writeln!(
+            out,
+            "year,season,first_iteration,iterations,metamers_start,metamers_end,light,leaves_fallen,prunes,pruned_metamers,pruned_biomass_m3,harvested,yield_kg"
+        )?;
+        for summary in self.summaries(pruning, fruit) {
+            let record = &summary.record;
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                record.year,
+                record.season,
+                record.first_iteration,
+                record.iterations,
+                record.metamers_start,
+                record.metamers_end,
+                record.light,
+                record.leaves_fallen,
+                summary.prunes,
+                summary.pruned_metamers,
+                summary.pruned_biomass,
+                summary.harvested,
+                summary.harvested_mass
+            )?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! SEASON_HISTORY_STRING_FORMAT {
+    () => {
+        "season{}"
+    };
+}
+
+impl FromStr for SeasonHistory {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut records = vec![];
+        let mut it = s.bytes().into_iter().peekable();
+        while it.peek().is_some() {
+            let mut record = SeasonRecord::default();
+            pscan!(&mut it => SEASON_HISTORY_STRING_FORMAT!(), record).map_err(|_| ())?;
+            records.push(record);
+            // records are separated by a space
+            it.next();
+        }
+        Ok(Self { records })
+    }
+}
+
+impl Display for SeasonHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let records: Vec<String> = self.records.iter().map(|record| record.to_string()).collect();
+        for (i, record) in records.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            let writer: &mut dyn std::fmt::Write = f;
+            pwrite!(SEASON_HISTORY_STRING_FORMAT!() => writer, record).map_err(|_| fmt::Error)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+#![cfg(test)]
+
+use crate::{
+    profile::ParameterProfile,
+    tree::{
+        pruning_module::strategy::{AutoPruning, PruningSchedule, PruningSeason, StrategyRegistry},
+        Simulation,
+    },
+    treeparameter::{GeneticParameter, TreeParameter},
+};
+
+use super::{Calendar, Season, SeasonHistory};
+
+#[test]
+fn test_calendar_seasons() {
+    let calendar = Calendar::new(3, 1);
+    let seasons: Vec<Season> = (0..5).map(|it| calendar.season(it)).collect();
+    assert_eq!(
+        seasons,
+        vec![Season::Growing, Season::Growing, Season::Growing, Season::Dormant, Season::Growing]
+    );
+    assert_eq!(calendar.year(3), 1);
+    assert_eq!(calendar.year(4), 2);
+
+    assert_eq!(calendar.to_string().parse(), Ok(calendar));
+    assert!("0 growing 1 dormant".parse::<Calendar>().is_err());
+    assert!(PruningSeason::Winter.allows(Season::Dormant));
+    assert!(!PruningSeason::Summer.allows(Season::Dormant));
+}
+
+#[test]
+fn test_dormant_season() {
+    let mut profile = ParameterProfile::default();
+    profile.genetics.update_param(GeneticParameter::LeafSize(0.3));
+    let mut simulation = Simulation::with_profile(profile, 2);
+    simulation.update_tree_param(TreeParameter::Calendar(Some(Calendar::new(3, 1))));
+    let mut strategy = StrategyRegistry::default().create("vase").unwrap();
+    strategy.set_parameter("trunk_height", 0.2).unwrap();
+    let auto_pruning = AutoPruning::new(strategy, PruningSchedule::EveryIteration).unwrap();
+    assert_eq!(auto_pruning.season(), PruningSeason::Winter);
+    simulation.update_tree_param(TreeParameter::AutoPruning(Some(auto_pruning)));
+
+    // the winter strategy does not prune while growing
+    let mut metamers = 0;
+    for _ in 0..3 {
+        simulation.perform_growth_iteration();
+        let count = simulation.plant().root().count_metamers();
+        assert!(count >= metamers);
+        metamers = count;
+        assert!(simulation.pruning_history().records().is_empty());
+    }
+    assert!(!simulation.plant().root().collect_leaves().is_empty());
+
+    // nothing grows in dormancy, the leaves fall and the winter strategy prunes
+    simulation.perform_growth_iteration();
+    assert!(simulation.plant().root().collect_leaves().is_empty());
+    let records = simulation.pruning_history().records();
+    assert!(!records.is_empty());
+    assert!(records.iter().all(|record| record.iteration == 4));
+    let removed: usize = records.iter().map(|record| record.removed_ids.len()).sum();
+    assert!(removed > 0);
+    assert_eq!(simulation.plant().root().count_metamers() as usize, metamers as usize - removed);
+
+    simulation.perform_growth_iteration();
+    let history = simulation.season_history();
+    let seasons: Vec<(u32, Season, u32)> = history
+        .records()
+        .iter()
+        .map(|record| (record.year, record.season, record.iterations))
+        .collect();
+    assert_eq!(seasons, vec![(1, Season::Growing, 3), (1, Season::Dormant, 1), (2, Season::Growing, 1)]);
+    assert!(history.records()[1].leaves_fallen > 0);
+
+    let parsed: SeasonHistory = history.to_string().parse().unwrap();
+    assert_eq!(&parsed, history);
+}
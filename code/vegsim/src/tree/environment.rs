@@ -1,12 +1,103 @@
+use std::{fmt::{self, Display}, str::FromStr};
+
+use patfile::{pscan, pwrite};
 use patutil::{Vecf3, Vecu3};
 
-use crate::{util::BoundingVolume, treeparameter::SpaceDividingMode, parameters};
+use crate::{util::{BoundingVolume, random::Random}, treeparameter::SpaceDividingMode, parameters};
 
 use super::{markerset::MarkerSet, shadowvoxelset::ShadowVoxelSet, PlantGenetics};
 
+/**
+ * Settings of the space a plant grows in, defaults are taken from parameters.rs
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvironmentParameters {
+    pub bounding_box_side: f32,
+    pub space_div_mode: SpaceDividingMode,
+    pub space_div_resolution: u32,
+    pub shadow_voxel_a: f32,
+    pub shadow_voxel_b: f32,
+    pub shadow_voxel_c: f32,
+    pub shadow_voxel_max_shadow: f32,
+    pub shadow_voxel_piramid_layers: i32,
+}
+
+impl EnvironmentParameters {
+    // bounding box with the plant seed in the middle of the bottom side
+    pub fn bounding_volume(&self) -> BoundingVolume {
+        let mut bounding_volume = BoundingVolume::new();
+        let size = self.bounding_box_side;
+        let min_p = Vecf3::new(-size / 2., 0., 0.);
+        let max_p = Vecf3::new(size / 2., size, size);
+        bounding_volume.include_point(min_p);
+        bounding_volume.include_point(max_p);
+        bounding_volume
+    }
+}
+
+impl Default for EnvironmentParameters {
+    fn default() -> Self {
+        Self {
+            bounding_box_side: parameters::BOUNDING_BOX_SIDE,
+            space_div_mode: parameters::SPACE_DIV_MODE,
+            space_div_resolution: parameters::SPACE_DIV_RESOLUTION,
+            shadow_voxel_a: parameters::SHADOW_VOXEL_A,
+            shadow_voxel_b: parameters::SHADOW_VOXEL_B,
+            shadow_voxel_c: parameters::SHADOW_VOXEL_C,
+            shadow_voxel_max_shadow: parameters::SHADOW_VOXEL_MAX_SHADOW,
+            shadow_voxel_piramid_layers: parameters::SHADOW_VOXEL_PIRAMID_LAYERS,
+        }
+    }
+}
+
+macro_rules! ENVIRONMENT_STRING_FORMAT {
+    () => {
+        "environment side{} spacemode{} resolution{} a{} b{} c{} maxshadow{} layers{}"
+    };
+}
+
+impl FromStr for EnvironmentParameters {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parameters = EnvironmentParameters::default();
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => ENVIRONMENT_STRING_FORMAT!(),
+            parameters.bounding_box_side,
+            parameters.space_div_mode,
+            parameters.space_div_resolution,
+            parameters.shadow_voxel_a,
+            parameters.shadow_voxel_b,
+            parameters.shadow_voxel_c,
+            parameters.shadow_voxel_max_shadow,
+            parameters.shadow_voxel_piramid_layers
+        )
+        .map_err(|_| ())?;
+
+        Ok(parameters)
+    }
+}
+
+impl Display for EnvironmentParameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(ENVIRONMENT_STRING_FORMAT!() => writer,
+            self.bounding_box_side,
+            self.space_div_mode,
+            self.space_div_resolution,
+            self.shadow_voxel_a,
+            self.shadow_voxel_b,
+            self.shadow_voxel_c,
+            self.shadow_voxel_max_shadow,
+            self.shadow_voxel_piramid_layers
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Environment {
-    tropism_growth_direction_weight: f32,
     markers: MarkerSet,
     shadowvoxels: ShadowVoxelSet,
     mode: SpaceDividingMode,
@@ -14,24 +105,17 @@ pub struct Environment {
 }
 
 impl Environment {
-    pub fn new(bounding_volume: BoundingVolume) -> Self {
-        let resolution = parameters::SPACE_DIV_RESOLUTION;
+    pub fn new(parameters: &EnvironmentParameters, random: &mut Random) -> Self {
+        let bounding_volume = parameters.bounding_volume();
+        let resolution = parameters.space_div_resolution;
         Self {
-            tropism_growth_direction_weight: parameters::TROPISM_START_WEIGTH,
-            markers: MarkerSet::new(bounding_volume, Vecu3::new(resolution, resolution, resolution)),
-            shadowvoxels: ShadowVoxelSet::new(bounding_volume, Vecu3::new(resolution, resolution, resolution)),
-            mode: parameters::SPACE_DIV_MODE,
+            markers: MarkerSet::new(bounding_volume, Vecu3::new(resolution, resolution, resolution), random),
+            shadowvoxels: ShadowVoxelSet::new(bounding_volume, Vecu3::new(resolution, resolution, resolution), parameters),
+            mode: parameters.space_div_mode,
             bounding_volume
         }
     }
 
-    pub fn increase_tropism(&mut self) {
-        self.tropism_growth_direction_weight *= parameters::TROPISM_CHANGE_RATE;
-    }
-    pub fn tropism_dir(&self) -> Vecf3{
-        parameters::TROPISM_DIR
-    }
-
     // return true if point is inside the environment
     pub fn is_inside(&self, point: Vecf3)-> bool{
         return self.bounding_volume.includes(point);
@@ -92,8 +176,17 @@ impl Environment {
         }
     }
 
-    pub const fn tropism_growth_direction_weight(&self) -> f32 {
-        return self.tropism_growth_direction_weight;
+    /**
+     * Direction of the light a shoot at the position bends towards, longer the more uneven the light is.
+     * The markers take the free space around the position as the light
+     */
+    pub fn light_direction(&self, pos: Vecf3, genetics: &PlantGenetics) -> Vecf3 {
+        let r = genetics.bud_perception_radius_factor();
+        match self.mode {
+            SpaceDividingMode::Markers => self.markers.free_space_dir_in_sphere(pos, r),
+            SpaceDividingMode::ShadowVoxels => self.shadowvoxels.light_direction(pos, r),
+            SpaceDividingMode::None => Vecf3::new(0., 0., 0.),
+        }
     }
 
     pub fn reset_space(&mut self) {
@@ -101,6 +194,10 @@ impl Environment {
         self.shadowvoxels.clear();
     }
 
+    pub fn markers(&self) -> &MarkerSet {
+        &self.markers
+    }
+
     pub fn markers_mut(&mut self) -> &mut MarkerSet {
         &mut self.markers
     }
@@ -0,0 +1,452 @@
+/*
+ * Flowering and fruit set: the terminal bud of a short lateral shoot (spur) turns into a flower bud
+ * once its metamer is old enough and the bud gets enough light. Flowers and fruits draw resources
+ * like a bud with the fruit sink times its light, so they compete with the vegetative buds of the plant.
+ * A flower that gets the set requirement sets a fruit, otherwise it drops and the bud grows on.
+ * Fruits grow from the resources they get until they are harvested at maturity,
+ * after the harvest the spur can flower again.
+ */
+mod tests;
+
+use std::{
+    collections::HashMap,
+    f32::consts::PI,
+    fmt::{self, Display},
+    fs::File,
+    io,
+    path::Path,
+    str::FromStr,
+};
+
+use patfile::{pscan, pwrite};
+use patutil::{Color, Vecf3};
+
+use crate::util::{meter_to_real_length, radius_to_width};
+
+use super::{
+    branchdata::BranchData,
+    budfate::BudState,
+    metamer::{IdCounter, Metamer},
+    plantgenetics::PlantGenetics,
+};
+
+// color of fruits in the viewer
+pub const FRUIT_COLOR: Color = Color::new(200, 30, 30, 255);
+// fruit density in kg/m3, to draw a fruit of the right size
+const FRUIT_DENSITY: f32 = 1000.;
+
+#[derive(Debug, Clone)]
+pub struct Fruit {
+    // hangs down from the node, its width follows the mass
+    data: BranchData,
+    // growth iterations since the fruit was set
+    age: u32,
+    // kg
+    mass: f32,
+}
+
+impl Fruit {
+    pub fn new(node: Vecf3, id: u32) -> Self {
+        let mut fruit = Self {
+            data: BranchData::new(node, node, 0., 0., FRUIT_COLOR, id),
+            age: 0,
+            mass: 0.,
+        };
+        fruit.update_data();
+        fruit
+    }
+
+    pub fn id(&self) -> u32 {
+        self.data.id()
+    }
+    pub fn data(&self) -> &BranchData {
+        &self.data
+    }
+    pub fn age(&self) -> u32 {
+        self.age
+    }
+    pub fn mass(&self) -> f32 {
+        self.mass
+    }
+
+    // radius in m of a round fruit with the mass
+    pub fn radius(&self) -> f32 {
+        (3. * self.mass / (4. * PI * FRUIT_DENSITY)).cbrt()
+    }
+
+    pub fn grow(&mut self, mass: f32) {
+        self.age += 1;
+        self.mass += mass;
+        self.update_data();
+    }
+
+    // move the fruit with its node, e.g. after a cut
+    pub fn set_node(&mut self, node: Vecf3) {
+        self.data.set_start_point(node);
+        self.update_data();
+    }
+
+    fn update_data(&mut self) {
+        let radius = meter_to_real_length(self.radius()).max(0.01);
+        let node = self.data.begin_point();
+        self.data.set_end_point(node - Vecf3::new(0., 2. * radius, 0.));
+        self.data.set_start_width(radius_to_width(radius));
+        self.data.set_end_width(radius_to_width(radius));
+    }
+}
+
+macro_rules! FRUIT_STRING_FORMAT {
+    () => {
+        "fruit data{} age{} mass{}"
+    };
+}
+
+impl FromStr for Fruit {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fruit = Self {
+            data: BranchData::new(Vecf3::default(), Vecf3::default(), 0., 0., FRUIT_COLOR, 0),
+            age: 0,
+            mass: 0.,
+        };
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => FRUIT_STRING_FORMAT!(), fruit.data, fruit.age, fruit.mass).map_err(|_| ())?;
+        Ok(fruit)
+    }
+}
+
+impl Display for Fruit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(FRUIT_STRING_FORMAT!() => writer, &self.data, self.age, self.mass).map_err(|_| fmt::Error)
+    }
+}
+
+/**
+ * Fruit load of the plant after one growth iteration
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FruitStats {
+    // growth iterations grown, including this one
+    pub iteration: i32,
+    // flower buds initiated in the iteration
+    pub initiated: u32,
+    // fruits set in the iteration
+    pub set: u32,
+    // flowers dropped without setting a fruit
+    pub dropped: u32,
+    // flowers waiting for resources and fruits on the plant after the iteration
+    pub flowers: u32,
+    pub fruits: u32,
+    // kg of the fruits on the plant
+    pub mass: f32,
+    // fruits picked at maturity in the iteration and their kg
+    pub harvested: u32,
+    pub harvested_mass: f32,
+}
+
+macro_rules! FRUIT_STATS_STRING_FORMAT {
+    () => {
+        "iteration{} initiated{} set{} dropped{} flowers{} fruits{} mass{} harvested{} yield{}"
+    };
+}
+
+impl FromStr for FruitStats {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut stats = Self::default();
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => FRUIT_STATS_STRING_FORMAT!(),
+            stats.iteration,
+            stats.initiated,
+            stats.set,
+            stats.dropped,
+            stats.flowers,
+            stats.fruits,
+            stats.mass,
+            stats.harvested,
+            stats.harvested_mass
+        )
+        .map_err(|_| ())?;
+        Ok(stats)
+    }
+}
+
+impl Display for FruitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(FRUIT_STATS_STRING_FORMAT!() => writer,
+            self.iteration,
+            self.initiated,
+            self.set,
+            self.dropped,
+            self.flowers,
+            self.fruits,
+            self.mass,
+            self.harvested,
+            self.harvested_mass
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
+
+/**
+ * Fruit load of every growth iteration of a simulation in order
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FruitHistory {
+    records: Vec<FruitStats>,
+}
+
+impl FruitHistory {
+    pub fn records(&self) -> &[FruitStats] {
+        &self.records
+    }
+
+    pub fn last(&self) -> Option<&FruitStats> {
+        self.records.last()
+    }
+
+    pub fn push(&mut self, stats: FruitStats) {
+        self.records.push(stats);
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    // fruits and kg harvested over the whole simulation
+    pub fn total_yield(&self) -> (u32, f32) {
+        self.records
+            .iter()
+            .fold((0, 0.), |(fruits, mass), stats| (fruits + stats.harvested, mass + stats.harvested_mass))
+    }
+
+    pub fn save_csv(&self, path: &Path) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        self.write_csv(&mut file)
+    }
+
+    // one line per growth iteration
+    pub fn write_csv(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "iteration,flowers_initiated,fruits_set,flowers_dropped,flowers,fruits,fruit_mass_kg,harvested,yield_kg"
+        )?;
+        for stats in &self.records {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{}",
+                stats.iteration,
+                stats.initiated,
+                stats.set,
+                stats.dropped,
+                stats.flowers,
+                stats.fruits,
+                stats.mass,
+                stats.harvested,
+                stats.harvested_mass
+            )?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! FRUIT_HISTORY_STRING_FORMAT {
+    () => {
+        "load{}"
+    };
+}
+
+impl FromStr for FruitHistory {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut records = vec![];
+        let mut it = s.bytes().into_iter().peekable();
+        while it.peek().is_some() {
+            let mut stats = FruitStats::default();
+            pscan!(&mut it => FRUIT_HISTORY_STRING_FORMAT!(), stats).map_err(|_| ())?;
+            records.push(stats);
+            // records are separated by a space
+            it.next();
+        }
+        Ok(Self { records })
+    }
+}
+
+impl Display for FruitHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let records: Vec<String> = self.records.iter().map(|stats| stats.to_string()).collect();
+        for (i, record) in records.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            let writer: &mut dyn std::fmt::Write = f;
+            pwrite!(FRUIT_HISTORY_STRING_FORMAT!() => writer, record).map_err(|_| fmt::Error)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Flowering {
+    // longest lateral shoot in metamers that flowers, 0 switches flowering off
+    spur_length: u32,
+    flower_age: u32,
+    flower_light: f32,
+    sink: f32,
+    set_requirement: f32,
+    growth: f32,
+    maturity: u32,
+}
+
+impl Flowering {
+    pub fn new(genetics: &PlantGenetics) -> Self {
+        Self {
+            spur_length: genetics.spur_length(),
+            flower_age: genetics.flower_age(),
+            flower_light: genetics.flower_light(),
+            sink: genetics.fruit_sink(),
+            set_requirement: genetics.fruit_set_requirement(),
+            growth: genetics.fruit_growth(),
+            maturity: genetics.fruit_maturity(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.spur_length > 0
+    }
+
+    // @returns true when the tip of the metamer carries a flower or a fruit
+    pub fn has_flower(metamer: &Metamer) -> bool {
+        metamer.terminal_metamer().is_none()
+            && metamer.terminal_bud_damage == 0.
+            && metamer.terminal_bud_fate.state() == BudState::Flower
+    }
+
+    /**
+     * Light that flowers and fruits draw resources with on top of their own light,
+     * by the id of the first metamer of every branch holding flowers and by the terminal bud id of every flower
+     * @returns the extra light of the metamer and all its child metamers
+     */
+    pub fn fruit_demand(&self, metamer: &Metamer, demand: &mut HashMap<u32, f32>) -> f32 {
+        let mut extra = 0.;
+        match metamer.terminal_metamer() {
+            Some(m) => extra += self.fruit_demand(m, demand),
+            None if Self::has_flower(metamer) => {
+                let tip = metamer.last_terminal_light_generated * (self.sink - 1.);
+                demand.insert(metamer.terminal_bud_data.id(), tip);
+                extra += tip;
+            }
+            None => {}
+        }
+        if let Some(m) = metamer.auxillary_metamer() {
+            extra += self.fruit_demand(m, demand);
+        }
+        for m in metamer.extra_buds().iter().filter_map(|bud| bud.metamer()) {
+            extra += self.fruit_demand(m, demand);
+        }
+
+        if extra != 0. {
+            demand.insert(metamer.id(), extra);
+        }
+        extra
+    }
+
+    /**
+     * Set and grow fruits from the resources the flowers got and harvest ripe fruits,
+     * then let the tips of spurs flower. Adds what happened to the stats.
+     * @param shoot_length metamers from the base of the shoot up to and including this metamer
+     * @param lateral false on the trunk, which never flowers
+     */
+    pub fn update(&self, metamer: &mut Metamer, shoot_length: u32, lateral: bool, ids: &mut IdCounter, stats: &mut FruitStats) {
+        if let Some(m) = metamer.terminal_metamer_mut() {
+            self.update(m, shoot_length + 1, lateral, ids, stats);
+        } else if Self::has_flower(metamer) {
+            self.update_flower(metamer, ids, stats);
+        } else if lateral && self.initiates(metamer, shoot_length) {
+            metamer.terminal_bud_fate.set_state(BudState::Flower);
+            metamer.terminal_bud_data.set_color(BudState::Flower.color());
+            stats.initiated += 1;
+            stats.flowers += 1;
+        }
+
+        if let Some(m) = metamer.auxillary_metamer_mut() {
+            self.update(m, 1, true, ids, stats);
+        }
+        for m in metamer.extra_buds_mut().iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            self.update(m, 1, true, ids, stats);
+        }
+    }
+
+    // pick every fruit and let flowers without a fruit drop, e.g. when the plant goes dormant
+    pub fn harvest(&self, metamer: &mut Metamer, stats: &mut FruitStats) {
+        if let Some(m) = metamer.terminal_metamer_mut() {
+            self.harvest(m, stats);
+        } else if Self::has_flower(metamer) {
+            match metamer.fruit.take() {
+                Some(fruit) => {
+                    stats.harvested += 1;
+                    stats.harvested_mass += fruit.mass();
+                }
+                None => stats.dropped += 1,
+            }
+            Self::reset_bud(metamer);
+        }
+
+        if let Some(m) = metamer.auxillary_metamer_mut() {
+            self.harvest(m, stats);
+        }
+        for m in metamer.extra_buds_mut().iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            self.harvest(m, stats);
+        }
+    }
+
+    // the terminal bud of a short shoot flowers when it is old enough and gets enough light
+    fn initiates(&self, metamer: &Metamer, shoot_length: u32) -> bool {
+        shoot_length <= self.spur_length
+            && metamer.terminal_bud_damage == 0.
+            && metamer.terminal_bud_fate.can_grow()
+            && metamer.age >= self.flower_age
+            && metamer.last_terminal_light_generated >= self.flower_light
+    }
+
+    fn update_flower(&self, metamer: &mut Metamer, ids: &mut IdCounter, stats: &mut FruitStats) {
+        let resources = metamer.last_terminal_resources;
+        let node = metamer.end_point();
+        match &mut metamer.fruit {
+            Some(fruit) => fruit.grow(resources * self.growth),
+            None if resources >= self.set_requirement => {
+                let mut fruit = Fruit::new(node, ids.get_id());
+                fruit.grow(resources * self.growth);
+                metamer.fruit = Some(fruit);
+                stats.set += 1;
+            }
+            None => {
+                // the flower drops, the bud grows on like a vegetative bud
+                Self::reset_bud(metamer);
+                stats.dropped += 1;
+                return;
+            }
+        }
+
+        let fruit = metamer.fruit.as_ref().unwrap();
+        if fruit.age() >= self.maturity {
+            stats.harvested += 1;
+            stats.harvested_mass += fruit.mass();
+            metamer.fruit = None;
+            Self::reset_bud(metamer);
+        } else {
+            stats.fruits += 1;
+            stats.mass += fruit.mass();
+        }
+    }
+
+    fn reset_bud(metamer: &mut Metamer) {
+        metamer.terminal_bud_fate.set_state(BudState::Active);
+        metamer.terminal_bud_data.set_color(BudState::Active.color());
+    }
+}
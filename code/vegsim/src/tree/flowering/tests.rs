@@ -0,0 +1,61 @@
+#![cfg(test)]
+
+use crate::{
+    tree::{budfate::BudState, Simulation},
+    treeparameter::GeneticParameter,
+};
+
+use super::{FruitHistory, FruitStats};
+
+#[test]
+fn test_spurs_flower_and_fruit() {
+    let mut simulation = Simulation::with_params(
+        &[
+            GeneticParameter::SpurLength(3.),
+            GeneticParameter::FlowerAge(1.),
+            GeneticParameter::FlowerLight(0.),
+        ],
+        1,
+    );
+    for _ in 0..12 {
+        simulation.perform_growth_iteration();
+    }
+
+    let history = simulation.fruit_history();
+    assert_eq!(history.records().len(), 12);
+    let initiated: u32 = history.records().iter().map(|stats| stats.initiated).sum();
+    let set: u32 = history.records().iter().map(|stats| stats.set).sum();
+    assert!(initiated > 0);
+    assert!(set > 0);
+    let (harvested, mass) = history.total_yield();
+    assert!(harvested > 0);
+    assert!(mass > 0.);
+
+    // the trunk never flowers
+    let mut metamer = simulation.plant().root();
+    while let Some(m) = metamer.terminal_metamer() {
+        metamer = m;
+    }
+    assert_ne!(metamer.terminal_bud_fate.state(), BudState::Flower);
+}
+
+#[test]
+fn test_fruit_history_format() {
+    let mut history = FruitHistory::default();
+    for iteration in 1..3 {
+        history.push(FruitStats {
+            iteration,
+            initiated: 2,
+            set: 1,
+            fruits: 3,
+            mass: 0.25,
+            harvested: 1,
+            harvested_mass: 0.5,
+            ..Default::default()
+        });
+    }
+
+    let parsed: FruitHistory = history.to_string().parse().unwrap();
+    assert_eq!(parsed, history);
+    assert_eq!(history.total_yield(), (2, 1.));
+}
@@ -0,0 +1,203 @@
+/*
+ * Leaves grow on the node at the end of a metamer, one for every bud of the node.
+ * The blade points away from the axis on the side of its bud, at the leaf angle from the axis.
+ * Leaves gather light and cast shade in the shadow voxel space, with markers a leaf claims the markers
+ * in the perception cone above its blade like a bud does.
+ * The light of the leaves of a node feeds the axillary buds of the node.
+ * A leaf falls off when it reaches its lifespan or when it gathers less light than the abscission light.
+ */
+mod tests;
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use patfile::{pscan, pwrite};
+use patutil::{Color, Vecf3};
+
+use crate::{
+    treeparameter::SpaceDividingMode,
+    util::{meter_to_real_length, rot_vec_around_axis},
+};
+
+use super::{environment::Environment, plantgenetics::PlantGenetics};
+
+// color of leaves in the viewer
+pub const LEAF_COLOR: Color = Color::new(60, 150, 40, 255);
+// width of the blade compared to its length
+pub const LEAF_WIDTH_RATIO: f32 = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leaf {
+    id: u32,
+    // node the leaf is attached to
+    base: Vecf3,
+    // along the blade from the base to the tip
+    direction: Vecf3,
+    // upper side of the blade
+    normal: Vecf3,
+    size: f32,
+    age: u32,
+    pub last_light_generated: f32,
+}
+
+impl Leaf {
+    /**
+     * @param axis direction of the metamer the leaf grows on
+     * @param bud_direction direction of the bud the leaf belongs to
+     */
+    pub fn new(base: Vecf3, axis: Vecf3, bud_direction: Vecf3, genetics: &PlantGenetics, id: u32) -> Self {
+        // side of the axis the bud is on, buds along the axis get any side
+        let mut side = bud_direction - axis * (bud_direction * axis);
+        if side.length() < 1.0e-4 {
+            side = axis.cross(Vecf3::new(1., 0., 0.));
+            if side.length() < 1.0e-4 {
+                side = axis.cross(Vecf3::new(0., 0., 1.));
+            }
+        }
+        let side = side.norm();
+
+        let angle = genetics.leaf_angle();
+        let direction = (axis * angle.cos() + side * angle.sin()).norm();
+        let mut normal = direction.cross(axis.cross(side)).norm();
+        if normal.y < 0. {
+            normal = normal * -1.;
+        }
+
+        Self {
+            id,
+            base,
+            direction,
+            normal,
+            size: genetics.leaf_size(),
+            age: 0,
+            last_light_generated: 0.,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn base(&self) -> Vecf3 {
+        self.base
+    }
+    pub fn direction(&self) -> Vecf3 {
+        self.direction
+    }
+    pub fn normal(&self) -> Vecf3 {
+        self.normal
+    }
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+    pub fn age(&self) -> u32 {
+        self.age
+    }
+
+    pub fn center(&self) -> Vecf3 {
+        self.base + self.direction * (self.size / 2.)
+    }
+
+    pub fn increase_age(&mut self) {
+        self.age += 1;
+    }
+
+    // turn the leaf with the branch it grows on
+    pub fn rotate(&mut self, pivot: Vecf3, axis: Vecf3, radians: f32) {
+        self.base = pivot + rot_vec_around_axis(&(self.base - pivot), &axis, radians);
+        self.direction = rot_vec_around_axis(&self.direction, &axis, radians).norm();
+        self.normal = rot_vec_around_axis(&self.normal, &axis, radians).norm();
+        if self.normal.y < 0. {
+            self.normal = self.normal * -1.;
+        }
+    }
+
+    // shade and light grow with the square of the leaf size, a leaf as long as a metamer counts like a metamer
+    pub fn area(&self, genetics: &PlantGenetics) -> f32 {
+        let relative = self.size / genetics.metamer_base_length();
+        relative * relative
+    }
+
+    // area of the blade in m2
+    pub fn blade_area(&self) -> f32 {
+        let length = self.size / meter_to_real_length(1.);
+        length * length * LEAF_WIDTH_RATIO
+    }
+
+    /**
+     * Light falling on the blade, with shadow voxels leaves facing up get the most light
+     * @returns the light gathered
+     */
+    pub fn calc_light_gathered(&mut self, environment: &Environment, genetics: &PlantGenetics) -> f32 {
+        let light = match environment.mode() {
+            SpaceDividingMode::ShadowVoxels => {
+                environment.shadowvoxels().get_light_exposure(self.center()) * self.normal.y
+            }
+            SpaceDividingMode::Markers => {
+                environment.calc_light_gathered(self.center(), genetics, self.id, self.size, self.normal)
+            }
+            SpaceDividingMode::None => 0.,
+        };
+        self.last_light_generated = light * self.area(genetics) * genetics.leaf_efficiency();
+        self.last_light_generated
+    }
+
+    // @returns true when the leaf is too old or gathers too little light
+    pub fn falls(&self, genetics: &PlantGenetics) -> bool {
+        let lifespan = genetics.leaf_lifespan();
+        let abscission = genetics.leaf_abscission_light();
+        (lifespan > 0 && self.age >= lifespan) || (abscission > 0. && self.last_light_generated < abscission)
+    }
+}
+
+macro_rules! LEAF_STRING_FORMAT {
+    () => {
+        "leaf id{} base{} dir{} normal{} size{} age{} light{}"
+    };
+}
+
+impl FromStr for Leaf {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut leaf = Self {
+            id: 0,
+            base: Vecf3::default(),
+            direction: Vecf3::default(),
+            normal: Vecf3::default(),
+            size: 0.,
+            age: 0,
+            last_light_generated: 0.,
+        };
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => LEAF_STRING_FORMAT!(),
+            leaf.id,
+            leaf.base,
+            leaf.direction,
+            leaf.normal,
+            leaf.size,
+            leaf.age,
+            leaf.last_light_generated
+        )
+        .map_err(|_| ())?;
+        Ok(leaf)
+    }
+}
+
+impl Display for Leaf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(LEAF_STRING_FORMAT!() => writer,
+            self.id,
+            self.base,
+            self.direction,
+            self.normal,
+            self.size,
+            self.age,
+            self.last_light_generated
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use crate::{
+    profile::ParameterProfile,
+    tree::{resourcedistibutor::ResourceDistibutor, Simulation},
+    treeparameter::{GeneticParameter, SpaceDividingMode},
+};
+
+#[test]
+fn test_leaves_gather_light() {
+    let mut bare = Simulation::with_seed(1);
+    let mut leafy = Simulation::with_params(&[GeneticParameter::LeafSize(0.3)], 1);
+    bare.perform_growth_iteration();
+    leafy.perform_growth_iteration();
+
+    // every node gets a leaf facing up that adds to the light of the plant
+    let root = leafy.plant().root();
+    assert_eq!(root.leaves().len(), 1);
+    assert!(root.leaves()[0].normal().y > 0.);
+    assert!(root.leaves()[0].last_light_generated > 0.);
+    assert!(root.last_light_generated() > bare.plant().root().last_light_generated());
+}
+
+#[test]
+fn test_leaves_fall() {
+    let mut simulation = Simulation::with_params(&[GeneticParameter::LeafSize(0.3), GeneticParameter::LeafLifespan(2.)], 1);
+    for _ in 0..5 {
+        simulation.perform_growth_iteration();
+    }
+
+    // only leaves of the last two iterations are left
+    let leaves = simulation.plant().root().collect_leaves();
+    assert!(!leaves.is_empty());
+    assert!(leaves.iter().all(|leaf| leaf.age() < 2));
+    assert!(simulation.plant().root().leaves().is_empty());
+}
+
+#[test]
+fn test_leaves_with_markers() {
+    let mut profile = ParameterProfile::default();
+    profile.genetics.update_param(GeneticParameter::LeafSize(0.3));
+    profile.environment.space_div_mode = SpaceDividingMode::Markers;
+    let mut simulation = Simulation::with_profile(profile, 1);
+    for _ in 0..3 {
+        simulation.perform_growth_iteration();
+    }
+
+    // leaves claim markers above their blade like buds
+    let leaves = simulation.plant().root().collect_leaves();
+    assert!(!leaves.is_empty());
+    assert!(leaves.iter().any(|leaf| leaf.last_light_generated > 0.));
+}
+
+#[test]
+fn test_leaves_feed_axillary_buds() {
+    let mut simulation = Simulation::with_params(&[GeneticParameter::LeafSize(0.3)], 1);
+    simulation.perform_growth_iteration();
+    let root = simulation.plant_mut().root_mut();
+    assert!(root.leaf_light() > 0.);
+    root.distribute_resources(&ResourceDistibutor::new(), 10.);
+
+    // the light of the leaves of a node counts on the lateral side of the Borchert-Honda split
+    let lambda = root.genetics.lock().unwrap().borchert_honda_lambda();
+    let q_m = root.last_terminal_light_generated;
+    let q_l = root.lateral_light() + root.leaf_light();
+    let expected = 10. * (1. - lambda) * q_l / (lambda * q_m + (1. - lambda) * q_l);
+    assert!((root.last_aux_resources - expected).abs() < 1.0e-4);
+}
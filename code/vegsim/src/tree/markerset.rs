@@ -31,7 +31,7 @@ pub struct MarkerSet {
 }
 
 impl MarkerSet {
-    pub fn new(bounding_volume: BoundingVolume, resolution: Vecu3) -> Self {
+    pub fn new(bounding_volume: BoundingVolume, resolution: Vecu3, random: &mut Random) -> Self {
         let mut markers = Vec::new();
 
         let step = bounding_volume.interpolate(Vecu3::new(1,1,1), resolution)
@@ -43,9 +43,9 @@ impl MarkerSet {
                     let mut pos = bounding_volume.interpolate(Vecu3::new(x, y, z), resolution);
 
                     // add random offset on positons
-                    pos.x += step.x*Random::rand();
-                    pos.y += step.y*Random::rand();
-                    pos.z += step.z*Random::rand();
+                    pos.x += step.x*random.rand();
+                    pos.y += step.y*random.rand();
+                    pos.z += step.z*random.rand();
 
                     markers.push(Marker::new(pos));
                 }
@@ -126,6 +126,27 @@ impl MarkerSet {
         return Some(marker_dir.norm());
     }
 
+    /**
+     * Direction of the free space around the point, the average of the directions to the markers in the sphere
+     * no metamer occupies. Its length grows with the difference in free space around the point,
+     * @returns the zero vector when there are no markers around
+     */
+    pub fn free_space_dir_in_sphere(&self, point: Vecf3, r: f32) -> Vecf3 {
+        let markers = self.get_markers_in_sphere(point, r);
+        if markers.is_empty() {
+            return Vecf3::new(0., 0., 0.);
+        }
+
+        let mut free_dir = Vecf3::new(0., 0., 0.);
+        for marker in markers.iter() {
+            let dir = marker.position - point;
+            if marker.claimed_bud != Some(0) && dir.length() > 1.0e-6 {
+                free_dir += dir.norm();
+            }
+        }
+        free_dir / markers.len() as f32
+    }
+
     fn get_markers_in_cone(&self, point: Vecf3, dir: Vecf3, theta: f32, r: f32) -> Vec<&Marker>{
         let sphere_markers = self.get_markers_in_sphere(point, r);
 
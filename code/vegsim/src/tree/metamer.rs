@@ -1,21 +1,111 @@
 use std::{
+    fmt::{self, Display},
     ops::{Deref, DerefMut},
+    str::FromStr,
     sync::{Arc, Mutex},
 };
 
+use patfile::{pscan, pwrite};
 use patutil::{Color, Vecf3};
 
-use crate::{util::{BoundingVolume, random::Random}, parameters};
+use crate::util::{rot_vec_around_axis, BoundingVolume, random::Random};
 
 use super::{
-    branchdata::BranchData, environment::Environment, markerset::MarkerSet,
+    apicalcontrol::ApicalControl, branchdata::BranchData, budfate::{BudFate, BudState, BudTriggers}, environment::Environment, flowering::Fruit, leaf::Leaf, markerset::MarkerSet,
     resourcedistibutor::ResourceDistibutor, shadowvoxelset::ShadowVoxelSet,
-    support_pole::SupportPole, PlantGenetics,
+    snapshot::SnapshotOption, support_pole::SupportPole, tropism::Tropism, PlantGenetics,
 };
 
-static ID_COUNTER: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(2);
-fn get_id() -> u32 {
-    ID_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+/**
+ * Hands out the ids of the metamers, buds, leaves, fruits and poles of one plant,
+ * every plant counts on its own so two simulations never share or skip ids
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdCounter {
+    next: u32,
+}
+
+impl IdCounter {
+    // the root metamer has id 1
+    pub const fn new() -> Self {
+        Self { next: 2 }
+    }
+
+    pub fn get_id(&mut self) -> u32 {
+        let id = self.next;
+        self.next += 1;
+        id
+    }
+
+    // @returns the id the next created part will get
+    pub fn next_id(&self) -> u32 {
+        self.next
+    }
+
+    pub fn set_next_id(&mut self, id: u32) {
+        self.next = id;
+    }
+}
+
+impl Default for IdCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// shortest part of a metamer that is left after a cut
+pub const MIN_CUT_FRACTION: f32 = 0.1;
+
+/**
+ * Axillary bud at the same node as the first axillary bud of a metamer,
+ * nodes get more than one bud with opposite, decussate or whorled phyllotaxis
+ */
+#[derive(Debug, Clone)]
+pub struct ExtraBud {
+    metamer: Option<Box<Metamer>>,
+    pub direction: Vecf3,
+    pub bud_data: BranchData,
+    pub damage: f32,
+    pub fate: BudFate,
+    pub last_light_generated: f32,
+    pub last_resources: f32,
+}
+
+impl ExtraBud {
+    fn new(point: Vecf3, direction: Vecf3, id: u32) -> Self {
+        Self {
+            metamer: None,
+            direction,
+            bud_data: BranchData::new(
+                point,
+                point + direction * 0.05,
+                0.0001,
+                0.0001,
+                Color::new(255, 0, 0, 255),
+                id,
+            ),
+            damage: 0.,
+            fate: BudFate::new(),
+            last_light_generated: 0.,
+            last_resources: 0.,
+        }
+    }
+
+    pub fn metamer(&self) -> Option<&Metamer> {
+        self.metamer.as_ref().map(|v| v.as_ref())
+    }
+    pub fn metamer_mut(&mut self) -> Option<&mut Metamer> {
+        self.metamer.as_mut().map(|v| v.as_mut())
+    }
+    pub fn set_metamer(&mut self, metamer: Option<Metamer>) {
+        self.metamer = metamer.map(Box::new);
+    }
+
+    pub fn prune(&mut self) {
+        self.metamer = None;
+        self.damage = 1.;
+        self.fate.prune();
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +115,17 @@ pub struct Metamer {
     pub last_light_generated: f32,
     pub support_pole: Option<SupportPole>,
     pub aux_support_pole: Option<SupportPole>,
+    // growth iterations since the metamer was created
+    pub age: u32,
+    // auxin passing the end of the metamer in the last growth iteration, calculated again every iteration
+    pub auxin: f32,
+    // rotation around its start the metamer and everything above it got from their load, axis times angle in radians
+    pub bending: Vecf3,
+    // rotation of the first axillary bud around the axis in radians, the next node adds the divergence angle
+    phyllotaxis_angle: f32,
+    extra_buds: Vec<ExtraBud>,
+    // leaves on the node at the end of the metamer, one for every bud until they fall off
+    leaves: Vec<Leaf>,
 
     // terminal variables
     terminal_metamer: Option<Box<Metamer>>,
@@ -32,6 +133,9 @@ pub struct Metamer {
     pub last_terminal_resources: f32,
     pub terminal_bud_data: BranchData,
     pub terminal_bud_damage: f32,
+    pub terminal_bud_fate: BudFate,
+    // fruit set by a flower on the terminal bud
+    pub fruit: Option<Fruit>,
 
     // auxillary variables
     auxillary_metamer: Option<Box<Metamer>>,
@@ -40,6 +144,7 @@ pub struct Metamer {
     pub last_aux_resources: f32,
     pub aux_bud_data: BranchData,
     pub auxillary_bud_damage: f32,
+    pub aux_bud_fate: BudFate,
 }
 
 impl Metamer {
@@ -49,11 +154,76 @@ impl Metamer {
         genetics: Arc<Mutex<PlantGenetics>>,
         metamer_id: u32,
         support_pole: Option<SupportPole>,
+        phyllotaxis_angle: f32,
+        ids: &mut IdCounter,
+        random: &mut Random,
     ) -> Self {
         let dir = (end_point - start_point).norm();
-        let auxillary_direction =
-            Self::random_perturbation(dir, genetics.lock().unwrap().axillary_perturbation_angle());
+        let (buds, branching_angle, perturbation_angle, leaf_size) = {
+            let genetics = genetics.lock().unwrap();
+            (
+                genetics.buds_per_node(),
+                genetics.branching_angle(),
+                genetics.axillary_perturbation_angle(),
+                genetics.leaf_size(),
+            )
+        };
+
+        // without a branching angle the buds are placed randomly close to the axis and the divergence angle has no effect
+        let directions: Vec<Vecf3> = if branching_angle > 0. {
+            Self::node_bud_directions(dir, phyllotaxis_angle, branching_angle, buds)
+        } else {
+            (0..buds).map(|_| Self::random_perturbation(dir, perturbation_angle, random)).collect()
+        };
+
+        let mut metamer =
+            Self::with_auxillary_direction(start_point, end_point, genetics, metamer_id, support_pole, directions[0], ids);
+        metamer.phyllotaxis_angle = phyllotaxis_angle;
+        metamer.extra_buds = directions[1..]
+            .iter()
+            .map(|direction| ExtraBud::new(end_point, *direction, ids.get_id()))
+            .collect();
+        if leaf_size > 0. {
+            let genetics = metamer.genetics.lock().unwrap().clone();
+            metamer.leaves = directions
+                .iter()
+                .map(|direction| Leaf::new(end_point, dir, *direction, &genetics, ids.get_id()))
+                .collect();
+        }
+        metamer
+    }
+
+    /**
+     * Directions of the buds of a node spread evenly around the axis
+     * @param angle rotation of the first bud around the axis in radians
+     */
+    pub fn node_bud_directions(axis: Vecf3, angle: f32, branching_angle: f32, buds: usize) -> Vec<Vecf3> {
+        let reference = if axis.x.abs() > 1.0 - 1.0e-6 {
+            Vecf3::new(0., 1., 0.)
+        } else {
+            Vecf3::new(1., 0., 0.)
+        };
+        let u = axis.cross(reference).norm();
+        let v = axis.cross(u).norm();
+
+        (0..buds)
+            .map(|i| {
+                let angle = angle + 2. * std::f32::consts::PI * i as f32 / buds as f32;
+                let side = u * angle.cos() + v * angle.sin();
+                (axis * branching_angle.cos() + side * branching_angle.sin()).norm()
+            })
+            .collect()
+    }
 
+    pub fn with_auxillary_direction(
+        start_point: Vecf3,
+        end_point: Vecf3,
+        genetics: Arc<Mutex<PlantGenetics>>,
+        metamer_id: u32,
+        support_pole: Option<SupportPole>,
+        auxillary_direction: Vecf3,
+        ids: &mut IdCounter,
+    ) -> Self {
         Self {
             branch_data: BranchData::new(
                 start_point,
@@ -78,7 +248,7 @@ impl Metamer {
                 0.0001,
                 0.0001,
                 Color::new(255, 0, 0, 255),
-                get_id(),
+                ids.get_id(),
             ),
             aux_bud_data: BranchData::new(
                 end_point,
@@ -86,38 +256,56 @@ impl Metamer {
                 0.0001,
                 0.0001,
                 Color::new(255, 0, 0, 255),
-                get_id(),
+                ids.get_id(),
             ),
             terminal_bud_damage: 0.,
             auxillary_bud_damage: 0.,
+            terminal_bud_fate: BudFate::new(),
+            aux_bud_fate: BudFate::new(),
+            fruit: None,
             support_pole,
-            aux_support_pole: None
+            aux_support_pole: None,
+            age: 0,
+            auxin: 0.,
+            bending: Vecf3::new(0., 0., 0.),
+            phyllotaxis_angle: 0.,
+            extra_buds: vec![],
+            leaves: vec![],
         }
     }
 
-    pub fn collect_branchdata(&mut self) -> Vec<&mut BranchData> {
+    pub fn collect_branchdata(&self) -> Vec<&BranchData> {
         let mut result = vec![];
-        result.push(&mut self.branch_data);
+        result.push(&self.branch_data);
 
-        if let Some(metamer) = &mut self.terminal_metamer {
+        if let Some(metamer) = &self.terminal_metamer {
             result.append(&mut metamer.collect_branchdata());
         } else {
-            result.push(&mut self.terminal_bud_data);
+            result.push(&self.terminal_bud_data);
+            if let Some(fruit) = &self.fruit {
+                result.push(fruit.data());
+            }
         }
-        if let Some(metamer) = &mut self.auxillary_metamer {
+        if let Some(metamer) = &self.auxillary_metamer {
             result.append(&mut metamer.collect_branchdata());
         } else {
-            result.push(&mut self.aux_bud_data);
-            if let Some(pole) = &mut self.aux_support_pole {
+            result.push(&self.aux_bud_data);
+            if let Some(pole) = &self.aux_support_pole {
                 if pole.visible(){
-                    result.push(pole.model_mut());
+                    result.push(pole.model());
                 }
             }
         }
+        for bud in &self.extra_buds {
+            match &bud.metamer {
+                Some(metamer) => result.append(&mut metamer.collect_branchdata()),
+                None => result.push(&bud.bud_data),
+            }
+        }
 
-        if let Some(pole) = &mut self.support_pole {
+        if let Some(pole) = &self.support_pole {
             if pole.visible(){
-                result.push(pole.model_mut());
+                result.push(pole.model());
             }
         }
 
@@ -132,6 +320,9 @@ impl Metamer {
         if let Some(metamer) = &self.auxillary_metamer {
             metamer.remove_markers_on_buds(markers);
         }
+        for metamer in self.extra_buds.iter().filter_map(|bud| bud.metamer()) {
+            metamer.remove_markers_on_buds(markers);
+        }
     }
 
     pub fn place_markers(&self, markers: &mut MarkerSet) -> u32 {
@@ -165,17 +356,38 @@ impl Metamer {
             );
         }
 
+        for bud in &self.extra_buds {
+            total += match &bud.metamer {
+                Some(metamer) => metamer.place_markers(markers),
+                None => markers.set_markers_in_cone(bud.bud_data.id(), self.end_point(), bud.direction, theta, r),
+            };
+        }
+
+        // leaves claim the space above their blade
+        for leaf in &self.leaves {
+            total += markers.set_markers_in_cone(leaf.id(), leaf.center(), leaf.normal(), theta, r);
+        }
+
         total
     }
 
     pub fn place_shadows(&self, shadowvoxels: &mut ShadowVoxelSet) {
         shadowvoxels.add_shadow(self.end_point());
+        if !self.leaves.is_empty() {
+            let genetics = self.genetics.lock().unwrap();
+            for leaf in &self.leaves {
+                shadowvoxels.add_shade(leaf.center(), leaf.area(&genetics));
+            }
+        }
         if let Some(metamer) = &self.terminal_metamer {
             metamer.place_shadows(shadowvoxels);
         }
         if let Some(metamer) = &self.auxillary_metamer {
             metamer.place_shadows(shadowvoxels);
         }
+        for metamer in self.extra_buds.iter().filter_map(|bud| bud.metamer()) {
+            metamer.place_shadows(shadowvoxels);
+        }
     }
 
     /**
@@ -208,12 +420,49 @@ impl Metamer {
             );
         }
 
+        // calc light of the other buds of the node
+        let (end_point, length) = (self.end_point(), self.length());
+        for bud in &mut self.extra_buds {
+            bud.last_light_generated = match &mut bud.metamer {
+                Some(metamer) => metamer.calc_light_gathered(environment),
+                None => environment.calc_light_gathered(
+                    end_point,
+                    &self.genetics.lock().unwrap(),
+                    bud.bud_data.id(),
+                    length,
+                    bud.direction,
+                ),
+            };
+        }
+
         self.last_light_generated =
             self.last_terminal_light_generated + self.last_aux_light_generated;
+        for bud in &self.extra_buds {
+            self.last_light_generated += bud.last_light_generated;
+        }
+
+        // leaves add their light to the node
+        if !self.leaves.is_empty() {
+            let genetics = self.genetics.lock().unwrap();
+            for leaf in &mut self.leaves {
+                self.last_light_generated += leaf.calc_light_gathered(environment, &genetics);
+            }
+        }
 
         return self.last_light_generated;
     }
 
+    // light gathered by the leaves of the node
+    pub fn leaf_light(&self) -> f32 {
+        self.leaves.iter().map(|leaf| leaf.last_light_generated).sum()
+    }
+
+    // light gathered by all axillary buds and branches of the node
+    pub fn lateral_light(&self) -> f32 {
+        let extra: f32 = self.extra_buds.iter().map(|bud| bud.last_light_generated).sum();
+        self.last_aux_light_generated + extra
+    }
+
     /**
      * Distibute resources to itself and its branches
      */
@@ -221,29 +470,38 @@ impl Metamer {
         distributor.distribute_resources(total_resources, self);
     }
 
-    pub fn add_shoots(&mut self, environment: &Environment) -> u32 {
+    // @param order branch order of the metamer, 0 for the trunk, shoots from lateral buds are one order higher
+    pub fn add_shoots(&mut self, environment: &Environment, order: u32, ids: &mut IdCounter, random: &mut Random) -> u32 {
         let mut total_added = 0;
 
-        total_added += self.add_auxillary_shoot(environment);
-        total_added += self.add_terminal_shoot(environment);
+        total_added += self.add_auxillary_shoot(environment, order + 1, ids, random);
+        total_added += self.add_extra_shoots(environment, order + 1, ids, random);
+        total_added += self.add_terminal_shoot(environment, order, ids, random);
 
         total_added
     }
 
-    fn add_terminal_shoot(&mut self, environment: &Environment) -> u32 {
+    fn add_terminal_shoot(&mut self, environment: &Environment, order: u32, ids: &mut IdCounter, random: &mut Random) -> u32 {
         if let Some(metamer) = &mut self.terminal_metamer {
-            return metamer.add_shoots(environment);
+            return metamer.add_shoots(environment, order, ids, random);
         } else{
 
+            if self.terminal_bud_damage > 0. {
+                // terminal bud damaged, recovering without resources as a damaged bud gets none
+                self.terminal_bud_damage -= self.genetics.lock().unwrap().bud_recovery_speed();
+                self.terminal_bud_damage = self.terminal_bud_damage.max(0.);
+                if self.terminal_bud_damage == 0. {
+                    self.terminal_bud_fate.set_state(BudState::Active);
+                }
+                return 0;
+            }
+
             if self.last_terminal_resources < self.genetics.lock().unwrap().terminal_shoot_requirement(){
                 // not enough resources
                 return 0;
             }
 
-            if self.terminal_bud_damage > 0. {
-                // terminal bud damaged, recovering
-                self.terminal_bud_damage -= parameters::BUD_RECOVERY_SPEED;
-                self.terminal_bud_damage = self.terminal_bud_damage.max(0.);
+            if !self.terminal_bud_fate.can_grow() {
                 return 0;
             }
 
@@ -263,6 +521,10 @@ impl Metamer {
                 self.end_point(),
                 self.direction(),
                 support,
+                self.next_phyllotaxis_angle(),
+                order,
+                ids,
+                random,
             );
             if self.terminal_metamer.is_some() {
                 return 1;
@@ -271,20 +533,33 @@ impl Metamer {
         }
     }
 
-    fn add_auxillary_shoot(&mut self, environment: &Environment) -> u32 {
+    fn add_auxillary_shoot(&mut self, environment: &Environment, order: u32, ids: &mut IdCounter, random: &mut Random) -> u32 {
         if let Some(metamer) = &mut self.auxillary_metamer {
-            return metamer.add_shoots(environment);
+            return metamer.add_shoots(environment, order, ids, random);
         } else {
 
+            if self.auxillary_bud_damage > 0. {
+                // aux bud damaged, recovering
+                self.auxillary_bud_damage -= self.genetics.lock().unwrap().bud_recovery_speed();
+                self.auxillary_bud_damage = self.auxillary_bud_damage.max(0.);
+                if self.auxillary_bud_damage == 0. {
+                    self.aux_bud_fate.set_state(BudState::Active);
+                }
+                return 0;
+            }
+
             if self.last_aux_resources < self.genetics.lock().unwrap().aux_shoot_requirement(Some(self)){
                 // not enough aux resources
                 return 0;
             }
 
-            if self.auxillary_bud_damage > 0. {
-                // aux bud damaged, recovering
-                self.auxillary_bud_damage -= parameters::BUD_RECOVERY_SPEED;
-                self.auxillary_bud_damage = self.auxillary_bud_damage.max(0.);
+            if !self.aux_bud_fate.can_grow() {
+                // dormant, dead or flower bud
+                return 0;
+            }
+
+            if ApicalControl::new(&self.genetics.lock().unwrap()).suppresses(self.auxin) {
+                // kept from breaking by the buds above
                 return 0;
             }
 
@@ -308,6 +583,10 @@ impl Metamer {
                 self.end_point(),
                 dir.norm(),
                 support,
+                self.next_phyllotaxis_angle(),
+                order,
+                ids,
+                random,
             );
             if self.auxillary_metamer.is_some() {
                 return 1;
@@ -316,16 +595,146 @@ impl Metamer {
         }
     }
 
-    // calc the direction of a metamer
+    // grow the other buds of the node like the axillary bud
+    fn add_extra_shoots(&mut self, environment: &Environment, order: u32, ids: &mut IdCounter, random: &mut Random) -> u32 {
+        let mut total_added = 0;
+        for i in 0..self.extra_buds.len() {
+            if let Some(metamer) = &mut self.extra_buds[i].metamer {
+                total_added += metamer.add_shoots(environment, order, ids, random);
+                continue;
+            }
+
+            let bud = &self.extra_buds[i];
+            if bud.damage > 0. {
+                let recovery = self.genetics.lock().unwrap().bud_recovery_speed();
+                let bud = &mut self.extra_buds[i];
+                bud.damage = (bud.damage - recovery).max(0.);
+                if bud.damage == 0. {
+                    bud.fate.set_state(BudState::Active);
+                }
+                continue;
+            }
+            if bud.last_resources < self.genetics.lock().unwrap().aux_shoot_requirement(Some(self)) {
+                continue;
+            }
+            if !bud.fate.can_grow() || ApicalControl::new(&self.genetics.lock().unwrap()).suppresses(self.auxin) {
+                continue;
+            }
+            if self.terminal_metamer.is_none() && self.terminal_bud_damage == 0. {
+                continue;
+            }
+
+            let mut dir = bud.direction;
+            if self.terminal_bud_damage > 0. {
+                dir += self.direction();
+            }
+            let shoot = self.create_shoot(
+                environment,
+                bud.last_resources,
+                bud.bud_data.id(),
+                self.end_point(),
+                dir.norm(),
+                None,
+                self.next_phyllotaxis_angle(),
+                order,
+                ids,
+                random,
+            );
+            if shoot.is_some() {
+                total_added += 1;
+            }
+            self.extra_buds[i].metamer = shoot;
+        }
+        total_added
+    }
+
+    // rotation of the first bud on the next node
+    fn next_phyllotaxis_angle(&self) -> f32 {
+        let divergence = self.genetics.lock().unwrap().divergence_angle();
+        (self.phyllotaxis_angle + divergence) % (2. * std::f32::consts::PI)
+    }
+
+    /**
+     * Move every axillary bud that has not grown a shoot to its next state
+     * @returns the amount of metamers up to the end of the axis and whether the axis lost its apex
+     */
+    pub fn update_bud_fate(&mut self) -> (u32, bool) {
+        let (tip_distance, apex_lost) = match &mut self.terminal_metamer {
+            Some(metamer) => {
+                let (distance, apex_lost) = metamer.update_bud_fate();
+                (distance + 1, apex_lost)
+            }
+            // the buds below are released once, in the iteration after the apex was pruned
+            None => (1, self.terminal_bud_damage > 0. && self.terminal_bud_fate.update_pruned()),
+        };
+
+        let triggers = BudTriggers {
+            age: self.age,
+            light: self.last_aux_light_generated,
+            tip_distance,
+            apex_lost,
+        };
+        if let Some(metamer) = &mut self.auxillary_metamer {
+            metamer.update_bud_fate();
+        } else if self.auxillary_bud_damage == 0. && self.aux_bud_fate.update(&triggers, &self.genetics.lock().unwrap()) {
+            self.aux_bud_data.set_color(self.aux_bud_fate.state().color());
+        }
+
+        for bud in &mut self.extra_buds {
+            if let Some(metamer) = &mut bud.metamer {
+                metamer.update_bud_fate();
+            } else if bud.damage == 0. {
+                let triggers = BudTriggers {
+                    light: bud.last_light_generated,
+                    ..triggers
+                };
+                if bud.fate.update(&triggers, &self.genetics.lock().unwrap()) {
+                    bud.bud_data.set_color(bud.fate.state().color());
+                }
+            }
+        }
+
+        (tip_distance, apex_lost)
+    }
+
+    // @returns amount of buds in the given state that have not grown a shoot and are not pruned
+    pub fn count_buds(&self, state: BudState) -> u32 {
+        let mut count = 0;
+        if let Some(metamer) = &self.terminal_metamer {
+            count += metamer.count_buds(state);
+        } else if self.terminal_bud_damage == 0. && self.terminal_bud_fate.state() == state {
+            count += 1;
+        }
+        if let Some(metamer) = &self.auxillary_metamer {
+            count += metamer.count_buds(state);
+        } else if self.auxillary_bud_damage == 0. && self.aux_bud_fate.state() == state {
+            count += 1;
+        }
+        for bud in &self.extra_buds {
+            if let Some(metamer) = &bud.metamer {
+                count += metamer.count_buds(state);
+            } else if bud.damage == 0. && bud.fate.state() == state {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    // calc the direction of a metamer growing from the position
     fn calc_metamer_direction(
         &self,
         environment: &Environment,
         optimal_growth_direction: Vecf3,
         bud_direction: Vecf3,
+        position: Vecf3,
+        tropism: &Tropism,
     ) -> Vecf3 {
+        let genetics = self.genetics.lock().unwrap();
         let mut metamer_dir = bud_direction;
-        metamer_dir += optimal_growth_direction * self.genetics.lock().unwrap().optimal_growth_direction_weight();
-        metamer_dir += environment.tropism_dir() * environment.tropism_growth_direction_weight();
+        metamer_dir += optimal_growth_direction * genetics.optimal_growth_direction_weight();
+        if tropism.enabled() {
+            metamer_dir += tropism.response(environment, &genetics, position, bud_direction);
+        }
         return metamer_dir.norm();
     }
 
@@ -338,7 +747,13 @@ impl Metamer {
         point: Vecf3,
         dir: Vecf3,
         mut support_pole: Option<SupportPole>,
+        mut phyllotaxis_angle: f32,
+        order: u32,
+        ids: &mut IdCounter,
+        random: &mut Random,
     ) -> Option<Box<Metamer>> {
+        let divergence = self.genetics.lock().unwrap().divergence_angle();
+        let tropism = Tropism::new(&self.genetics.lock().unwrap(), order);
 
         let optimal_growth_dir =
             environment.optimal_growth_direction(point, &self.genetics.lock().unwrap(), bud_id, self.length(), dir);
@@ -357,7 +772,7 @@ impl Metamer {
         let mut metamer_dir = dir;
 
         for _ in 0..total_metamers_adding {
-            metamer_dir = self.calc_metamer_direction(environment, optimal_growth_dir, metamer_dir);
+            metamer_dir = self.calc_metamer_direction(environment, optimal_growth_dir, metamer_dir, prev_end, &tropism);
 
             if let Some(support) = &support_pole {
                 metamer_dir = (metamer_dir + support.dir()).norm();
@@ -370,8 +785,12 @@ impl Metamer {
                 self.genetics.clone(),
                 bud_id,
                 support_pole.clone(),
+                phyllotaxis_angle,
+                ids,
+                random,
             ));
             bud_id = metamer.terminal_bud_data.id();
+            phyllotaxis_angle = (phyllotaxis_angle + divergence) % (2. * std::f32::consts::PI);
             if last_metamer.is_some() {
                 let last_terminal = &mut last_metamer.unwrap().terminal_metamer;
                 *last_terminal = Some(metamer);
@@ -404,12 +823,15 @@ impl Metamer {
         if let Some(metamer) = &self.auxillary_metamer {
             volume = volume.merge(metamer.bounding_volume());
         }
+        for metamer in self.extra_buds.iter().filter_map(|bud| bud.metamer()) {
+            volume = volume.merge(metamer.bounding_volume());
+        }
 
         return volume;
     }
 
     // @returns total metamers in the tree
-    fn count_metamers(&self) -> u32 {
+    pub fn count_metamers(&self) -> u32 {
         let mut count = 1;
         if let Some(metamer) = &self.terminal_metamer {
             count += metamer.count_metamers();
@@ -417,6 +839,9 @@ impl Metamer {
         if let Some(metamer) = &self.auxillary_metamer {
             count += metamer.count_metamers();
         }
+        for metamer in self.extra_buds.iter().filter_map(|bud| bud.metamer()) {
+            count += metamer.count_metamers();
+        }
         return count;
     }
 
@@ -448,22 +873,44 @@ impl Metamer {
                 }
             }
         }
+        let shed_treshhold = self.genetics.lock().unwrap().shed_treshhold();
+        for bud in &mut self.extra_buds {
+            if let Some(metamer) = &mut bud.metamer {
+                if !environment.is_inside(metamer.end_point())
+                    || bud.last_light_generated / (metamer.count_metamers() as f32) < shed_treshhold
+                {
+                    bud.prune();
+                } else {
+                    metamer.shed_branches(environment);
+                }
+            }
+        }
     }
 
     // update all metamer widths
     pub fn update_width(&mut self) {
 
-        let mut total = parameters::WIDTH_MIN_VALUE;
+        let (width_min_value, width_grow_exponent) = {
+            let genetics = self.genetics.lock().unwrap();
+            (genetics.width_min_value(), genetics.width_grow_exponent())
+        };
+        let mut total = width_min_value;
 
         if let Some(metamer) = &mut self.terminal_metamer {
             metamer.update_width();
-            total += metamer.start_width().powf(parameters::WIDTH_GROW_EXPONENT);
+            total += metamer.start_width().powf(width_grow_exponent);
             self.branch_data
                 .set_end_width(self.branch_data.end_width().max(metamer.start_width()));
         }
         if let Some(metamer) = &mut self.auxillary_metamer {
             metamer.update_width();
-            total += metamer.start_width().powf(parameters::WIDTH_GROW_EXPONENT);
+            total += metamer.start_width().powf(width_grow_exponent);
+            self.branch_data
+                .set_end_width(self.branch_data.end_width().max(metamer.start_width()));
+        }
+        for metamer in self.extra_buds.iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            metamer.update_width();
+            total += metamer.start_width().powf(width_grow_exponent);
             self.branch_data
                 .set_end_width(self.branch_data.end_width().max(metamer.start_width()));
         }
@@ -471,7 +918,7 @@ impl Metamer {
         self.branch_data.set_start_width(
             self.branch_data
                 .start_width()
-                .max(total.powf(1. / parameters::WIDTH_GROW_EXPONENT)),
+                .max(total.powf(1. / width_grow_exponent)),
         );
     }
 
@@ -493,13 +940,19 @@ impl Metamer {
                 return v;
             }
         }
+        for metamer in self.extra_buds.iter().filter_map(|bud| bud.metamer()) {
+            let v = metamer.get_metamer_by_id(id);
+            if v.is_some() {
+                return v;
+            }
+        }
         return None;
     }
 
     /**
      * Perturbates a vector in a random direction by the specified angle.
      */
-    fn random_perturbation(mut original_vector: Vecf3, angle: f32) -> Vecf3 {
+    fn random_perturbation(mut original_vector: Vecf3, angle: f32, random: &mut Random) -> Vecf3 {
         original_vector = original_vector * 0.01;
         let vx = Vecf3::new(1.0, 0.0, 0.0);
         let vy = Vecf3::new(0.0, 1.0, 0.0);
@@ -508,8 +961,8 @@ impl Metamer {
             auxiliary_vector = vy;
         }
         let cross_vector = original_vector.cross(auxiliary_vector).norm();
-        let s = Random::rand();
-        let r = Random::rand();
+        let s = random.rand();
+        let r = random.rand();
         let h = angle.cos();
         let phi = 2.0 * 4.0 * 1.0f32.atan() * s;
         let z = h + (1.0f32 - h) * r;
@@ -523,6 +976,25 @@ impl Metamer {
         self.auxillary_direction
     }
 
+    // angle of the axillary bud around the vertical axis in radians, from the x axis towards the z axis
+    pub fn auxillary_azimuth(&self) -> f32 {
+        self.auxillary_direction.z.atan2(self.auxillary_direction.x)
+    }
+
+    // angle in radians between the horizontal direction of the metamer and the direction to a vertical axis,
+    // 0 when it grows straight towards the axis, none when it grows vertical or starts on the axis
+    pub fn angle_to_axis(&self, axis: Vecf3) -> Option<f32> {
+        let start = self.start_point();
+        let to_axis = Vecf3::new(axis.x - start.x, 0., axis.z - start.z);
+        let dir = self.direction();
+        let horizontal = Vecf3::new(dir.x, 0., dir.z);
+        if to_axis.length() < 1.0e-6 || horizontal.length() < 1.0e-6 {
+            return None;
+        }
+        let cos = (to_axis.norm() * horizontal.norm()).clamp(-1., 1.);
+        Some(cos.acos())
+    }
+
     pub fn last_light_generated(&self) -> f32 {
         self.last_light_generated
     }
@@ -537,6 +1009,10 @@ impl Metamer {
             self.prune_terminal();
             return;
         }
+        if let Some(bud) = self.extra_buds.iter_mut().find(|bud| bud.bud_data.id() == id) {
+            bud.prune();
+            return;
+        }
 
         if let Some(metamer) = &mut self.terminal_metamer {
             metamer.prune_id(id);
@@ -544,15 +1020,212 @@ impl Metamer {
         if let Some(metamer) = &mut self.auxillary_metamer {
             metamer.prune_id(id);
         }
+        for metamer in self.extra_buds.iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            metamer.prune_id(id);
+        }
     }
 
     pub fn prune_terminal(&mut self) {
         self.terminal_metamer = None;
+        self.fruit = None;
         self.terminal_bud_damage = 1.;
+        self.terminal_bud_fate.prune();
     }
     pub fn prune_auxillary(&mut self) {
         self.auxillary_metamer = None;
         self.auxillary_bud_damage = 1.;
+        self.aux_bud_fate.prune();
+    }
+    /**
+     * Let leaves that are too old or too shaded fall off
+     * @returns amount of leaves fallen
+     */
+    pub fn update_leaves(&mut self) -> u32 {
+        let mut fallen = 0;
+        if !self.leaves.is_empty() {
+            let genetics = self.genetics.lock().unwrap();
+            let before = self.leaves.len();
+            self.leaves.retain(|leaf| !leaf.falls(&genetics));
+            fallen += (before - self.leaves.len()) as u32;
+        }
+        if let Some(metamer) = &mut self.terminal_metamer {
+            fallen += metamer.update_leaves();
+        }
+        if let Some(metamer) = &mut self.auxillary_metamer {
+            fallen += metamer.update_leaves();
+        }
+        for metamer in self.extra_buds.iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            fallen += metamer.update_leaves();
+        }
+        fallen
+    }
+
+    /**
+     * Let every leaf fall off, e.g. when the plant goes dormant
+     * @returns amount of leaves fallen
+     */
+    pub fn drop_leaves(&mut self) -> u32 {
+        let mut fallen = self.leaves.len() as u32;
+        self.leaves.clear();
+        if let Some(metamer) = &mut self.terminal_metamer {
+            fallen += metamer.drop_leaves();
+        }
+        if let Some(metamer) = &mut self.auxillary_metamer {
+            fallen += metamer.drop_leaves();
+        }
+        for metamer in self.extra_buds.iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            fallen += metamer.drop_leaves();
+        }
+        fallen
+    }
+
+    /**
+     * Turn the metamer and everything growing on it around the pivot, support poles stay in place
+     * @param axis normalized rotation axis
+     */
+    pub fn rotate(&mut self, pivot: Vecf3, axis: Vecf3, radians: f32) {
+        self.branch_data.rotate(pivot, axis, radians);
+        self.terminal_bud_data.rotate(pivot, axis, radians);
+        self.aux_bud_data.rotate(pivot, axis, radians);
+        self.auxillary_direction = rot_vec_around_axis(&self.auxillary_direction, &axis, radians).norm();
+        self.bending = rot_vec_around_axis(&self.bending, &axis, radians);
+        for leaf in &mut self.leaves {
+            leaf.rotate(pivot, axis, radians);
+        }
+        if let Some(fruit) = &mut self.fruit {
+            // fruits keep hanging down from the node
+            fruit.set_node(self.branch_data.end_point());
+        }
+
+        if let Some(metamer) = &mut self.terminal_metamer {
+            metamer.rotate(pivot, axis, radians);
+        }
+        if let Some(metamer) = &mut self.auxillary_metamer {
+            metamer.rotate(pivot, axis, radians);
+        }
+        for bud in &mut self.extra_buds {
+            bud.direction = rot_vec_around_axis(&bud.direction, &axis, radians).norm();
+            bud.bud_data.rotate(pivot, axis, radians);
+            if let Some(metamer) = bud.metamer_mut() {
+                metamer.rotate(pivot, axis, radians);
+            }
+        }
+    }
+
+    // leaves of this metamer and all its child metamers
+    pub fn collect_leaves(&self) -> Vec<&Leaf> {
+        let mut result: Vec<&Leaf> = self.leaves.iter().collect();
+        if let Some(metamer) = &self.terminal_metamer {
+            result.append(&mut metamer.collect_leaves());
+        }
+        if let Some(metamer) = &self.auxillary_metamer {
+            result.append(&mut metamer.collect_leaves());
+        }
+        for metamer in self.extra_buds.iter().filter_map(|bud| bud.metamer()) {
+            result.append(&mut metamer.collect_leaves());
+        }
+        result
+    }
+
+    pub fn leaves(&self) -> &[Leaf] {
+        &self.leaves
+    }
+
+    // shoots grown from the axillary bud and the other buds of the node
+    pub fn lateral_metamers(&self) -> impl Iterator<Item = &Metamer> {
+        self.auxillary_metamer()
+            .into_iter()
+            .chain(self.extra_buds.iter().filter_map(|bud| bud.metamer()))
+    }
+    pub fn lateral_metamers_mut(&mut self) -> impl Iterator<Item = &mut Metamer> {
+        self.auxillary_metamer
+            .as_deref_mut()
+            .into_iter()
+            .chain(self.extra_buds.iter_mut().filter_map(|bud| bud.metamer_mut()))
+    }
+
+    // prune the axillary bud and every other bud of the node
+    pub fn prune_laterals(&mut self) {
+        self.prune_auxillary();
+        self.prune_extra_buds();
+    }
+    // prune every axillary bud of the node except the first one
+    pub fn prune_extra_buds(&mut self) {
+        for bud in &mut self.extra_buds {
+            bud.prune();
+        }
+    }
+
+    /**
+     * Cut the metamer at a fraction of its length, everything above the cut is removed.
+     * The buds are moved to the new end and the end width is taken from the width at the cut,
+     * the terminal bud keeps growing from there.
+     */
+    pub fn cut_at(&mut self, fraction: f32) {
+        let fraction = fraction.clamp(MIN_CUT_FRACTION, 1.);
+        self.prune_terminal();
+        self.prune_laterals();
+        self.terminal_bud_damage = 0.;
+        self.terminal_bud_fate = BudFate::new();
+        // the node with its leaves is cut off
+        self.leaves.clear();
+
+        let start = self.start_point();
+        let dir = self.direction();
+        let end = start + dir * (self.length() * fraction);
+        let end_width = self.start_width() + (self.end_width() - self.start_width()) * fraction;
+        self.branch_data.set_end_point(end);
+        self.branch_data.set_end_width(end_width);
+
+        self.terminal_bud_data.set_start_point(end);
+        self.terminal_bud_data.set_end_point(end + dir * 0.05);
+        self.aux_bud_data.set_start_point(end);
+        self.aux_bud_data.set_end_point(end + self.auxillary_direction * 0.05);
+        for bud in &mut self.extra_buds {
+            bud.bud_data.set_start_point(end);
+            bud.bud_data.set_end_point(end + bud.direction * 0.05);
+        }
+    }
+
+    // cut the metamer with the given id
+    // @returns true when the metamer was found
+    pub fn cut_id(&mut self, id: u32, fraction: f32) -> bool {
+        if self.id() == id {
+            self.cut_at(fraction);
+            return true;
+        }
+
+        if let Some(metamer) = &mut self.terminal_metamer {
+            if metamer.cut_id(id, fraction) {
+                return true;
+            }
+        }
+        if let Some(metamer) = &mut self.auxillary_metamer {
+            if metamer.cut_id(id, fraction) {
+                return true;
+            }
+        }
+        self.extra_buds
+            .iter_mut()
+            .filter_map(|bud| bud.metamer_mut())
+            .any(|metamer| metamer.cut_id(id, fraction))
+    }
+
+    // one more growth iteration has passed for this metamer and all its child metamers
+    pub fn increase_age(&mut self) {
+        self.age += 1;
+        for leaf in &mut self.leaves {
+            leaf.increase_age();
+        }
+        if let Some(metamer) = &mut self.terminal_metamer {
+            metamer.increase_age();
+        }
+        if let Some(metamer) = &mut self.auxillary_metamer {
+            metamer.increase_age();
+        }
+        for metamer in self.extra_buds.iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            metamer.increase_age();
+        }
     }
 
     // @returns total metamers
@@ -568,6 +1241,9 @@ impl Metamer {
         } else {
             total += 1;
         }
+        for bud in &self.extra_buds {
+            total += bud.metamer().map_or(1, |metamer| metamer.total_metamers());
+        }
         return total;
     }
 
@@ -584,6 +1260,9 @@ impl Metamer {
         if let Some(metamer) = &self.auxillary_metamer {
             auxillary_length = metamer.longest_path();
         }
+        for metamer in self.extra_buds.iter().filter_map(|bud| bud.metamer()) {
+            auxillary_length = auxillary_length.max(metamer.longest_path());
+        }
 
         length += u32::max(terminal_length, auxillary_length); 
         return length;
@@ -596,9 +1275,296 @@ impl Metamer {
     pub fn terminal_metamer_mut(&mut self) -> Option<&mut Metamer> {
         self.terminal_metamer.as_mut().map(|v|v.as_mut())
     }
-    pub fn auxillary_metamer(&mut self) -> Option<&mut Metamer> {
+    pub fn auxillary_metamer(& self) -> Option<&Metamer> {
+        self.auxillary_metamer.as_ref().map(|v|v.as_ref())
+    }
+    pub fn auxillary_metamer_mut(&mut self) -> Option<&mut Metamer> {
         self.auxillary_metamer.as_mut().map(|v|v.as_mut())
     }
+    pub fn set_terminal_metamer(&mut self, metamer: Option<Metamer>) {
+        self.terminal_metamer = metamer.map(Box::new);
+    }
+    pub fn set_auxillary_metamer(&mut self, metamer: Option<Metamer>) {
+        self.auxillary_metamer = metamer.map(Box::new);
+    }
+    // the other axillary buds of the node, after the first one
+    pub fn extra_buds(&self) -> &[ExtraBud] {
+        &self.extra_buds
+    }
+    pub fn extra_buds_mut(&mut self) -> &mut [ExtraBud] {
+        &mut self.extra_buds
+    }
+    // add an axillary bud pointing in the given direction to the node
+    pub fn add_extra_bud(&mut self, direction: Vecf3, ids: &mut IdCounter) -> &mut ExtraBud {
+        self.extra_buds.push(ExtraBud::new(self.end_point(), direction, ids.get_id()));
+        self.extra_buds.last_mut().unwrap()
+    }
+    pub fn phyllotaxis_angle(&self) -> f32 {
+        self.phyllotaxis_angle
+    }
+
+    // share the given genetics with this metamer and all its child metamers
+    pub fn set_genetics(&mut self, genetics: Arc<Mutex<PlantGenetics>>) {
+        if let Some(metamer) = &mut self.terminal_metamer {
+            metamer.set_genetics(genetics.clone());
+        }
+        if let Some(metamer) = &mut self.auxillary_metamer {
+            metamer.set_genetics(genetics.clone());
+        }
+        for metamer in self.extra_buds.iter_mut().filter_map(|bud| bud.metamer_mut()) {
+            metamer.set_genetics(genetics.clone());
+        }
+        self.genetics = genetics;
+    }
+}
+
+macro_rules! METAMER_STRING_FORMAT {
+    () => {
+        "metamer branch{} pole{} light{} term{} termbud{} termlight{} termres{} termdamage{} termfate{} aux{} auxdir{} auxbud{} auxpole{} auxlight{} auxres{} auxdamage{} auxfate{} age{} phyllotaxis{} extra{} leaves{} fruit{} bending{}"
+    };
+}
+
+macro_rules! EXTRA_BUD_STRING_FORMAT {
+    () => {
+        "extrabud metamer{} dir{} bud{} damage{} fate{} light{} res{}"
+    };
+}
+
+impl FromStr for ExtraBud {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut metamer = SnapshotOption::<Metamer>(None);
+        let mut direction = Vecf3::default();
+        let mut bud_data = BranchData::new(Vecf3::default(), Vecf3::default(), 0., 0., Color::default(), 0);
+        let mut damage: f32 = 0.;
+        let mut fate = BudFate::new();
+        let mut last_light_generated: f32 = 0.;
+        let mut last_resources: f32 = 0.;
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => EXTRA_BUD_STRING_FORMAT!(),
+            metamer,
+            direction,
+            bud_data,
+            damage,
+            fate,
+            last_light_generated,
+            last_resources
+        )
+        .map_err(|_| ())?;
+
+        Ok(Self {
+            metamer: metamer.0.map(Box::new),
+            direction,
+            bud_data,
+            damage,
+            fate,
+            last_light_generated,
+            last_resources,
+        })
+    }
+}
+
+impl Display for ExtraBud {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(EXTRA_BUD_STRING_FORMAT!() => writer,
+            SnapshotOption(self.metamer.as_deref()),
+            self.direction,
+            &self.bud_data,
+            self.damage,
+            self.fate,
+            self.last_light_generated,
+            self.last_resources
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
+
+macro_rules! EXTRA_BUD_LIST_STRING_FORMAT {
+    () => {
+        "bud{}"
+    };
+}
+
+macro_rules! LEAF_LIST_STRING_FORMAT {
+    () => {
+        "leaf{}"
+    };
+}
+
+// leaves are written one after the other, separated by a space
+fn parse_leaves(s: &str) -> Result<Vec<Leaf>, ()> {
+    let mut leaves = vec![];
+    let mut it = s.bytes().into_iter().peekable();
+    while it.peek().is_some() {
+        let mut leaf = String::new();
+        pscan!(&mut it => LEAF_LIST_STRING_FORMAT!(), leaf).map_err(|_| ())?;
+        leaves.push(leaf.parse()?);
+        it.next();
+    }
+    Ok(leaves)
+}
+
+fn write_leaves(leaves: &[Leaf]) -> Result<String, fmt::Error> {
+    let mut result = String::new();
+    for (i, leaf) in leaves.iter().enumerate() {
+        if i > 0 {
+            result.push(' ');
+        }
+        let writer: &mut dyn std::fmt::Write = &mut result;
+        pwrite!(LEAF_LIST_STRING_FORMAT!() => writer, leaf).map_err(|_| fmt::Error)?;
+    }
+    Ok(result)
+}
+
+// extra buds are written one after the other, separated by a space
+fn parse_extra_buds(s: &str) -> Result<Vec<ExtraBud>, ()> {
+    let mut buds = vec![];
+    let mut it = s.bytes().into_iter().peekable();
+    while it.peek().is_some() {
+        let mut bud = String::new();
+        pscan!(&mut it => EXTRA_BUD_LIST_STRING_FORMAT!(), bud).map_err(|_| ())?;
+        buds.push(bud.parse()?);
+        it.next();
+    }
+    Ok(buds)
+}
+
+fn write_extra_buds(buds: &[ExtraBud]) -> Result<String, fmt::Error> {
+    let mut result = String::new();
+    for (i, bud) in buds.iter().enumerate() {
+        if i > 0 {
+            result.push(' ');
+        }
+        let writer: &mut dyn std::fmt::Write = &mut result;
+        pwrite!(EXTRA_BUD_LIST_STRING_FORMAT!() => writer, bud).map_err(|_| fmt::Error)?;
+    }
+    Ok(result)
+}
+
+/**
+ * Parsed metamers get their own genetics, use set_genetics to share the plant genetics again
+ */
+impl FromStr for Metamer {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let empty_data = || BranchData::new(Vecf3::default(), Vecf3::default(), 0., 0., Color::default(), 0);
+
+        let mut branch_data = empty_data();
+        let mut support_pole = SnapshotOption::<SupportPole>(None);
+        let mut last_light_generated: f32 = 0.;
+        let mut terminal_metamer = SnapshotOption::<Metamer>(None);
+        let mut terminal_bud_data = empty_data();
+        let mut last_terminal_light_generated: f32 = 0.;
+        let mut last_terminal_resources: f32 = 0.;
+        let mut terminal_bud_damage: f32 = 0.;
+        let mut terminal_bud_fate = BudFate::new();
+        let mut auxillary_metamer = SnapshotOption::<Metamer>(None);
+        let mut auxillary_direction = Vecf3::default();
+        let mut aux_bud_data = empty_data();
+        let mut aux_support_pole = SnapshotOption::<SupportPole>(None);
+        let mut last_aux_light_generated: f32 = 0.;
+        let mut last_aux_resources: f32 = 0.;
+        let mut auxillary_bud_damage: f32 = 0.;
+        let mut aux_bud_fate = BudFate::new();
+        let mut age: u32 = 0;
+        let mut phyllotaxis_angle: f32 = 0.;
+        let mut extra_buds = String::new();
+        let mut leaves = String::new();
+        let mut fruit = SnapshotOption::<Fruit>(None);
+        let mut bending = Vecf3::default();
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => METAMER_STRING_FORMAT!(),
+            branch_data,
+            support_pole,
+            last_light_generated,
+            terminal_metamer,
+            terminal_bud_data,
+            last_terminal_light_generated,
+            last_terminal_resources,
+            terminal_bud_damage,
+            terminal_bud_fate,
+            auxillary_metamer,
+            auxillary_direction,
+            aux_bud_data,
+            aux_support_pole,
+            last_aux_light_generated,
+            last_aux_resources,
+            auxillary_bud_damage,
+            aux_bud_fate,
+            age,
+            phyllotaxis_angle,
+            extra_buds,
+            leaves,
+            fruit,
+            bending
+        )
+        .map_err(|_| ())?;
+
+        Ok(Self {
+            branch_data,
+            genetics: Arc::new(Mutex::new(PlantGenetics::new())),
+            last_light_generated,
+            support_pole: support_pole.0,
+            aux_support_pole: aux_support_pole.0,
+            terminal_metamer: terminal_metamer.0.map(Box::new),
+            last_terminal_light_generated,
+            last_terminal_resources,
+            terminal_bud_data,
+            terminal_bud_damage,
+            terminal_bud_fate,
+            auxillary_metamer: auxillary_metamer.0.map(Box::new),
+            auxillary_direction,
+            last_aux_light_generated,
+            last_aux_resources,
+            aux_bud_data,
+            auxillary_bud_damage,
+            aux_bud_fate,
+            age,
+            auxin: 0.,
+            bending,
+            phyllotaxis_angle,
+            extra_buds: parse_extra_buds(&extra_buds)?,
+            leaves: parse_leaves(&leaves)?,
+            fruit: fruit.0,
+        })
+    }
+}
+
+impl Display for Metamer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(METAMER_STRING_FORMAT!() => writer,
+            &self.branch_data,
+            SnapshotOption(self.support_pole.as_ref()),
+            self.last_light_generated,
+            SnapshotOption(self.terminal_metamer.as_deref()),
+            &self.terminal_bud_data,
+            self.last_terminal_light_generated,
+            self.last_terminal_resources,
+            self.terminal_bud_damage,
+            self.terminal_bud_fate,
+            SnapshotOption(self.auxillary_metamer.as_deref()),
+            self.auxillary_direction,
+            &self.aux_bud_data,
+            SnapshotOption(self.aux_support_pole.as_ref()),
+            self.last_aux_light_generated,
+            self.last_aux_resources,
+            self.auxillary_bud_damage,
+            self.aux_bud_fate,
+            self.age,
+            self.phyllotaxis_angle,
+            write_extra_buds(&self.extra_buds)?,
+            write_leaves(&self.leaves)?,
+            SnapshotOption(self.fruit.as_ref()),
+            self.bending
+        )
+        .map_err(|_| fmt::Error)
+    }
 }
 
 impl Deref for Metamer {
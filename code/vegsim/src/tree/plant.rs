@@ -2,50 +2,63 @@ use std::sync::{Arc, Mutex};
 
 use patutil::Vecf3;
 
-use crate::treeparameter::DistributionMode;
+use crate::{treeparameter::DistributionMode, util::random::Random};
 
-use super::{branchdata::BranchData, markerset::MarkerSet, metamer::Metamer, PlantGenetics, environment::Environment, support_pole::SupportPole, resourcedistibutor::{ResourceDistibutor}};
+use super::{apicalcontrol::ApicalControl, bending::Bending, branchdata::BranchData, flowering::{Flowering, FruitStats}, markerset::MarkerSet, metamer::{IdCounter, Metamer}, PlantGenetics, environment::Environment, support_pole::SupportPole, resourcedistibutor::{ResourceDistibutor}};
 
 pub struct Plant {
     genetics: Arc<Mutex<PlantGenetics>>,
     root: Metamer,
-    distributor: ResourceDistibutor
+    // ids of the parts grown, cut or tied to this plant
+    ids: IdCounter,
+    distributor: ResourceDistibutor,
+    // flowers and fruits after the last growth iteration
+    fruit_stats: FruitStats,
 }
 
 impl Plant {
-    pub fn new(seed_pos: Vecf3, genetics: Arc<Mutex<PlantGenetics>>) -> Self {
+    pub fn new(seed_pos: Vecf3, genetics: Arc<Mutex<PlantGenetics>>, random: &mut Random) -> Self {
         let seed_dir = Vecf3::new(0., 1., 0.);
         // let seed_dir = Vecf3::new(0.5,0.5, 0.).norm();
 
         let root_start = seed_pos;
         let root_end = seed_pos + seed_dir*genetics.lock().unwrap().metamer_base_length();
-        let support_pole = Self::start_pole(seed_pos, &genetics.lock().unwrap(), seed_dir);
-        let mut root = Metamer::new(root_start, root_end, genetics.clone(), 1, Some(support_pole));
+        let mut ids = IdCounter::new();
+        let support_pole = Self::start_pole(seed_pos, &genetics.lock().unwrap(), seed_dir, &mut ids);
+        let mut root = Metamer::new(root_start, root_end, genetics.clone(), 1, Some(support_pole), 0., &mut ids, random);
 
         root.update_width();
 
-        Self { root, genetics, distributor: ResourceDistibutor::new() }
+        Self { root, ids, genetics, distributor: ResourceDistibutor::new(), fruit_stats: FruitStats::default() }
     }
 
-    pub fn reset(&mut self, seed_pos: Vecf3){
+    pub fn reset(&mut self, seed_pos: Vecf3, random: &mut Random){
         let seed_dir = Vecf3::new(0., 1., 0.);
         let root_start = seed_pos;
         let root_end = seed_pos + seed_dir*self.genetics.lock().unwrap().metamer_base_length();
-        let support_pole = Self::start_pole(seed_pos, &self.genetics.lock().unwrap(), seed_dir);
-        self.root = Metamer::new(root_start, root_end, self.genetics.clone(), 1, Some(support_pole));
+        self.ids = IdCounter::new();
+        let support_pole = Self::start_pole(seed_pos, &self.genetics.lock().unwrap(), seed_dir, &mut self.ids);
+        self.root = Metamer::new(root_start, root_end, self.genetics.clone(), 1, Some(support_pole), 0., &mut self.ids, random);
         self.root.update_width();
+        self.fruit_stats = FruitStats::default();
     }
 
-    fn start_pole(seed_pos: Vecf3, genetics: &PlantGenetics, seed_dir: Vecf3) -> SupportPole{
-        SupportPole::new(genetics.base_pole_lenght(), seed_pos - Vecf3::new(0.,0.,-0.3), seed_dir, false)
+    fn start_pole(seed_pos: Vecf3, genetics: &PlantGenetics, seed_dir: Vecf3, ids: &mut IdCounter) -> SupportPole{
+        SupportPole::new(genetics.base_pole_lenght(), seed_pos - Vecf3::new(0.,0.,-0.3), seed_dir, false, ids.get_id())
         // SupportPole::new(genetics.base_pole_lenght(), seed_pos - Vecf3::new(0.,0.,-0.3), Vecf3::new(0.5, 0.5, 0.), false)
     }
 
-    pub fn collect_branchdata(&mut self) -> Vec<&mut BranchData> {
+    pub fn collect_branchdata(&self) -> Vec<&BranchData> {
         return self.root.collect_branchdata();
     }
 
-    pub fn perform_growth_iteration(&mut self, environment: &mut Environment) {
+    pub fn perform_growth_iteration(&mut self, environment: &mut Environment, random: &mut Random) {
+        // shoots added in this iteration start at age 0
+        self.root.increase_age();
+
+        // buds go dormant, die or are released by the state of the plant after the last iteration
+        self.root.update_bud_fate();
+
         // move light to base
         let total_light = self.calc_light_gathered(environment);
         println!("Total light gathed: {}", total_light);
@@ -58,20 +71,50 @@ impl Plant {
         self.resources_to_tips(total_resources);
         println!("Resources moved toward tips");
 
+        // flowers set fruits, fruits grow and spurs flower
+        self.update_flowering();
+
+        // auxin from the buds above keeps axillary buds from breaking
+        self.update_auxin();
+
         // create shoots from resources
-        let total_shoots_added = self.add_shoots(environment);
+        let total_shoots_added = self.add_shoots(environment, random);
         println!("Total shoots added: {}", total_shoots_added);
 
         // recalculate light and shed branches
         self.calc_light_gathered(environment);
         self.root.shed_branches(environment);
 
+        // old and shaded leaves fall off
+        self.root.update_leaves();
+
 
         // update metamer withs
         self.update_metamer_widths();
         println!("Updated metamer widths");
 
-        environment.increase_tropism();
+        // branches sag under wood, leaves and fruits
+        self.bend_branches();
+    }
+
+    /**
+     * Nothing grows while the plant is dormant, leaves fall and the fruits left are picked
+     * @returns amount of leaves fallen
+     */
+    pub fn perform_dormant_iteration(&mut self) -> u32 {
+        let fallen = self.root.drop_leaves();
+        println!("Dormant, leaves fallen: {}", fallen);
+
+        let flowering = Flowering::new(&self.genetics.lock().unwrap());
+        self.fruit_stats = FruitStats::default();
+        if flowering.enabled() {
+            flowering.harvest(&mut self.root, &mut self.fruit_stats);
+            println!("Fruits picked: {}", self.fruit_stats.harvested);
+        }
+
+        // without leaves and fruits the branches rise again
+        self.bend_branches();
+        fallen
     }
 
     pub fn place_markers(&mut self, markers: &mut MarkerSet)-> u32 {
@@ -87,6 +130,16 @@ impl Plant {
         self.root.prune_id(id);
     }
 
+    // cut the metamer with the given id at a fraction of its length
+    // @returns true when the metamer was found
+    pub fn cut_id(&mut self, id: u32, fraction: f32) -> bool {
+        let found = self.root.cut_id(id, fraction);
+        if found {
+            self.update_metamer_widths();
+        }
+        found
+    }
+
     fn calc_light_gathered(&mut self, environment: &mut Environment) -> f32 {
         // allocate markers
         environment.reset_space();
@@ -104,17 +157,72 @@ impl Plant {
     fn resources_to_tips(&mut self, total_resources: f32) {
         self.root.distribute_resources(&self.distributor,total_resources);
     }
-    fn add_shoots(&mut self, environment: &Environment) -> u32 {
-        self.root.add_shoots(environment)
-    }
-    fn update_metamer_widths(&mut self) {
+    fn update_flowering(&mut self) {
+        let flowering = Flowering::new(&self.genetics.lock().unwrap());
+        self.fruit_stats = FruitStats::default();
+        if flowering.enabled() {
+            flowering.update(&mut self.root, 1, false, &mut self.ids, &mut self.fruit_stats);
+            println!(
+                "Flowers: {}, fruits set: {}, harvested: {}",
+                self.fruit_stats.flowers, self.fruit_stats.set, self.fruit_stats.harvested
+            );
+        }
+    }
+    fn update_auxin(&mut self) {
+        let control = ApicalControl::new(&self.genetics.lock().unwrap());
+        if control.enabled() {
+            control.update(&mut self.root);
+        }
+    }
+    pub(crate) fn bend_branches(&mut self) {
+        let bending = Bending::new(&self.genetics.lock().unwrap());
+        if bending.enabled() {
+            let load = bending.update(&mut self.root);
+            println!(
+                "Branches bent, load: {} kg, largest bending: {}°",
+                load.mass,
+                load.max_angle.to_degrees()
+            );
+        }
+    }
+    fn add_shoots(&mut self, environment: &Environment, random: &mut Random) -> u32 {
+        self.root.add_shoots(environment, 0, &mut self.ids, random)
+    }
+    pub(crate) fn update_metamer_widths(&mut self) {
         self.root.update_width();
     }
 
+    pub fn fruit_stats(&self) -> &FruitStats {
+        &self.fruit_stats
+    }
+
+    pub fn root(&self) -> &Metamer {
+        &self.root
+    }
+
     pub fn root_mut(&mut self) -> &mut Metamer {
         &mut self.root
     }
 
+    pub fn ids(&self) -> &IdCounter {
+        &self.ids
+    }
+
+    pub fn ids_mut(&mut self) -> &mut IdCounter {
+        &mut self.ids
+    }
+
+    // the metamer tree together with the ids to give new parts, e.g. support poles tied by a strategy
+    pub fn root_and_ids_mut(&mut self) -> (&mut Metamer, &mut IdCounter) {
+        (&mut self.root, &mut self.ids)
+    }
+
+    // replace the whole metamer tree, the new tree shares the plant genetics
+    pub fn set_root(&mut self, mut root: Metamer) {
+        root.set_genetics(self.genetics.clone());
+        self.root = root;
+    }
+
     pub fn set_resource_distibution_mode(&mut self, mode: DistributionMode){
         self.distributor.set_mode(mode);
     }
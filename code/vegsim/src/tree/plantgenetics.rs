@@ -1,9 +1,12 @@
+use std::{fmt::{self, Display}, str::FromStr};
+
+use patfile::{pscan, pwrite};
 
 use crate::{treeparameter::GeneticParameter, parameters};
 
 use super::metamer::Metamer;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PlantGenetics {
     borchert_honda_lambda: f32,
     borchert_honda_alpha: f32,
@@ -17,6 +20,41 @@ pub struct PlantGenetics {
     axillary_perturbation_angle: f32,
     optimal_growth_direction_weight: f32,
     shed_treshhold: f32,
+    width_grow_exponent: f32,
+    width_min_value: f32,
+    bud_recovery_speed: f32,
+    bud_dormancy_age: f32,
+    bud_release_distance: f32,
+    bud_release_light: f32,
+    bud_lifespan: f32,
+    auxin_production: f32,
+    auxin_decay: f32,
+    auxin_threshold: f32,
+    buds_per_node: f32,
+    divergence_angle: f32,
+    branching_angle: f32,
+    leaf_size: f32,
+    leaf_angle: f32,
+    leaf_lifespan: f32,
+    leaf_abscission_light: f32,
+    leaf_efficiency: f32,
+    spur_length: f32,
+    flower_age: f32,
+    flower_light: f32,
+    fruit_sink: f32,
+    fruit_set_requirement: f32,
+    fruit_growth: f32,
+    fruit_maturity: f32,
+    gravitropism: f32,
+    gravitropism_angle: f32,
+    lateral_gravitropism: f32,
+    lateral_gravitropism_angle: f32,
+    phototropism: f32,
+    lateral_phototropism: f32,
+    tropism_order_factor: f32,
+    wood_stiffness: f32,
+    wood_density: f32,
+    leaf_mass: f32,
 }
 
 impl PlantGenetics {
@@ -36,7 +74,50 @@ impl PlantGenetics {
     
             axillary_perturbation_angle: parameters::AXILLARY_PERTURBATION_ANGLE,
             optimal_growth_direction_weight: parameters::OPTIMAL_GROWTH_DIRECTION_WEIGHT,
-            shed_treshhold: parameters::SHED_TRESHHOLD
+            shed_treshhold: parameters::SHED_TRESHHOLD,
+
+            width_grow_exponent: parameters::WIDTH_GROW_EXPONENT,
+            width_min_value: parameters::WIDTH_MIN_VALUE,
+            bud_recovery_speed: parameters::BUD_RECOVERY_SPEED,
+
+            bud_dormancy_age: parameters::BUD_DORMANCY_AGE,
+            bud_release_distance: parameters::BUD_RELEASE_DISTANCE,
+            bud_release_light: parameters::BUD_RELEASE_LIGHT,
+            bud_lifespan: parameters::BUD_LIFESPAN,
+
+            auxin_production: parameters::AUXIN_PRODUCTION,
+            auxin_decay: parameters::AUXIN_DECAY,
+            auxin_threshold: parameters::AUXIN_THRESHOLD,
+
+            buds_per_node: parameters::BUDS_PER_NODE,
+            divergence_angle: parameters::DIVERGENCE_ANGLE,
+            branching_angle: parameters::BRANCHING_ANGLE,
+
+            leaf_size: parameters::LEAF_SIZE,
+            leaf_angle: parameters::LEAF_ANGLE,
+            leaf_lifespan: parameters::LEAF_LIFESPAN,
+            leaf_abscission_light: parameters::LEAF_ABSCISSION_LIGHT,
+            leaf_efficiency: parameters::LEAF_EFFICIENCY,
+
+            spur_length: parameters::SPUR_LENGTH,
+            flower_age: parameters::FLOWER_AGE,
+            flower_light: parameters::FLOWER_LIGHT,
+            fruit_sink: parameters::FRUIT_SINK,
+            fruit_set_requirement: parameters::FRUIT_SET_REQUIREMENT,
+            fruit_growth: parameters::FRUIT_GROWTH,
+            fruit_maturity: parameters::FRUIT_MATURITY,
+
+            gravitropism: parameters::GRAVITROPISM,
+            gravitropism_angle: parameters::GRAVITROPISM_ANGLE,
+            lateral_gravitropism: parameters::LATERAL_GRAVITROPISM,
+            lateral_gravitropism_angle: parameters::LATERAL_GRAVITROPISM_ANGLE,
+            phototropism: parameters::PHOTOTROPISM,
+            lateral_phototropism: parameters::LATERAL_PHOTOTROPISM,
+            tropism_order_factor: parameters::TROPISM_ORDER_FACTOR,
+
+            wood_stiffness: parameters::WOOD_STIFFNESS,
+            wood_density: parameters::WOOD_DENSITY,
+            leaf_mass: parameters::LEAF_MASS,
         }
     }
 
@@ -91,24 +172,347 @@ impl PlantGenetics {
         return self.pole_length;
     }
 
+    pub const fn width_grow_exponent(&self) -> f32 {
+        self.width_grow_exponent
+    }
+    pub const fn width_min_value(&self) -> f32 {
+        self.width_min_value
+    }
+
+    pub const fn bud_recovery_speed(&self) -> f32 {
+        self.bud_recovery_speed
+    }
+
+    pub const fn bud_dormancy_age(&self) -> f32 {
+        self.bud_dormancy_age
+    }
+    pub const fn bud_release_distance(&self) -> f32 {
+        self.bud_release_distance
+    }
+    pub const fn bud_release_light(&self) -> f32 {
+        self.bud_release_light
+    }
+    pub const fn bud_lifespan(&self) -> f32 {
+        self.bud_lifespan
+    }
+
+    pub const fn auxin_production(&self) -> f32 {
+        self.auxin_production
+    }
+    pub const fn auxin_decay(&self) -> f32 {
+        self.auxin_decay
+    }
+    pub const fn auxin_threshold(&self) -> f32 {
+        self.auxin_threshold
+    }
+
+    pub fn buds_per_node(&self) -> usize {
+        self.buds_per_node.round().max(1.) as usize
+    }
+    pub const fn divergence_angle(&self) -> f32 {
+        self.divergence_angle
+    }
+    pub const fn branching_angle(&self) -> f32 {
+        self.branching_angle
+    }
+
+    pub const fn leaf_size(&self) -> f32 {
+        self.leaf_size
+    }
+    pub const fn leaf_angle(&self) -> f32 {
+        self.leaf_angle
+    }
+    pub fn leaf_lifespan(&self) -> u32 {
+        self.leaf_lifespan.round().max(0.) as u32
+    }
+    pub const fn leaf_abscission_light(&self) -> f32 {
+        self.leaf_abscission_light
+    }
+    pub const fn leaf_efficiency(&self) -> f32 {
+        self.leaf_efficiency
+    }
+
+    pub fn spur_length(&self) -> u32 {
+        self.spur_length.round().max(0.) as u32
+    }
+    pub fn flower_age(&self) -> u32 {
+        self.flower_age.round().max(0.) as u32
+    }
+    pub const fn flower_light(&self) -> f32 {
+        self.flower_light
+    }
+    pub const fn fruit_sink(&self) -> f32 {
+        self.fruit_sink
+    }
+    pub const fn fruit_set_requirement(&self) -> f32 {
+        self.fruit_set_requirement
+    }
+    pub const fn fruit_growth(&self) -> f32 {
+        self.fruit_growth
+    }
+    pub fn fruit_maturity(&self) -> u32 {
+        self.fruit_maturity.round().max(1.) as u32
+    }
+    pub const fn gravitropism(&self) -> f32 {
+        self.gravitropism
+    }
+    pub const fn gravitropism_angle(&self) -> f32 {
+        self.gravitropism_angle
+    }
+    pub const fn lateral_gravitropism(&self) -> f32 {
+        self.lateral_gravitropism
+    }
+    pub const fn lateral_gravitropism_angle(&self) -> f32 {
+        self.lateral_gravitropism_angle
+    }
+    pub const fn phototropism(&self) -> f32 {
+        self.phototropism
+    }
+    pub const fn lateral_phototropism(&self) -> f32 {
+        self.lateral_phototropism
+    }
+    pub const fn tropism_order_factor(&self) -> f32 {
+        self.tropism_order_factor
+    }
+    pub const fn wood_stiffness(&self) -> f32 {
+        self.wood_stiffness
+    }
+    pub const fn wood_density(&self) -> f32 {
+        self.wood_density
+    }
+    pub const fn leaf_mass(&self) -> f32 {
+        self.leaf_mass
+    }
+
     pub fn update_param(&mut self, param: GeneticParameter) {
         match param {
             GeneticParameter::BorchertHondaLambda(value) => self.borchert_honda_lambda = value,
             GeneticParameter::BorchertHondaAlpha(value) => self.borchert_honda_alpha = value,
             GeneticParameter::PoleLength(value) => self.pole_length = value,
             GeneticParameter::AuxShootReq(value) => self.aux_shoot_requirement = value,
+            GeneticParameter::TermShootReq(value) => self.term_shoot_requirement = value,
+            GeneticParameter::MetamerBaseLength(value) => self.metamer_base_length = value,
+            GeneticParameter::BudPerceptionAngle(value) => self.bud_perception_angle = value,
+            GeneticParameter::BudPerceptionRadiusFactor(value) => self.bud_perception_radius_factor = value,
+            GeneticParameter::OccupancyRadiusFactor(value) => self.occupancy_radius_factor = value,
+            GeneticParameter::AxillaryPerturbationAngle(value) => self.axillary_perturbation_angle = value,
+            GeneticParameter::OptimalGrowthDirectionWeight(value) => self.optimal_growth_direction_weight = value,
+            GeneticParameter::ShedTreshhold(value) => self.shed_treshhold = value,
+            GeneticParameter::WidthGrowExponent(value) => self.width_grow_exponent = value,
+            GeneticParameter::WidthMinValue(value) => self.width_min_value = value,
+            GeneticParameter::BudRecoverySpeed(value) => self.bud_recovery_speed = value,
+            GeneticParameter::BudDormancyAge(value) => self.bud_dormancy_age = value,
+            GeneticParameter::BudReleaseDistance(value) => self.bud_release_distance = value,
+            GeneticParameter::BudReleaseLight(value) => self.bud_release_light = value,
+            GeneticParameter::BudLifespan(value) => self.bud_lifespan = value,
+            GeneticParameter::AuxinProduction(value) => self.auxin_production = value,
+            GeneticParameter::AuxinDecay(value) => self.auxin_decay = value,
+            GeneticParameter::AuxinThreshold(value) => self.auxin_threshold = value,
+            GeneticParameter::BudsPerNode(value) => self.buds_per_node = value,
+            GeneticParameter::DivergenceAngle(value) => self.divergence_angle = value,
+            GeneticParameter::BranchingAngle(value) => self.branching_angle = value,
+            GeneticParameter::LeafSize(value) => self.leaf_size = value,
+            GeneticParameter::LeafAngle(value) => self.leaf_angle = value,
+            GeneticParameter::LeafLifespan(value) => self.leaf_lifespan = value,
+            GeneticParameter::LeafAbscissionLight(value) => self.leaf_abscission_light = value,
+            GeneticParameter::LeafEfficiency(value) => self.leaf_efficiency = value,
+            GeneticParameter::SpurLength(value) => self.spur_length = value,
+            GeneticParameter::FlowerAge(value) => self.flower_age = value,
+            GeneticParameter::FlowerLight(value) => self.flower_light = value,
+            GeneticParameter::FruitSink(value) => self.fruit_sink = value,
+            GeneticParameter::FruitSetRequirement(value) => self.fruit_set_requirement = value,
+            GeneticParameter::FruitGrowth(value) => self.fruit_growth = value,
+            GeneticParameter::FruitMaturity(value) => self.fruit_maturity = value,
+            GeneticParameter::Gravitropism(value) => self.gravitropism = value,
+            GeneticParameter::GravitropismAngle(value) => self.gravitropism_angle = value,
+            GeneticParameter::LateralGravitropism(value) => self.lateral_gravitropism = value,
+            GeneticParameter::LateralGravitropismAngle(value) => self.lateral_gravitropism_angle = value,
+            GeneticParameter::Phototropism(value) => self.phototropism = value,
+            GeneticParameter::LateralPhototropism(value) => self.lateral_phototropism = value,
+            GeneticParameter::TropismOrderFactor(value) => self.tropism_order_factor = value,
+            GeneticParameter::WoodStiffness(value) => self.wood_stiffness = value,
+            GeneticParameter::WoodDensity(value) => self.wood_density = value,
+            GeneticParameter::LeafMass(value) => self.leaf_mass = value,
         }
     }
 
-    pub fn get_param(&mut self, param: GeneticParameter) -> GeneticParameter {
-        match param {
-            GeneticParameter::BorchertHondaLambda(_) => GeneticParameter::BorchertHondaLambda(self.borchert_honda_lambda),
-            GeneticParameter::BorchertHondaAlpha(_) => GeneticParameter::BorchertHondaAlpha(self.borchert_honda_alpha),
-            GeneticParameter::PoleLength(_) => GeneticParameter::PoleLength(self.pole_length),
-            GeneticParameter::AuxShootReq(_) => GeneticParameter::AuxShootReq(self.aux_shoot_requirement),
-        }
+    pub fn get_param(&self, param: GeneticParameter) -> GeneticParameter {
+        let value = match param {
+            GeneticParameter::BorchertHondaLambda(_) => self.borchert_honda_lambda,
+            GeneticParameter::BorchertHondaAlpha(_) => self.borchert_honda_alpha,
+            GeneticParameter::PoleLength(_) => self.pole_length,
+            GeneticParameter::AuxShootReq(_) => self.aux_shoot_requirement,
+            GeneticParameter::TermShootReq(_) => self.term_shoot_requirement,
+            GeneticParameter::MetamerBaseLength(_) => self.metamer_base_length,
+            GeneticParameter::BudPerceptionAngle(_) => self.bud_perception_angle,
+            GeneticParameter::BudPerceptionRadiusFactor(_) => self.bud_perception_radius_factor,
+            GeneticParameter::OccupancyRadiusFactor(_) => self.occupancy_radius_factor,
+            GeneticParameter::AxillaryPerturbationAngle(_) => self.axillary_perturbation_angle,
+            GeneticParameter::OptimalGrowthDirectionWeight(_) => self.optimal_growth_direction_weight,
+            GeneticParameter::ShedTreshhold(_) => self.shed_treshhold,
+            GeneticParameter::WidthGrowExponent(_) => self.width_grow_exponent,
+            GeneticParameter::WidthMinValue(_) => self.width_min_value,
+            GeneticParameter::BudRecoverySpeed(_) => self.bud_recovery_speed,
+            GeneticParameter::BudDormancyAge(_) => self.bud_dormancy_age,
+            GeneticParameter::BudReleaseDistance(_) => self.bud_release_distance,
+            GeneticParameter::BudReleaseLight(_) => self.bud_release_light,
+            GeneticParameter::BudLifespan(_) => self.bud_lifespan,
+            GeneticParameter::AuxinProduction(_) => self.auxin_production,
+            GeneticParameter::AuxinDecay(_) => self.auxin_decay,
+            GeneticParameter::AuxinThreshold(_) => self.auxin_threshold,
+            GeneticParameter::BudsPerNode(_) => self.buds_per_node,
+            GeneticParameter::DivergenceAngle(_) => self.divergence_angle,
+            GeneticParameter::BranchingAngle(_) => self.branching_angle,
+            GeneticParameter::LeafSize(_) => self.leaf_size,
+            GeneticParameter::LeafAngle(_) => self.leaf_angle,
+            GeneticParameter::LeafLifespan(_) => self.leaf_lifespan,
+            GeneticParameter::LeafAbscissionLight(_) => self.leaf_abscission_light,
+            GeneticParameter::LeafEfficiency(_) => self.leaf_efficiency,
+            GeneticParameter::SpurLength(_) => self.spur_length,
+            GeneticParameter::FlowerAge(_) => self.flower_age,
+            GeneticParameter::FlowerLight(_) => self.flower_light,
+            GeneticParameter::FruitSink(_) => self.fruit_sink,
+            GeneticParameter::FruitSetRequirement(_) => self.fruit_set_requirement,
+            GeneticParameter::FruitGrowth(_) => self.fruit_growth,
+            GeneticParameter::FruitMaturity(_) => self.fruit_maturity,
+            GeneticParameter::Gravitropism(_) => self.gravitropism,
+            GeneticParameter::GravitropismAngle(_) => self.gravitropism_angle,
+            GeneticParameter::LateralGravitropism(_) => self.lateral_gravitropism,
+            GeneticParameter::LateralGravitropismAngle(_) => self.lateral_gravitropism_angle,
+            GeneticParameter::Phototropism(_) => self.phototropism,
+            GeneticParameter::LateralPhototropism(_) => self.lateral_phototropism,
+            GeneticParameter::TropismOrderFactor(_) => self.tropism_order_factor,
+            GeneticParameter::WoodStiffness(_) => self.wood_stiffness,
+            GeneticParameter::WoodDensity(_) => self.wood_density,
+            GeneticParameter::LeafMass(_) => self.leaf_mass,
+        };
+        param.with_value(value)
     }
     
 }
 
 unsafe impl Send for PlantGenetics {}
+
+macro_rules! GENETICS_STRING_FORMAT {
+    () => {
+        "genetics lambda{} alpha{} pole{} auxreq{} termreq{} length{} angle{} radius{} occupancy{} perturbation{} optimal{} shed{} widthexp{} widthmin{} recovery{} dormancy{} release{} releaselight{} lifespan{} auxin{} auxindecay{} auxinthreshold{} budspernode{} divergence{} branching{} leafsize{} leafangle{} leaflifespan{} abscission{} leafefficiency{} spurlength{} flowerage{} flowerlight{} fruitsink{} fruitset{} fruitgrowth{} fruitmaturity{} gravitropism{} gravitropismangle{} lateralgravitropism{} lateralgravitropismangle{} phototropism{} lateralphototropism{} tropismorder{} stiffness{} wooddensity{} leafmass{}"
+    };
+}
+
+impl FromStr for PlantGenetics {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut genetics = PlantGenetics::new();
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => GENETICS_STRING_FORMAT!(),
+            genetics.borchert_honda_lambda,
+            genetics.borchert_honda_alpha,
+            genetics.pole_length,
+            genetics.aux_shoot_requirement,
+            genetics.term_shoot_requirement,
+            genetics.metamer_base_length,
+            genetics.bud_perception_angle,
+            genetics.bud_perception_radius_factor,
+            genetics.occupancy_radius_factor,
+            genetics.axillary_perturbation_angle,
+            genetics.optimal_growth_direction_weight,
+            genetics.shed_treshhold,
+            genetics.width_grow_exponent,
+            genetics.width_min_value,
+            genetics.bud_recovery_speed,
+            genetics.bud_dormancy_age,
+            genetics.bud_release_distance,
+            genetics.bud_release_light,
+            genetics.bud_lifespan,
+            genetics.auxin_production,
+            genetics.auxin_decay,
+            genetics.auxin_threshold,
+            genetics.buds_per_node,
+            genetics.divergence_angle,
+            genetics.branching_angle,
+            genetics.leaf_size,
+            genetics.leaf_angle,
+            genetics.leaf_lifespan,
+            genetics.leaf_abscission_light,
+            genetics.leaf_efficiency,
+            genetics.spur_length,
+            genetics.flower_age,
+            genetics.flower_light,
+            genetics.fruit_sink,
+            genetics.fruit_set_requirement,
+            genetics.fruit_growth,
+            genetics.fruit_maturity,
+            genetics.gravitropism,
+            genetics.gravitropism_angle,
+            genetics.lateral_gravitropism,
+            genetics.lateral_gravitropism_angle,
+            genetics.phototropism,
+            genetics.lateral_phototropism,
+            genetics.tropism_order_factor,
+            genetics.wood_stiffness,
+            genetics.wood_density,
+            genetics.leaf_mass
+        )
+        .map_err(|_| ())?;
+
+        Ok(genetics)
+    }
+}
+
+impl Display for PlantGenetics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(GENETICS_STRING_FORMAT!() => writer,
+            self.borchert_honda_lambda,
+            self.borchert_honda_alpha,
+            self.pole_length,
+            self.aux_shoot_requirement,
+            self.term_shoot_requirement,
+            self.metamer_base_length,
+            self.bud_perception_angle,
+            self.bud_perception_radius_factor,
+            self.occupancy_radius_factor,
+            self.axillary_perturbation_angle,
+            self.optimal_growth_direction_weight,
+            self.shed_treshhold,
+            self.width_grow_exponent,
+            self.width_min_value,
+            self.bud_recovery_speed,
+            self.bud_dormancy_age,
+            self.bud_release_distance,
+            self.bud_release_light,
+            self.bud_lifespan,
+            self.auxin_production,
+            self.auxin_decay,
+            self.auxin_threshold,
+            self.buds_per_node,
+            self.divergence_angle,
+            self.branching_angle,
+            self.leaf_size,
+            self.leaf_angle,
+            self.leaf_lifespan,
+            self.leaf_abscission_light,
+            self.leaf_efficiency,
+            self.spur_length,
+            self.flower_age,
+            self.flower_light,
+            self.fruit_sink,
+            self.fruit_set_requirement,
+            self.fruit_growth,
+            self.fruit_maturity,
+            self.gravitropism,
+            self.gravitropism_angle,
+            self.lateral_gravitropism,
+            self.lateral_gravitropism_angle,
+            self.phototropism,
+            self.lateral_phototropism,
+            self.tropism_order_factor,
+            self.wood_stiffness,
+            self.wood_density,
+            self.leaf_mass
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
@@ -0,0 +1,245 @@
+use patutil::Vecf3;
+
+use crate::{
+    tree::{metamer::Metamer, plant::Plant, support_pole::SupportPole},
+    util::meter_to_real_length,
+};
+
+use super::strategy::{scaffold_pole_dir, PruningSeason, PruningStrategy, StrategyParameter};
+
+/**
+ * Central leader training: one dominant leader with tiers of scaffold branches inside a cone.
+ * A slender spindle is the same system with a single tier along the whole leader,
+ * flatter scaffolds and a narrower cone.
+ */
+#[derive(Debug, Clone)]
+pub struct AutopruneCentralLeader {
+    name: &'static str,
+    trunk_height: f32,          // height of the clear trunk below the first tier in m
+    tiers: u32,                 // amount of scaffold tiers
+    tier_spacing: f32,          // distance between the bottom of two tiers in m
+    tier_height: f32,           // height of the part of a tier where scaffolds are kept in m
+    scaffolds_per_tier: u32,    // max scaffold branches in one tier
+    scaffold_angle: f32,        // angle between a scaffold and the leader in degrees
+    scaffold_pole_length: f32,  // length of the supportpoles for new scaffolds in m
+    competitor_angle: f32,      // shoots on the leader steeper than this angle in degrees compete with the leader
+    leader_height: f32,         // height the leader is headed back to in m
+    cone_radius: f32,           // radius of the envelope cone at the first tier in m, it ends at the top of the leader
+}
+
+impl AutopruneCentralLeader {
+    pub const fn new() -> Self {
+        Self {
+            name: "central_leader",
+            trunk_height: 0.6,
+            tiers: 3,
+            tier_spacing: 0.8,
+            tier_height: 0.3,
+            scaffolds_per_tier: 4,
+            scaffold_angle: 60.,
+            scaffold_pole_length: 1.,
+            competitor_angle: 30.,
+            leader_height: 3.5,
+            cone_radius: 1.5,
+        }
+    }
+
+    pub const fn slender_spindle() -> Self {
+        Self {
+            name: "slender_spindle",
+            trunk_height: 0.5,
+            tiers: 1,
+            tier_spacing: 3.,
+            tier_height: 3.,
+            scaffolds_per_tier: 12,
+            scaffold_angle: 75.,
+            scaffold_pole_length: 0.6,
+            competitor_angle: 30.,
+            leader_height: 2.5,
+            cone_radius: 0.8,
+        }
+    }
+
+    // @returns the tier a height on the leader belongs to, none between and outside the tiers
+    fn tier(&self, height: f32) -> Option<u32> {
+        let height = height - meter_to_real_length(self.trunk_height);
+        if height < 0. {
+            return None;
+        }
+        let spacing = meter_to_real_length(self.tier_spacing);
+        let tier = (height / spacing).floor() as u32;
+        if tier >= self.tiers || height - tier as f32 * spacing > meter_to_real_length(self.tier_height) {
+            return None;
+        }
+        Some(tier)
+    }
+
+    // radius of the envelope cone at a height, the cone keeps its widest radius below the first tier
+    fn cone_radius(&self, height: f32) -> f32 {
+        let bottom = meter_to_real_length(self.trunk_height);
+        let top = meter_to_real_length(self.leader_height);
+        if top <= bottom {
+            return 0.;
+        }
+        let fraction = ((top - height) / (top - bottom)).clamp(0., 1.);
+        meter_to_real_length(self.cone_radius) * fraction
+    }
+
+    fn inside_cone(&self, point: Vecf3, base: Vecf3) -> bool {
+        let height = point.y - base.y;
+        let offset = Vecf3::new(point.x - base.x, 0., point.z - base.z);
+        offset.length() <= self.cone_radius(height)
+    }
+
+    // a shoot competes with the leader when it grows steep or higher than the leader
+    fn is_competitor(&self, shoot: &Metamer, leader_top: f32) -> bool {
+        let up = (shoot.direction() * Vecf3::new(0., 1., 0.)).clamp(-1., 1.);
+        up.acos() < self.competitor_angle.to_radians() || Self::top(shoot) > leader_top
+    }
+
+    // highest point of a metamer and its child metamers
+    fn top(metamer: &Metamer) -> f32 {
+        let mut top = metamer.end_point().y;
+        if let Some(m) = metamer.terminal_metamer() {
+            top = top.max(Self::top(m));
+        }
+        for m in metamer.lateral_metamers() {
+            top = top.max(Self::top(m));
+        }
+        top
+    }
+
+    // head back every shoot of a scaffold where it grows out of the cone
+    fn head_back_to_cone(&self, metamer: &mut Metamer, base: Vecf3) {
+        if let Some(m) = metamer.terminal_metamer() {
+            if !self.inside_cone(m.end_point(), base) {
+                metamer.prune_terminal();
+            }
+        }
+        if let Some(m) = metamer.auxillary_metamer() {
+            if !self.inside_cone(m.end_point(), base) {
+                metamer.prune_auxillary();
+            }
+        }
+        for bud in metamer.extra_buds_mut() {
+            if bud.metamer().is_some_and(|m| !self.inside_cone(m.end_point(), base)) {
+                bud.prune();
+            }
+        }
+        if let Some(m) = metamer.terminal_metamer_mut() {
+            self.head_back_to_cone(m, base);
+        }
+        for m in metamer.lateral_metamers_mut() {
+            self.head_back_to_cone(m, base);
+        }
+    }
+
+    fn leader_metamer(root: &mut Metamer, n: u32) -> Option<&mut Metamer> {
+        if n == 0 {
+            return Some(root);
+        }
+        Self::leader_metamer(root.terminal_metamer_mut()?, n - 1)
+    }
+
+    fn leader_top(root: &Metamer) -> f32 {
+        match root.terminal_metamer() {
+            Some(m) => Self::leader_top(m),
+            None => root.end_point().y,
+        }
+    }
+}
+
+impl PruningStrategy for AutopruneCentralLeader {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn parameters(&self) -> Vec<StrategyParameter> {
+        vec![
+            StrategyParameter::new("trunk_height", self.trunk_height, 0., 3., 0.05),
+            StrategyParameter::new("tiers", self.tiers as f32, 1., 6., 1.),
+            StrategyParameter::new("tier_spacing", self.tier_spacing, 0.1, 3., 0.05),
+            StrategyParameter::new("tier_height", self.tier_height, 0.05, 3., 0.05),
+            StrategyParameter::new("scaffolds_per_tier", self.scaffolds_per_tier as f32, 1., 12., 1.),
+            StrategyParameter::new("scaffold_angle", self.scaffold_angle, 10., 90., 1.),
+            StrategyParameter::new("scaffold_pole_length", self.scaffold_pole_length, 0.1, 5., 0.1),
+            StrategyParameter::new("competitor_angle", self.competitor_angle, 0., 90., 1.),
+            StrategyParameter::new("leader_height", self.leader_height, 0.5, 10., 0.1),
+            StrategyParameter::new("cone_radius", self.cone_radius, 0.1, 5., 0.1),
+        ]
+    }
+
+    fn update_parameter(&mut self, name: &str, value: f32) {
+        match name {
+            "trunk_height" => self.trunk_height = value,
+            "tiers" => self.tiers = value.round() as u32,
+            "tier_spacing" => self.tier_spacing = value,
+            "tier_height" => self.tier_height = value,
+            "scaffolds_per_tier" => self.scaffolds_per_tier = value.round() as u32,
+            "scaffold_angle" => self.scaffold_angle = value,
+            "scaffold_pole_length" => self.scaffold_pole_length = value,
+            "competitor_angle" => self.competitor_angle = value,
+            "leader_height" => self.leader_height = value,
+            "cone_radius" => self.cone_radius = value,
+            _ => {}
+        }
+    }
+
+    fn update_plant(&mut self, plant: &mut Plant) {
+        let base = plant.root().start_point();
+        let max_height = base.y + meter_to_real_length(self.leader_height);
+        let leader_top = Self::leader_top(plant.root()).min(max_height);
+
+        // scaffolds kept so far in every tier
+        let mut scaffolds = vec![0; self.tiers as usize];
+        let mut n = 0;
+
+        let (root, ids) = plant.root_and_ids_mut();
+        while let Some(m) = Self::leader_metamer(root, n) {
+            // head back the leader at its max height
+            if m.end_point().y > max_height {
+                m.prune_terminal();
+                m.prune_laterals();
+                break;
+            }
+
+            let tier = self.tier(m.end_point().y - base.y);
+            let competitor = m
+                .auxillary_metamer()
+                .is_some_and(|shoot| self.is_competitor(shoot, leader_top));
+
+            match tier {
+                Some(tier) if !competitor && scaffolds[tier as usize] < self.scaffolds_per_tier => {
+                    // one scaffold per node
+                    m.prune_extra_buds();
+                    if let Some(shoot) = m.auxillary_metamer_mut() {
+                        self.head_back_to_cone(shoot, base);
+                    } else if m.aux_support_pole.is_none() {
+                        let mut pole = SupportPole::new(
+                            self.scaffold_pole_length,
+                            m.end_point(),
+                            scaffold_pole_dir(m.auxillary_azimuth(), self.scaffold_angle),
+                            true,
+                            ids.get_id(),
+                        );
+                        pole.update_width(0.0001);
+                        m.aux_support_pole = Some(pole);
+                    }
+                    scaffolds[tier as usize] += 1;
+                }
+                // trunk, space between tiers, full tiers and competing leaders are kept clear
+                _ => m.prune_laterals(),
+            }
+            n += 1;
+        }
+    }
+
+    // the frame is pruned in the dormant season
+    fn season(&self) -> PruningSeason {
+        PruningSeason::Winter
+    }
+
+    fn clone_box(&self) -> Box<dyn PruningStrategy> {
+        Box::new(self.clone())
+    }
+}
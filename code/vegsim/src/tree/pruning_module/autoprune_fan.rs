@@ -0,0 +1,118 @@
+use patutil::Vecf3;
+
+use crate::tree::{metamer::Metamer, plant::Plant, support_pole::SupportPole};
+
+use super::strategy::{branch_maintenance, PruningSeason, PruningStrategy, StrategyParameter};
+
+/**
+ * Fan training: a short trunk with ribs tied in a fan shape against a wall
+ */
+#[derive(Debug, Clone)]
+pub struct AutopruneFan {
+    trunk_length: u32,      // metamers of the trunk below the first rib
+    ribs: u32,              // amount of branches in the fan
+    fan_angle: f32,         // angle between the outer ribs in degrees
+    rib_pole_length: f32,   // length of the supportpoles for the ribs in m
+    rib_length: u32,        // max metamers of a rib
+    side_shoot_length: u32, // max metamers of a side shoot on a rib
+}
+
+impl AutopruneFan {
+    pub const fn new() -> Self {
+        Self {
+            trunk_length: 3,
+            ribs: 5,
+            fan_angle: 120.,
+            rib_pole_length: 1.7,
+            rib_length: 20,
+            side_shoot_length: 2,
+        }
+    }
+
+    // direction of a rib in the wall plane, the ribs go from left to right
+    fn rib_dir(&self, rib: u32) -> Vecf3 {
+        let angle = if self.ribs > 1 {
+            -self.fan_angle / 2. + self.fan_angle * rib as f32 / (self.ribs - 1) as f32
+        } else {
+            0.
+        };
+        let angle = angle.to_radians();
+        Vecf3::new(angle.sin(), angle.cos(), 0.)
+    }
+
+    fn trunk_metamer(root: &mut Metamer, n: u32) -> Option<&mut Metamer> {
+        if n == 0 {
+            return Some(root);
+        }
+        Self::trunk_metamer(root.terminal_metamer_mut()?, n - 1)
+    }
+}
+
+impl PruningStrategy for AutopruneFan {
+    fn name(&self) -> &'static str {
+        "fan"
+    }
+
+    fn parameters(&self) -> Vec<StrategyParameter> {
+        vec![
+            StrategyParameter::new("trunk_length", self.trunk_length as f32, 0., 20., 1.),
+            StrategyParameter::new("ribs", self.ribs as f32, 1., 12., 1.),
+            StrategyParameter::new("fan_angle", self.fan_angle, 0., 180., 1.),
+            StrategyParameter::new("rib_pole_length", self.rib_pole_length, 0.1, 5., 0.1),
+            StrategyParameter::new("rib_length", self.rib_length as f32, 1., 50., 1.),
+            StrategyParameter::new("side_shoot_length", self.side_shoot_length as f32, 1., 10., 1.),
+        ]
+    }
+
+    fn update_parameter(&mut self, name: &str, value: f32) {
+        match name {
+            "trunk_length" => self.trunk_length = value.round() as u32,
+            "ribs" => self.ribs = value.round() as u32,
+            "fan_angle" => self.fan_angle = value,
+            "rib_pole_length" => self.rib_pole_length = value,
+            "rib_length" => self.rib_length = value.round() as u32,
+            "side_shoot_length" => self.side_shoot_length = value.round() as u32,
+            _ => {}
+        }
+    }
+
+    fn update_plant(&mut self, plant: &mut Plant) {
+        let last_rib = self.trunk_length + self.ribs - 1;
+        let mut n = 0;
+
+        let (root, ids) = plant.root_and_ids_mut();
+        while let Some(m) = Self::trunk_metamer(root, n) {
+            // trunk below the fan is kept clear
+            if n < self.trunk_length {
+                m.prune_laterals();
+            } else {
+                let rib = n - self.trunk_length;
+                // one rib per node
+                m.prune_extra_buds();
+                if let Some(b) = m.auxillary_metamer_mut() {
+                    branch_maintenance(b, self.rib_length, self.side_shoot_length);
+                } else if m.aux_support_pole.is_none() {
+                    let mut pole = SupportPole::new(self.rib_pole_length, m.end_point(), self.rib_dir(rib), true, ids.get_id());
+                    pole.update_width(0.0001);
+                    m.aux_support_pole = Some(pole);
+                }
+
+                // the trunk stops at the last rib so the ribs get all resources
+                if n == last_rib {
+                    m.prune_terminal();
+                    break;
+                }
+            }
+            n += 1;
+        }
+    }
+
+    // trained and tied in while the shoots grow
+    fn season(&self) -> PruningSeason {
+        PruningSeason::Summer
+    }
+
+    fn clone_box(&self) -> Box<dyn PruningStrategy> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,73 @@
+use patutil::Vecf3;
+
+use crate::tree::plant::Plant;
+
+use super::{
+    hedge::PruningVolume,
+    strategy::{PruningSeason, PruningStrategy, StrategyParameter},
+};
+
+/**
+ * Mechanical hedging of an orchard row: the tree is trimmed to a box around the row,
+ * the row runs along the x axis like the wall of a spalier or fan
+ */
+#[derive(Debug, Clone)]
+pub struct AutopruneHedge {
+    height: f32,     // height of the hedge above the base of the tree in m
+    width: f32,      // thickness of the hedge across the row in m
+    row_length: f32, // length of the hedge along the row in m
+}
+
+impl AutopruneHedge {
+    pub const fn new() -> Self {
+        Self {
+            height: 2.5,
+            width: 1.2,
+            row_length: 3.,
+        }
+    }
+
+    // box around the row, standing on the base of the tree
+    fn volume(&self) -> PruningVolume {
+        PruningVolume::Box {
+            center: Vecf3::new(0., self.height / 2., 0.),
+            size: Vecf3::new(self.row_length, self.height, self.width),
+        }
+    }
+}
+
+impl PruningStrategy for AutopruneHedge {
+    fn name(&self) -> &'static str {
+        "hedge"
+    }
+
+    fn parameters(&self) -> Vec<StrategyParameter> {
+        vec![
+            StrategyParameter::new("height", self.height, 0.5, 6., 0.1),
+            StrategyParameter::new("width", self.width, 0.2, 5., 0.1),
+            StrategyParameter::new("row_length", self.row_length, 0.5, 20., 0.5),
+        ]
+    }
+
+    fn update_parameter(&mut self, name: &str, value: f32) {
+        match name {
+            "height" => self.height = value,
+            "width" => self.width = value,
+            "row_length" => self.row_length = value,
+            _ => {}
+        }
+    }
+
+    fn update_plant(&mut self, plant: &mut Plant) {
+        self.volume().trim(plant);
+    }
+
+    // hedges are trimmed while the shoots grow
+    fn season(&self) -> PruningSeason {
+        PruningSeason::Summer
+    }
+
+    fn clone_box(&self) -> Box<dyn PruningStrategy> {
+        Box::new(self.clone())
+    }
+}
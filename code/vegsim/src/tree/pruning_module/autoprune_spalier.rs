@@ -2,129 +2,136 @@ use patutil::Vecf3;
 
 use crate::tree::{plant::Plant, metamer::Metamer, support_pole::SupportPole};
 
+use super::strategy::{branch_maintenance, PruningSeason, PruningStrategy, StrategyParameter};
 
 
-
-const PASS_LENGTH: u32 = 6;
-
-
+#[derive(Debug, Clone)]
 pub struct AutopruneSpalier{
+    pass_length: u32,           // metamers in one layer of the spalier
+    max_trunk_metamers: u32,    // height limit of the tree
+    branch_pole_length: f32,    // length of the supportpoles for the branches in m
+    trunk_pole_length: f32,     // length of the supportpole for the next trunk part in m
+    branch_length: u32,         // max metamers of a supported branch
+    side_shoot_length: u32,     // max metamers of a side shoot on a supported branch
 }
 
 impl AutopruneSpalier {
-    pub const fn new() -> Self { Self {  } }
+    pub const fn new() -> Self {
+        Self {
+            pass_length: 6,
+            max_trunk_metamers: 23,
+            branch_pole_length: 1.7,
+            trunk_pole_length: 1.,
+            branch_length: 20,
+            side_shoot_length: 2,
+        }
+    }
 
-    pub fn update_plant(&mut self, plant: &mut Plant){
+    fn get_trunk_met_by_numb(root: &mut Metamer, n: u32, pass: u32, pass_length: u32) -> Option<&mut Metamer>{
+        if n == 0{
+            return Some(root);
+        }
+        if pass > 1{
+            if root.terminal_metamer().is_some(){
+                return Self::get_trunk_met_by_numb(root.terminal_metamer_mut().unwrap(), n-1, pass - 1, pass_length);
+            }
+        } else {
+            if let Some(m) = root.auxillary_metamer_mut(){
+                return Self::get_trunk_met_by_numb(m, n-1, pass_length, pass_length);
+            }
+        }
+        return None;
+    }
+}
+
+impl PruningStrategy for AutopruneSpalier {
+    fn name(&self) -> &'static str {
+        "spalier"
+    }
+
+    fn parameters(&self) -> Vec<StrategyParameter> {
+        vec![
+            StrategyParameter::new("pass_length", self.pass_length as f32, 4., 12., 1.),
+            StrategyParameter::new("max_trunk_metamers", self.max_trunk_metamers as f32, 1., 100., 1.),
+            StrategyParameter::new("branch_pole_length", self.branch_pole_length, 0.1, 5., 0.1),
+            StrategyParameter::new("trunk_pole_length", self.trunk_pole_length, 0.1, 5., 0.1),
+            StrategyParameter::new("branch_length", self.branch_length as f32, 1., 50., 1.),
+            StrategyParameter::new("side_shoot_length", self.side_shoot_length as f32, 1., 10., 1.),
+        ]
+    }
+
+    fn update_parameter(&mut self, name: &str, value: f32) {
+        match name {
+            "pass_length" => self.pass_length = value.round() as u32,
+            "max_trunk_metamers" => self.max_trunk_metamers = value.round() as u32,
+            "branch_pole_length" => self.branch_pole_length = value,
+            "trunk_pole_length" => self.trunk_pole_length = value,
+            "branch_length" => self.branch_length = value.round() as u32,
+            "side_shoot_length" => self.side_shoot_length = value.round() as u32,
+            _ => {}
+        }
+    }
+
+    fn update_plant(&mut self, plant: &mut Plant){
+        let pass_length = self.pass_length;
         let mut n = 0;
 
         // perform prune rule for every spalier trunk metamer
         // does this layer per layer
-        // layers contain PASS_LENGTH metamers
-        while let Some(m) = Self::get_trunk_met_by_numb(plant.root_mut(), n, PASS_LENGTH){
-            // limit the height of the tree to 23 metamers
-            if n > 23{
-                m.prune_auxillary();
+        // layers contain pass_length metamers
+        let (root, ids) = plant.root_and_ids_mut();
+        while let Some(m) = Self::get_trunk_met_by_numb(root, n, pass_length, pass_length){
+            // limit the height of the tree
+            if n > self.max_trunk_metamers{
+                m.prune_laterals();
                 m.prune_terminal();
                 break;
             }
 
-            // first 3 metamers of every layer is pruned for spacing
-            if n % PASS_LENGTH < PASS_LENGTH - 3{
-                m.prune_auxillary();
+            // first metamers of every layer are pruned for spacing
+            if n % pass_length < pass_length - 3{
+                m.prune_laterals();
+            } else {
+                // one branch per node
+                m.prune_extra_buds();
             }
-            // metamer 4 in layer needs to be a supported branch to the left
-            if n % PASS_LENGTH == PASS_LENGTH - 3{
+            // third last metamer in layer needs to be a supported branch to the left
+            if n % pass_length == pass_length - 3{
 
-                if let Some(b) = m.auxillary_metamer(){
-                    Self::branch_maintanance(b);
+                if let Some(b) = m.auxillary_metamer_mut(){
+                    branch_maintenance(b, self.branch_length, self.side_shoot_length);
                 } else{
-                    let mut pole = SupportPole::new(1.7, m.end_point(), Vecf3::new(-1., 0., 0.), true);
+                    let mut pole = SupportPole::new(self.branch_pole_length, m.end_point(), Vecf3::new(-1., 0., 0.), true, ids.get_id());
                     pole.update_width(0.0001);
                     m.aux_support_pole = Some(pole);
                 }
             }
-            // metamer 5 in layer needs to be a supported branch to the right
-            if n % PASS_LENGTH == PASS_LENGTH - 2{
-                if let Some(b) = m.auxillary_metamer(){
-                    Self::branch_maintanance(b);
+            // second last metamer in layer needs to be a supported branch to the right
+            if n % pass_length == pass_length - 2{
+                if let Some(b) = m.auxillary_metamer_mut(){
+                    branch_maintenance(b, self.branch_length, self.side_shoot_length);
                 } else{
-                    let mut pole = SupportPole::new(1.7, m.end_point(), Vecf3::new(1., 0., 0.), true);
+                    let mut pole = SupportPole::new(self.branch_pole_length, m.end_point(), Vecf3::new(1., 0., 0.), true, ids.get_id());
                     pole.update_width(0.0001);
                     m.aux_support_pole = Some(pole);
                 }
             }
-            // metamer 6 in layer needs to be pruned on terminal bud for branches to get more resources
+            // last metamer in layer needs to be pruned on terminal bud for branches to get more resources
             // auxillary bud takes role of trunk
-            if n % PASS_LENGTH == PASS_LENGTH - 1{
+            if n % pass_length == pass_length - 1{
                 m.prune_terminal();
-                m.aux_support_pole = Some(SupportPole::new(1., m.end_point(), Vecf3::new(0., 1., 0.), false));
+                m.aux_support_pole = Some(SupportPole::new(self.trunk_pole_length, m.end_point(), Vecf3::new(0., 1., 0.), false, ids.get_id()));
             }
             n += 1;
         }
     }
 
-    fn branch_maintanance(root: &mut Metamer){
-        Self::short_metamer_length(root, 20);
-        Self::short_branch_aux(root);
+    // trained and tied in while the shoots grow
+    fn season(&self) -> PruningSeason {
+        PruningSeason::Summer
     }
 
-    fn short_branch_aux(root: &mut Metamer){
-        if let Some(aux) = root.auxillary_metamer(){
-            Self::short_metamer_length(aux, 2);
-        }
-        if let Some(term) = root.terminal_metamer_mut(){
-            Self::branch_maintanance(term);
-        }
+    fn clone_box(&self) -> Box<dyn PruningStrategy> {
+        Box::new(self.clone())
     }
-
-    fn short_metamer_length(root:&mut Metamer, length: u32){
-        if length == 1{
-            root.prune_terminal();
-            root.prune_auxillary();
-            return;
-        }
-        if let Some(metamer) = root.terminal_metamer_mut(){
-            Self::short_metamer_buds(metamer, length-1);
-        }
-        if let Some(metamer) = root.auxillary_metamer(){
-            Self::short_metamer_buds(metamer, length-1);
-        }
-    }
-    fn short_metamer_buds(root:&mut Metamer, max_buds: u32){
-        if max_buds == 1{
-            root.prune_terminal();
-            root.prune_auxillary();
-            return;
-        }
-        if let Some(metamer) = root.terminal_metamer_mut(){
-            Self::short_metamer_buds(metamer, max_buds-1);
-        }
-        if let Some(metamer) = root.auxillary_metamer(){
-            Self::short_metamer_buds(metamer, max_buds-1);
-        }
-    }
-
-    fn get_trunk_met_by_numb(root: &mut Metamer, n: u32, pass: u32) -> Option<&mut Metamer>{
-        if n == 0{
-            return Some(root);
-        }
-        if pass > 1{
-            if root.terminal_metamer().is_some(){
-                return Self::get_trunk_met_by_numb(root.terminal_metamer_mut().unwrap(), n-1, pass - 1);
-            }
-        } else {
-            if let Some(m) = root.auxillary_metamer(){
-                return Self::get_trunk_met_by_numb(m, n-1, PASS_LENGTH);
-            }
-        }
-        return None;
-    }
-
-    fn term_length(metamer: &Metamer) -> u32{
-        if let Some(m) = metamer.terminal_metamer(){
-            return Self::term_length(m) + 1;
-        }
-
-        return 1;
-    }
-}
\ No newline at end of file
+}
@@ -0,0 +1,284 @@
+use std::f32::consts::PI;
+
+use patutil::Vecf3;
+
+use crate::{
+    tree::{budfate::BudState, metamer::Metamer, plant::Plant, support_pole::SupportPole},
+    util::meter_to_real_length,
+};
+
+use super::strategy::{scaffold_pole_dir, PruningSeason, PruningStrategy, StrategyParameter};
+
+// node of the trunk up to the height it is headed back at
+struct TrunkNode {
+    height: f32,
+    azimuth: f32,
+    id: u32,
+    // the axillary bud can still grow a scaffold or has grown one
+    bud_alive: bool,
+}
+
+/**
+ * Open center (vase) training: the trunk is headed back once it is tall enough and
+ * a few scaffolds spread around it form the vase, shoots growing into the center are thinned out.
+ * The scaffolds are chosen once and kept, the azimuths of nearly upright buds change as the trunk bends.
+ */
+#[derive(Debug, Clone)]
+pub struct AutopruneVase {
+    trunk_height: f32,          // height the trunk is headed back at in m
+    scaffolds: u32,             // amount of scaffold branches
+    candidate_zone: f32,        // part of the trunk below the cut where scaffolds are chosen in m
+    scaffold_angle: f32,        // angle between a scaffold and the vertical in degrees
+    scaffold_pole_length: f32,  // length of the supportpoles for new scaffolds in m
+    inward_angle: f32,          // shoots growing closer than this angle in degrees to the center are thinned out
+    scaffold_ids: Vec<u32>,     // trunk metamers carrying the scaffolds, empty until they are chosen
+}
+
+impl AutopruneVase {
+    pub const fn new() -> Self {
+        Self {
+            trunk_height: 0.7,
+            scaffolds: 3,
+            candidate_zone: 0.4,
+            scaffold_angle: 50.,
+            scaffold_pole_length: 1.,
+            inward_angle: 60.,
+            scaffold_ids: vec![],
+        }
+    }
+
+    fn trunk_metamer(root: &mut Metamer, n: usize) -> Option<&mut Metamer> {
+        if n == 0 {
+            return Some(root);
+        }
+        Self::trunk_metamer(root.terminal_metamer_mut()?, n - 1)
+    }
+
+    // the trunk metamers, up to the first one reaching the given height
+    fn trunk_nodes(root: &Metamer, base: f32, height: f32) -> Vec<TrunkNode> {
+        let mut nodes = vec![];
+        let mut metamer = Some(root);
+        while let Some(m) = metamer {
+            let bud_alive = m.auxillary_metamer().is_some()
+                || m.aux_support_pole.is_some()
+                || (m.auxillary_bud_damage == 0. && m.aux_bud_fate.state() != BudState::Dead);
+            nodes.push(TrunkNode {
+                height: m.end_point().y - base,
+                azimuth: m.auxillary_azimuth(),
+                id: m.id(),
+                bud_alive,
+            });
+            if m.end_point().y - base >= height {
+                break;
+            }
+            metamer = m.terminal_metamer();
+        }
+        nodes
+    }
+
+    // smallest angle between two of the azimuths, going around the circle
+    fn min_gap(azimuths: &[f32]) -> f32 {
+        let mut sorted = azimuths.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let mut gap = 2. * PI;
+        for pair in sorted.windows(2) {
+            gap = gap.min(pair[1] - pair[0]);
+        }
+        if let (Some(first), Some(last)) = (sorted.first(), sorted.last()) {
+            if sorted.len() > 1 {
+                gap = gap.min(2. * PI + first - last);
+            }
+        }
+        gap
+    }
+
+    // angle between two azimuths, going around the circle
+    fn azimuth_distance(a: f32, b: f32) -> f32 {
+        let distance = (a - b).abs() % (2. * PI);
+        distance.min(2. * PI - distance)
+    }
+
+    /**
+     * Choose candidates spread around the trunk: starting from every candidate add the one furthest
+     * from those chosen until there are enough, keep the start with the widest smallest gap.
+     * The lowest ones win a tie.
+     */
+    fn select_scaffolds(candidates: &[(usize, f32)], amount: usize) -> Vec<usize> {
+        let amount = amount.min(candidates.len());
+        let mut best = (-1., vec![]);
+        for start in 0..candidates.len() {
+            let mut chosen = vec![candidates[start]];
+            while chosen.len() < amount {
+                let mut next = None;
+                for &candidate in candidates {
+                    if chosen.iter().any(|(index, _)| *index == candidate.0) {
+                        continue;
+                    }
+                    let distance = chosen
+                        .iter()
+                        .map(|(_, azimuth)| Self::azimuth_distance(*azimuth, candidate.1))
+                        .fold(f32::MAX, f32::min);
+                    if next.is_none_or(|(_, furthest)| distance > furthest) {
+                        next = Some((candidate, distance));
+                    }
+                }
+                match next {
+                    Some((candidate, _)) => chosen.push(candidate),
+                    None => break,
+                }
+            }
+
+            let azimuths: Vec<f32> = chosen.iter().map(|(_, azimuth)| *azimuth).collect();
+            let gap = Self::min_gap(&azimuths);
+            if gap > best.0 {
+                best = (gap, chosen.iter().map(|(index, _)| *index).collect());
+            }
+        }
+        best.1
+    }
+
+    // thin out the side shoots of a scaffold that grow into the center of the vase
+    fn thin_inward(&self, metamer: &mut Metamer, center: Vecf3) {
+        let inward = metamer
+            .auxillary_metamer()
+            .and_then(|shoot| shoot.angle_to_axis(center))
+            .is_some_and(|angle| angle < self.inward_angle.to_radians());
+        if inward {
+            metamer.prune_auxillary();
+        }
+        for bud in metamer.extra_buds_mut() {
+            let inward = bud
+                .metamer()
+                .and_then(|shoot| shoot.angle_to_axis(center))
+                .is_some_and(|angle| angle < self.inward_angle.to_radians());
+            if inward {
+                bud.prune();
+            }
+        }
+
+        if let Some(m) = metamer.terminal_metamer_mut() {
+            self.thin_inward(m, center);
+        }
+        for m in metamer.lateral_metamers_mut() {
+            self.thin_inward(m, center);
+        }
+    }
+}
+
+impl PruningStrategy for AutopruneVase {
+    fn name(&self) -> &'static str {
+        "vase"
+    }
+
+    fn parameters(&self) -> Vec<StrategyParameter> {
+        vec![
+            StrategyParameter::new("trunk_height", self.trunk_height, 0.2, 3., 0.05),
+            StrategyParameter::new("scaffolds", self.scaffolds as f32, 2., 6., 1.),
+            StrategyParameter::new("candidate_zone", self.candidate_zone, 0.1, 1.5, 0.05),
+            StrategyParameter::new("scaffold_angle", self.scaffold_angle, 10., 90., 1.),
+            StrategyParameter::new("scaffold_pole_length", self.scaffold_pole_length, 0.1, 5., 0.1),
+            StrategyParameter::new("inward_angle", self.inward_angle, 0., 90., 1.),
+        ]
+    }
+
+    fn update_parameter(&mut self, name: &str, value: f32) {
+        match name {
+            // the scaffolds are chosen again for the new trunk
+            "trunk_height" => {
+                self.trunk_height = value;
+                self.scaffold_ids.clear();
+            }
+            "scaffolds" => {
+                self.scaffolds = value.round() as u32;
+                self.scaffold_ids.clear();
+            }
+            "candidate_zone" => {
+                self.candidate_zone = value;
+                self.scaffold_ids.clear();
+            }
+            "scaffold_angle" => self.scaffold_angle = value,
+            "scaffold_pole_length" => self.scaffold_pole_length = value,
+            "inward_angle" => self.inward_angle = value,
+            _ => {}
+        }
+    }
+
+    fn update_plant(&mut self, plant: &mut Plant) {
+        let base = plant.root().start_point();
+        let trunk_height = meter_to_real_length(self.trunk_height);
+        let trunk = Self::trunk_nodes(plant.root(), base.y, trunk_height);
+        let top = trunk.len() - 1;
+
+        if self.scaffold_ids.is_empty() {
+            // the trunk grows freely until it reaches its height
+            if trunk[top].height < trunk_height {
+                return;
+            }
+
+            let zone_bottom = trunk_height - meter_to_real_length(self.candidate_zone);
+            let candidates: Vec<(usize, f32)> = (0..=top)
+                .filter(|&i| trunk[i].height >= zone_bottom && trunk[i].bud_alive)
+                .map(|i| (i, trunk[i].azimuth))
+                .collect();
+            self.scaffold_ids = Self::select_scaffolds(&candidates, self.scaffolds as usize)
+                .into_iter()
+                .map(|i| trunk[i].id)
+                .collect();
+        }
+
+        let (root, ids) = plant.root_and_ids_mut();
+        for n in 0..=top {
+            let m = match Self::trunk_metamer(root, n) {
+                Some(m) => m,
+                None => break,
+            };
+            if n == top {
+                m.prune_terminal();
+            }
+
+            if !self.scaffold_ids.contains(&m.id()) {
+                m.prune_laterals();
+                continue;
+            }
+            // one scaffold per node
+            m.prune_extra_buds();
+            if let Some(shoot) = m.auxillary_metamer_mut() {
+                self.thin_inward(shoot, base);
+            } else if m.aux_support_pole.is_none() {
+                let mut pole = SupportPole::new(
+                    self.scaffold_pole_length,
+                    m.end_point(),
+                    scaffold_pole_dir(m.auxillary_azimuth(), self.scaffold_angle),
+                    true,
+                    ids.get_id(),
+                );
+                pole.update_width(0.0001);
+                m.aux_support_pole = Some(pole);
+            }
+        }
+    }
+
+    // the frame is pruned in the dormant season
+    fn season(&self) -> PruningSeason {
+        PruningSeason::Winter
+    }
+
+    fn state(&self) -> String {
+        let ids: Vec<String> = self.scaffold_ids.iter().map(|id| id.to_string()).collect();
+        ids.join(" ")
+    }
+
+    fn set_state(&mut self, state: &str) -> bool {
+        match state.split_whitespace().map(|id| id.parse()).collect() {
+            Ok(ids) => {
+                self.scaffold_ids = ids;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn PruningStrategy> {
+        Box::new(self.clone())
+    }
+}
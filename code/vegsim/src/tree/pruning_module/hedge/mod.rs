@@ -0,0 +1,353 @@
+/*
+ * Hedge trimming: every part of the tree outside a volume is cut off where it leaves the volume.
+ * Volumes are written as text in m relative to the base of the tree, with y pointing up:
+ *
+ *     plane point{x y z} normal{x y z}            keeps the side the normal points away from
+ *     box center{x y z} size{x y z}
+ *     cylinder center{x y z} diameter{d} height{h}  vertical cylinder
+ *     csg{<csg scene>}                            bricks as saved by the patutil csg editor
+ */
+mod tests;
+
+use std::{
+    fmt::{self, Display},
+    fs, io,
+    path::Path,
+    str::FromStr,
+};
+
+use patfile::{pscan, pwrite};
+use patutil::{
+    csg::{Brick, BrickOp, BrickRef, BrickType, CSGScene},
+    Vecf3, Vecf4,
+};
+
+use crate::{
+    tree::{
+        metamer::{Metamer, MIN_CUT_FRACTION},
+        plant::Plant,
+    },
+    util::meter_to_real_length,
+};
+
+// extension of volume files
+pub const VOLUME_EXTENSION: &str = "txt";
+
+// precision of the cut on the border of a volume, as a fraction of the metamer length
+const BORDER_PRECISION: f32 = 0.01;
+
+#[derive(Debug)]
+pub enum VolumeError {
+    Io(io::Error),
+    Format(String),
+}
+
+impl std::error::Error for VolumeError {}
+
+impl Display for VolumeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VolumeError::Io(err) => write!(f, "could not access volume file: {}", err),
+            VolumeError::Format(text) => write!(f, "expected a plane, box, cylinder or csg volume, found '{}'", text),
+        }
+    }
+}
+
+impl From<io::Error> for VolumeError {
+    fn from(err: io::Error) -> Self {
+        VolumeError::Io(err)
+    }
+}
+
+/**
+ * Space the tree is trimmed to, positions and sizes are in m relative to the base of the tree
+ */
+#[derive(Debug, Clone)]
+pub enum PruningVolume {
+    Plane { point: Vecf3, normal: Vecf3 },
+    Box { center: Vecf3, size: Vecf3 },
+    Cylinder { center: Vecf3, diameter: f32, height: f32 },
+    Csg(BrickRef),
+}
+
+impl PruningVolume {
+    pub fn load(path: &Path) -> Result<Self, VolumeError> {
+        let text = fs::read_to_string(path)?;
+        text.trim().parse()
+    }
+
+    // @param point position relative to the base of the tree in simulation units
+    pub fn contains(&self, point: Vecf3) -> bool {
+        let point = point * (1. / meter_to_real_length(1.));
+        match self {
+            PruningVolume::Plane { point: on_plane, normal } => (point - *on_plane) * *normal <= 0.,
+            PruningVolume::Csg(brick) => csg_distance(point, Some(brick.clone())) <= 0.,
+            _ => match self.brick() {
+                Some(brick) => brick_distance(point - to_vec3(brick.pos()), &brick) <= 0.,
+                None => false,
+            },
+        }
+    }
+
+    // the box and cylinder are checked as csg bricks
+    fn brick(&self) -> Option<Brick> {
+        let (brick_type, center, size) = match self {
+            PruningVolume::Box { center, size } => (BrickType::Box, *center, *size),
+            PruningVolume::Cylinder { center, diameter, height } => {
+                (BrickType::Cylinder, *center, Vecf3::new(*diameter, *height, *diameter))
+            }
+            _ => return None,
+        };
+        let brick: BrickRef = Brick::new(brick_type, BrickOp::Union, brick_type.to_string()).into();
+        brick.controller().set_pos([center.x, center.y, center.z, 0.]);
+        brick.controller().set_size([size.x, size.y, size.z, 1.]);
+        Some(brick.get())
+    }
+
+    /**
+     * Cut every metamer where it leaves the volume, parts that start outside the volume are removed.
+     * Metamers that would be cut below the minimal cut fraction are removed completely,
+     * only the root is kept at its shortest length.
+     * @returns amount of metamers removed
+     */
+    pub fn trim(&self, plant: &mut Plant) -> u32 {
+        let base = plant.root().start_point();
+        let root = plant.root_mut();
+        let (mut removed, remove_root) = self.trim_metamer(root, base);
+        if remove_root {
+            removed -= 1;
+            root.cut_at(MIN_CUT_FRACTION);
+        }
+        plant.update_metamer_widths();
+        removed
+    }
+
+    // @returns amount of metamers removed and if the metamer itself has to be removed by its parent
+    fn trim_metamer(&self, metamer: &mut Metamer, base: Vecf3) -> (u32, bool) {
+        if !self.contains(metamer.end_point() - base) {
+            let fraction = self.border_fraction(metamer.start_point() - base, metamer.end_point() - base);
+            if fraction < MIN_CUT_FRACTION {
+                return (metamer.count_metamers(), true);
+            }
+            let removed = metamer.count_metamers() - 1;
+            metamer.cut_at(fraction);
+            return (removed, false);
+        }
+
+        let mut removed = 0;
+        let mut remove = false;
+        if let Some(m) = metamer.terminal_metamer_mut() {
+            let (count, remove_child) = self.trim_metamer(m, base);
+            removed += count;
+            remove = remove_child;
+        }
+        if remove {
+            metamer.prune_terminal();
+        }
+        remove = false;
+        if let Some(m) = metamer.auxillary_metamer_mut() {
+            let (count, remove_child) = self.trim_metamer(m, base);
+            removed += count;
+            remove = remove_child;
+        }
+        if remove {
+            metamer.prune_auxillary();
+        }
+        for bud in metamer.extra_buds_mut() {
+            if let Some(m) = bud.metamer_mut() {
+                let (count, remove_child) = self.trim_metamer(m, base);
+                removed += count;
+                if remove_child {
+                    bud.prune();
+                }
+            }
+        }
+        (removed, false)
+    }
+
+    // fraction along a segment with an end outside the volume where it leaves the volume
+    fn border_fraction(&self, start: Vecf3, end: Vecf3) -> f32 {
+        if !self.contains(start) {
+            return 0.;
+        }
+        let (mut inside, mut outside) = (0., 1.);
+        while outside - inside > BORDER_PRECISION {
+            let middle = (inside + outside) / 2.;
+            if self.contains(start + (end - start) * middle) {
+                inside = middle;
+            } else {
+                outside = middle;
+            }
+        }
+        inside
+    }
+}
+
+macro_rules! PLANE_STRING_FORMAT {
+    () => {
+        "plane point{} normal{}"
+    };
+}
+macro_rules! BOX_STRING_FORMAT {
+    () => {
+        "box center{} size{}"
+    };
+}
+macro_rules! CYLINDER_STRING_FORMAT {
+    () => {
+        "cylinder center{} diameter{} height{}"
+    };
+}
+macro_rules! CSG_STRING_FORMAT {
+    () => {
+        "csg{}"
+    };
+}
+
+impl FromStr for PruningVolume {
+    type Err = VolumeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || VolumeError::Format(s.lines().next().unwrap_or_default().to_string());
+        let mut it = s.bytes();
+        let (mut center, mut vector) = (String::new(), String::new());
+
+        if s.starts_with("plane") {
+            pscan!(&mut it => PLANE_STRING_FORMAT!(), center, vector).map_err(|_| error())?;
+            let normal = parse_vec(&vector).filter(|normal| normal.length() > 0.).ok_or_else(error)?;
+            let point = parse_vec(&center).ok_or_else(error)?;
+            Ok(PruningVolume::Plane { point, normal: normal.norm() })
+        } else if s.starts_with("box") {
+            pscan!(&mut it => BOX_STRING_FORMAT!(), center, vector).map_err(|_| error())?;
+            let center = parse_vec(&center).ok_or_else(error)?;
+            let size = parse_vec(&vector).ok_or_else(error)?;
+            Ok(PruningVolume::Box { center, size })
+        } else if s.starts_with("cylinder") {
+            let (mut diameter, mut height) = (0f32, 0f32);
+            pscan!(&mut it => CYLINDER_STRING_FORMAT!(), center, diameter, height).map_err(|_| error())?;
+            let center = parse_vec(&center).ok_or_else(error)?;
+            Ok(PruningVolume::Cylinder { center, diameter, height })
+        } else if s.starts_with("csg") {
+            let mut scene = String::new();
+            pscan!(&mut it => CSG_STRING_FORMAT!(), scene).map_err(|_| error())?;
+            let scene: CSGScene = scene.parse().map_err(|_| error())?;
+            scene.get_brick().map(PruningVolume::Csg).ok_or_else(error)
+        } else {
+            Err(error())
+        }
+    }
+}
+
+impl Display for PruningVolume {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        match self {
+            PruningVolume::Plane { point, normal } => {
+                pwrite!(PLANE_STRING_FORMAT!() => writer, write_vec(*point), write_vec(*normal))
+            }
+            PruningVolume::Box { center, size } => {
+                pwrite!(BOX_STRING_FORMAT!() => writer, write_vec(*center), write_vec(*size))
+            }
+            PruningVolume::Cylinder { center, diameter, height } => {
+                pwrite!(CYLINDER_STRING_FORMAT!() => writer, write_vec(*center), diameter, height)
+            }
+            PruningVolume::Csg(brick) => {
+                pwrite!(CSG_STRING_FORMAT!() => writer, CSGScene::from(brick.clone()))
+            }
+        }
+        .map_err(|_| fmt::Error)
+    }
+}
+
+// vectors are written as "x y z" so volume files are easy to write by hand
+fn parse_vec(s: &str) -> Option<Vecf3> {
+    let values: Vec<f32> = s.split_whitespace().map(|v| v.parse().ok()).collect::<Option<_>>()?;
+    match values[..] {
+        [x, y, z] => Some(Vecf3::new(x, y, z)),
+        _ => None,
+    }
+}
+
+fn write_vec(v: Vecf3) -> String {
+    format!("{} {} {}", v.x, v.y, v.z)
+}
+
+fn to_vec3(v: Vecf4) -> Vecf3 {
+    Vecf3::new(v[0], v[1], v[2])
+}
+
+/*
+ * Signed distances of csg bricks, the same as the csg shader of glrender.
+ * Negative inside, sizes are the full size of the shape.
+ */
+
+// distance to a list of bricks combined in order, starting with the given brick
+fn csg_distance(point: Vecf3, first: Option<BrickRef>) -> f32 {
+    let mut result = f32::MAX;
+    let mut next = first;
+    while let Some(brick_ref) = next {
+        let brick = brick_ref.get();
+
+        // move the point into the space of the brick
+        let rot = brick.rot().to_vec();
+        let axis = Vecf3::new(rot[0], rot[1], rot[2]);
+        let local = point - to_vec3(brick.pos());
+        let local = local + axis.cross(axis.cross(local) + local * rot[3]) * 2.;
+
+        let distance = match brick.brick_type() {
+            BrickType::Layer => {
+                let scale = brick.size()[3];
+                csg_distance(local * (1. / scale), brick.child()) * scale
+            }
+            _ => brick_distance(local, &brick),
+        };
+        result = match brick.brick_op() {
+            BrickOp::Union => result.min(distance),
+            BrickOp::Inter => result.max(distance),
+            BrickOp::Diff => result.max(-distance),
+        };
+        next = brick.next_brick();
+    }
+    result
+}
+
+// distance to a single brick, the point is relative to the brick
+fn brick_distance(point: Vecf3, brick: &Brick) -> f32 {
+    let size = to_vec3(brick.size());
+    match brick.brick_type() {
+        BrickType::Box => {
+            let d = Vecf3::new(point.x.abs(), point.y.abs(), point.z.abs()) - size * 0.5;
+            d.x.max(d.y).max(d.z)
+        }
+        BrickType::Sphere => {
+            let half = size * 0.5;
+            let scaled = Vecf3::new(point.x / half.x, point.y / half.y, point.z / half.z);
+            (scaled.length() - 1.) * half.x.min(half.y).min(half.z)
+        }
+        BrickType::Cylinder => {
+            // cylinder along the y axis, capped at the top and bottom
+            let y = point.y + size.y / 2.;
+            let on_axis = Vecf3::new(point.x, y - y.clamp(0., size.y), point.z);
+            (on_axis.length() - size.x / 2.).max(-y).max(y - size.y)
+        }
+        BrickType::Cone => {
+            let y = point.y - size.y / 2.;
+            let depth = size.z * 2.;
+            let (qx, qy) = (size.y * size.x / depth, -size.y);
+            let (wx, wy) = ((point.x * point.x + point.z * point.z).sqrt(), y);
+            let t = ((wx * qx + wy * qy) / (qx * qx + qy * qy)).clamp(0., 1.);
+            let (ax, ay) = (wx - qx * t, wy - qy * t);
+            let (bx, by) = (wx - qx * (wx / qx).clamp(0., 1.), wy - qy);
+            let k = qy.signum();
+            let d = (ax * ax + ay * ay).min(bx * bx + by * by);
+            let s = (k * (wx * qy - wy * qx)).max(k * (wy - qy));
+            d.sqrt() * s.signum()
+        }
+        BrickType::Torus => {
+            let size = size * 0.25;
+            let ring = (point.x * point.x + point.z * point.z).sqrt() - size.x;
+            (ring * ring + point.y * point.y).sqrt() - size.y
+        }
+        BrickType::Layer => csg_distance(point, brick.child()),
+    }
+}
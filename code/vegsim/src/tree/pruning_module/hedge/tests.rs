@@ -0,0 +1,105 @@
+#![cfg(test)]
+
+use patutil::{
+    csg::{Brick, BrickOp, BrickRef, BrickType},
+    Vecf3,
+};
+
+use crate::{
+    profile::ParameterProfile,
+    tree::{metamer::Metamer, Simulation},
+    treeparameter::GeneticParameter,
+    util::meter_to_real_length,
+};
+
+use super::PruningVolume;
+
+fn meters(x: f32, y: f32, z: f32) -> Vecf3 {
+    Vecf3::new(meter_to_real_length(x), meter_to_real_length(y), meter_to_real_length(z))
+}
+
+fn all_inside(metamer: &Metamer, volume: &PruningVolume, base: Vecf3) -> bool {
+    volume.contains(metamer.end_point() - base)
+        && metamer.terminal_metamer().is_none_or(|m| all_inside(m, volume, base))
+        && metamer.auxillary_metamer().is_none_or(|m| all_inside(m, volume, base))
+        && metamer
+            .extra_buds()
+            .iter()
+            .filter_map(|bud| bud.metamer())
+            .all(|m| all_inside(m, volume, base))
+}
+
+#[test]
+fn test_volume_contains() {
+    let plane: PruningVolume = "plane point{0 1 0} normal{0 2 0}".parse().unwrap();
+    assert!(plane.contains(meters(5., 0.9, 3.)));
+    assert!(!plane.contains(meters(0., 1.1, 0.)));
+
+    let cylinder: PruningVolume = "cylinder center{0 1 0} diameter{1} height{2}".parse().unwrap();
+    assert!(cylinder.contains(meters(0.4, 1.9, 0.)));
+    assert!(!cylinder.contains(meters(0.4, 1., 0.4)));
+    assert!(!cylinder.contains(meters(0., 2.1, 0.)));
+
+    // a box with a sphere cut out of its center
+    let first: BrickRef = Brick::new(BrickType::Box, BrickOp::Union, "box").into();
+    first.controller().set_size([2., 2., 2., 1.]);
+    let hole: BrickRef = Brick::new(BrickType::Sphere, BrickOp::Diff, "hole").into();
+    first.controller().set_next_brick(Some(hole));
+    let csg = PruningVolume::Csg(first);
+    assert!(csg.contains(meters(0.9, 0.9, 0.9)));
+    assert!(!csg.contains(meters(0.1, 0., 0.)));
+    assert!(!csg.contains(meters(1.1, 0., 0.)));
+
+    // volumes read back from their text
+    for volume in [plane, cylinder, csg] {
+        let read: PruningVolume = volume.to_string().parse().unwrap();
+        assert_eq!(read.to_string(), volume.to_string());
+    }
+    assert!("sphere center{0 0 0}".parse::<PruningVolume>().is_err());
+    assert!("plane point{0 0 0} normal{0 0 0}".parse::<PruningVolume>().is_err());
+}
+
+#[test]
+fn test_volume_trim() {
+    let mut simulation = Simulation::with_seed(5);
+    for _ in 0..12 {
+        simulation.perform_growth_iteration();
+    }
+    let before = simulation.plant().root().count_metamers();
+
+    // hedge the top and one side of the tree
+    let volume = PruningVolume::Box {
+        center: Vecf3::new(0.5, 0.5, 0.),
+        size: Vecf3::new(2., 1., 4.),
+    };
+    let removed = simulation.trim_to_volume(&volume);
+    let after = simulation.plant().root().count_metamers();
+    assert!(removed > 0);
+    assert_eq!(after, before - removed);
+    let base = simulation.plant().root().start_point();
+    assert!(all_inside(simulation.plant().root(), &volume, base));
+}
+
+#[test]
+fn test_volume_trim_border() {
+    let mut profile = ParameterProfile::default();
+    profile.genetics.update_param(GeneticParameter::BudsPerNode(2.));
+    profile.genetics.update_param(GeneticParameter::BranchingAngle(0.7));
+
+    // cut at several heights, so some metamers only stick out a little above the plane
+    for height in [0.3, 0.5, 0.7, 0.9] {
+        let mut simulation = Simulation::with_profile(profile.clone(), 5);
+        for _ in 0..10 {
+            simulation.perform_growth_iteration();
+        }
+        let volume: PruningVolume = format!("plane point{{0 {} 0}} normal{{0 1 0}}", height).parse().unwrap();
+        let before = simulation.plant().root().count_metamers();
+        let removed = simulation.trim_to_volume(&volume);
+        assert_eq!(simulation.plant().root().count_metamers(), before - removed);
+
+        // a single trim leaves everything inside
+        let base = simulation.plant().root().start_point();
+        assert!(all_inside(simulation.plant().root(), &volume, base));
+        assert_eq!(simulation.trim_to_volume(&volume), 0);
+    }
+}
@@ -0,0 +1,288 @@
+mod tests;
+
+use std::{
+    collections::BTreeMap,
+    f32::consts::PI,
+    fmt::{self, Display},
+    fs::File,
+    io,
+    path::Path,
+    str::FromStr,
+};
+
+use patfile::{pscan, pwrite};
+
+use crate::{
+    tree::metamer::Metamer,
+    util::{meter_to_real_length, width_to_radius},
+};
+
+/**
+ * What one prune removed from the plant
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PruneRecord {
+    // growth iterations grown before the prune
+    pub iteration: i32,
+    // rule, rule file, volume or strategy that pruned
+    pub source: String,
+    pub removed_ids: Vec<u32>,
+    // wood removed in m3, length times cross section, cuts count the part above the cut
+    pub biomass: f32,
+    // light the removed branches gathered in the last growth iteration
+    pub light: f32,
+}
+
+macro_rules! PRUNE_RECORD_STRING_FORMAT {
+    () => {
+        "iteration{} source{} removed{} biomass{} light{}"
+    };
+}
+
+impl FromStr for PruneRecord {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut iteration = 0;
+        let mut source = String::new();
+        let mut removed = String::new();
+        let mut biomass = 0.;
+        let mut light = 0.;
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => PRUNE_RECORD_STRING_FORMAT!(), iteration, source, removed, biomass, light).map_err(|_| ())?;
+        let removed_ids = removed
+            .split_whitespace()
+            .map(|id| id.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| ())?;
+        Ok(Self {
+            iteration,
+            source,
+            removed_ids,
+            biomass,
+            light,
+        })
+    }
+}
+
+impl Display for PruneRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(PRUNE_RECORD_STRING_FORMAT!() => writer,
+            self.iteration,
+            &self.source,
+            join_ids(&self.removed_ids),
+            self.biomass,
+            self.light
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
+
+/**
+ * Total of all prunes by one rule, rule file, volume or strategy
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruneSummary {
+    pub source: String,
+    pub prunes: u32,
+    pub metamers: u32,
+    pub biomass: f32,
+    pub light: f32,
+}
+
+/**
+ * Every prune of a simulation in order
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PruningHistory {
+    records: Vec<PruneRecord>,
+}
+
+impl PruningHistory {
+    pub fn records(&self) -> &[PruneRecord] {
+        &self.records
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /**
+     * Compare the plant with its state before a prune and store what was removed,
+     * nothing is stored when the prune did not remove anything
+     */
+    pub fn record(&mut self, iteration: i32, source: &str, before: &PlantState, root: &Metamer) {
+        let after = PlantState::new(root);
+        let mut removed_ids = vec![];
+        let mut biomass = 0.;
+        let mut light = 0.;
+
+        for (id, part) in &before.parts {
+            match after.parts.get(id) {
+                Some(kept) => biomass += (part.biomass - kept.biomass).max(0.),
+                None => {
+                    removed_ids.push(*id);
+                    biomass += part.biomass;
+                    // the light of a branch is gathered by all of its metamers, count it at the base
+                    let parent_kept = part.parent.is_none_or(|parent| after.parts.contains_key(&parent));
+                    if parent_kept {
+                        light += part.light;
+                    }
+                }
+            }
+        }
+        if removed_ids.is_empty() && biomass <= 0. {
+            return;
+        }
+
+        self.records.push(PruneRecord {
+            iteration,
+            source: source.to_string(),
+            removed_ids,
+            biomass,
+            light,
+        });
+    }
+
+    // totals per source in the order the sources first pruned
+    pub fn summary(&self) -> Vec<PruneSummary> {
+        let mut summary: Vec<PruneSummary> = vec![];
+        for record in &self.records {
+            let index = match summary.iter().position(|total| total.source == record.source) {
+                Some(index) => index,
+                None => {
+                    summary.push(PruneSummary {
+                        source: record.source.clone(),
+                        prunes: 0,
+                        metamers: 0,
+                        biomass: 0.,
+                        light: 0.,
+                    });
+                    summary.len() - 1
+                }
+            };
+            let total = &mut summary[index];
+            total.prunes += 1;
+            total.metamers += record.removed_ids.len() as u32;
+            total.biomass += record.biomass;
+            total.light += record.light;
+        }
+        summary
+    }
+
+    pub fn save_csv(&self, path: &Path) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        self.write_csv(&mut file)
+    }
+
+    // one line per prune, removed ids are separated by spaces
+    pub fn write_csv(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(out, "iteration,source,removed_metamers,biomass_m3,light,removed_ids")?;
+        for record in &self.records {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                record.iteration,
+                record.source.replace(',', ";"),
+                record.removed_ids.len(),
+                record.biomass,
+                record.light,
+                join_ids(&record.removed_ids)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! PRUNING_HISTORY_STRING_FORMAT {
+    () => {
+        "prune{}"
+    };
+}
+
+impl FromStr for PruningHistory {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut records = vec![];
+        let mut it = s.bytes().into_iter().peekable();
+        while it.peek().is_some() {
+            let mut record = PruneRecord::default();
+            pscan!(&mut it => PRUNING_HISTORY_STRING_FORMAT!(), record).map_err(|_| ())?;
+            records.push(record);
+            // records are separated by a space
+            it.next();
+        }
+        Ok(Self { records })
+    }
+}
+
+impl Display for PruningHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let records: Vec<String> = self.records.iter().map(|record| record.to_string()).collect();
+        for (i, record) in records.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            let writer: &mut dyn std::fmt::Write = f;
+            pwrite!(PRUNING_HISTORY_STRING_FORMAT!() => writer, record).map_err(|_| fmt::Error)?;
+        }
+        Ok(())
+    }
+}
+
+fn join_ids(ids: &[u32]) -> String {
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    ids.join(" ")
+}
+
+struct MetamerState {
+    parent: Option<u32>,
+    biomass: f32,
+    light: f32,
+}
+
+/**
+ * Metamers of a plant before a prune, to find out what the prune removed.
+ * Ordered by id so the totals add up the same in every run.
+ */
+pub struct PlantState {
+    parts: BTreeMap<u32, MetamerState>,
+}
+
+impl PlantState {
+    pub fn new(root: &Metamer) -> Self {
+        let mut parts = BTreeMap::new();
+        Self::add(root, None, &mut parts);
+        Self { parts }
+    }
+
+    fn add(metamer: &Metamer, parent: Option<u32>, parts: &mut BTreeMap<u32, MetamerState>) {
+        parts.insert(
+            metamer.id(),
+            MetamerState {
+                parent,
+                biomass: biomass(metamer),
+                light: metamer.last_light_generated(),
+            },
+        );
+        if let Some(m) = metamer.terminal_metamer() {
+            Self::add(m, Some(metamer.id()), parts);
+        }
+        if let Some(m) = metamer.auxillary_metamer() {
+            Self::add(m, Some(metamer.id()), parts);
+        }
+        for m in metamer.extra_buds().iter().filter_map(|bud| bud.metamer()) {
+            Self::add(m, Some(metamer.id()), parts);
+        }
+    }
+}
+
+// wood of a metamer in m3, its length times the cross section at its average width
+fn biomass(metamer: &Metamer) -> f32 {
+    let meter = meter_to_real_length(1.);
+    let radius = width_to_radius((metamer.start_width() + metamer.end_width()) / 2.) / meter;
+    PI * radius * radius * metamer.length() / meter
+}
@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use crate::tree::{pruning_module::PruneOperation, snapshot::Snapshot, Simulation};
+
+use super::{PruneRecord, PruningHistory};
+
+#[test]
+fn test_history_string() {
+    let record = PruneRecord {
+        iteration: 4,
+        source: "spalier".to_string(),
+        removed_ids: vec![12, 40],
+        biomass: 0.25,
+        light: 3.5,
+    };
+    assert_eq!(record.to_string(), "iteration{4} source{spalier} removed{12 40} biomass{0.25} light{3.5}");
+    assert_eq!(record.to_string().parse(), Ok(record.clone()));
+
+    let empty = PruningHistory::default();
+    assert_eq!(empty.to_string().parse(), Ok(empty));
+}
+
+#[test]
+fn test_history_record() {
+    let mut simulation = Simulation::with_seed(3);
+    for _ in 0..8 {
+        simulation.perform_growth_iteration();
+    }
+    let before = simulation.plant().root().count_metamers();
+
+    // the removed metamers are found by comparing the plant before and after the prune
+    simulation.prune_by_rule(PruneOperation::Op1);
+    let removed = before - simulation.plant().root().count_metamers();
+    let history = simulation.pruning_history().clone();
+    let record = &history.records()[0];
+    assert_eq!(record.source, "Op1");
+    assert_eq!(record.iteration, 8);
+    assert_eq!(record.removed_ids.len() as u32, removed);
+    assert!(record.biomass > 0. && record.light > 0.);
+    assert!(record.removed_ids.iter().all(|&id| simulation.get_metamer_by_id(id).is_none()));
+
+    // a cut removes the wood above the cut and does not remove the metamer
+    let id = simulation.plant().root().id();
+    simulation.cut_id(id, 0.5);
+    let record = simulation.pruning_history().records().last().unwrap().clone();
+    assert_eq!(record.source, "cut");
+    assert!(!record.removed_ids.contains(&id));
+    assert!(record.biomass > 0.);
+
+    // a prune that removes nothing is not stored
+    let records = simulation.pruning_history().records().len();
+    simulation.prune_by_rule(PruneOperation::Op1);
+    assert_eq!(simulation.pruning_history().records().len(), records);
+
+    let summary = simulation.pruning_history().summary();
+    assert_eq!(summary[0].source, "Op1");
+    assert_eq!(summary[0].metamers, removed);
+
+    let mut csv = vec![];
+    simulation.pruning_history().write_csv(&mut csv).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap().lines().count(), records + 1);
+
+    // the history is part of the snapshot
+    let mut data = vec![];
+    simulation.snapshot().write(&mut data).unwrap();
+    let loaded = Snapshot::read(&mut data.into_iter()).unwrap();
+    assert_eq!(&loaded.pruning_history, simulation.pruning_history());
+}
@@ -6,8 +6,20 @@ use crate::util::{random::Random, meter_to_real_length, rot_vec_around_axis};
 
 use super::{metamer::Metamer, plant::Plant};
 
+mod autoprune_central_leader;
+mod autoprune_fan;
+mod autoprune_hedge;
 mod autoprune_spalier;
+mod autoprune_vase;
+pub mod hedge;
+pub mod history;
+pub mod rules;
+pub mod strategy;
+pub use autoprune_central_leader::*;
+pub use autoprune_fan::*;
+pub use autoprune_hedge::*;
 pub use autoprune_spalier::*;
+pub use autoprune_vase::*;
 
 #[derive(Debug, Clone)]
 pub enum PruneOperation{
@@ -15,11 +27,8 @@ pub enum PruneOperation{
     Op1 = 1,
     Op2 = 2,
     Op3 = 3,
-    Op4 = 4,
-    Op5 = 5,
     Spil_1,
     Spil_2,
-    Spil_3
 }
 
 impl std::fmt::Display for PruneOperation {
@@ -28,27 +37,37 @@ impl std::fmt::Display for PruneOperation {
     }
 }
 
+impl std::str::FromStr for PruneOperation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Op0" => Ok(PruneOperation::Op0),
+            "Op1" => Ok(PruneOperation::Op1),
+            "Op2" => Ok(PruneOperation::Op2),
+            "Op3" => Ok(PruneOperation::Op3),
+            "Spil_1" => Ok(PruneOperation::Spil_1),
+            "Spil_2" => Ok(PruneOperation::Spil_2),
+            _ => Err(()),
+        }
+    }
+}
+
 pub struct PruningModule{
 }
 
 impl PruningModule {
-    pub fn prune_by_rule(rule: PruneOperation, plant: &mut Plant){
+    pub fn prune_by_rule(rule: PruneOperation, plant: &mut Plant, random: &mut Random){
         match rule{
         
             PruneOperation::Op1 =>{
                 Self::prune_rule_1(plant.root_mut());
             }
             PruneOperation::Op2 =>{
-                Self::prune_rule_2(plant.root_mut());
+                Self::prune_rule_2(plant.root_mut(), random);
             }
             PruneOperation::Op3 =>{
-                Self::prune_rule_3(plant.root_mut());
-            }
-            PruneOperation::Op4 =>{
-                Self::prune_rule_4(plant.root_mut());
-            }
-            PruneOperation::Op5 =>{
-                Self::prune_rule_5(plant.root_mut());
+                Self::prune_rule_3(plant.root_mut(), random);
             }
             PruneOperation::Spil_1 =>{
                 Self::prune_spil_1(plant.root_mut());
@@ -56,9 +75,6 @@ impl PruningModule {
             PruneOperation::Spil_2 =>{
                 Self::prune_spil_2(plant.root_mut());
             }
-            PruneOperation::Spil_3 =>{
-                Self::prune_spil_3(plant.root_mut());
-            }
             _ =>{
             }
         }
@@ -68,30 +84,30 @@ impl PruningModule {
         if root.support_pole.is_none(){
             return;
         }
-        root.prune_auxillary();
+        root.prune_laterals();
         if let Some(terminal) = root.terminal_metamer_mut(){
             Self::prune_rule_1(terminal);
         }
     }
 
-    fn prune_rule_2(root: &mut Metamer){
-        if let Some(metamer) = root.auxillary_metamer(){
-            let bud_shorten = *Random::choose(&[3, 4]);
+    fn prune_rule_2(root: &mut Metamer, random: &mut Random){
+        for metamer in root.lateral_metamers_mut(){
+            let bud_shorten = *random.choose(&[3, 4]);
             Self::short_metamer_buds(metamer, bud_shorten);
         }
         if let Some(metamer) = root.terminal_metamer_mut(){
-            Self::prune_rule_2(metamer);
+            Self::prune_rule_2(metamer, random);
         }
     }
 
-    fn prune_rule_3(root: &mut Metamer){
+    fn prune_rule_3(root: &mut Metamer, random: &mut Random){
         fn helper(root: &mut Metamer, mut max_branches: u32){
             if max_branches == 0{
                 root.prune_terminal();
                 return;
             }
-            if let Some(metamer) = root.auxillary_metamer(){
-                max_branches -= 1;
+            for metamer in root.lateral_metamers_mut(){
+                max_branches = max_branches.saturating_sub(1);
                 let new_length = (metamer.longest_path() as f32 *(2./3.)).round() as u32;
                 PruningModule::short_metamer_length(metamer, new_length);
             }
@@ -100,23 +116,10 @@ impl PruningModule {
             }
         }
 
-        let max_branches = *Random::choose(&[3, 4]);
+        let max_branches = *random.choose(&[3, 4]);
         helper(root, max_branches);
     }
 
-    fn prune_rule_4(root: &mut Metamer){
-        root.prune_auxillary();
-        if let Some(terminal) = root.terminal_metamer_mut(){
-            Self::prune_rule_1(terminal);
-        }
-    }
-    fn prune_rule_5(root: &mut Metamer){
-        root.prune_auxillary();
-        if let Some(terminal) = root.terminal_metamer_mut(){
-            Self::prune_rule_1(terminal);
-        }
-    }
-
     fn prune_spil_1(root: &mut Metamer){
         // shorten terminal to 90 cm
         let max_term_height = meter_to_real_length(0.9);
@@ -148,10 +151,11 @@ impl PruningModule {
             if let Some(metamer) = root.terminal_metamer_mut(){
                 success |= branch_shorten(metamer, passed_length + 1);
             }
-            if !success{
-                if let Some(metamer) = root.auxillary_metamer(){
-                    success |= branch_shorten(metamer, passed_length + 1);
+            for metamer in root.lateral_metamers_mut(){
+                if success{
+                    break;
                 }
+                success |= branch_shorten(metamer, passed_length + 1);
             }
             return success;
         }
@@ -160,7 +164,7 @@ impl PruningModule {
         fn iterator(root: &mut Metamer){
 
             // shorten side branch
-            if let Some(metamer) = root.auxillary_metamer(){
+            for metamer in root.lateral_metamers_mut(){
                 if branch_shorten(metamer, 0){
                     // shorten succes
                 } else{
@@ -176,33 +180,31 @@ impl PruningModule {
         }
         iterator(root);
     }
-    fn prune_spil_3(root: &mut Metamer){
-    }
-
-    fn short_metamer_length(root:&mut Metamer, length: u32){
-        if length == 1{
+    // keep length metamers of the shoot starting at root, the metamer itself counts as one
+    pub(super) fn short_metamer_length(root:&mut Metamer, length: u32){
+        if length <= 1{
             root.prune_terminal();
-            root.prune_auxillary();
+            root.prune_laterals();
             return;
         }
         if let Some(metamer) = root.terminal_metamer_mut(){
             Self::short_metamer_buds(metamer, length-1);
         }
-        if let Some(metamer) = root.auxillary_metamer(){
+        for metamer in root.lateral_metamers_mut(){
             Self::short_metamer_buds(metamer, length-1);
         }
     }
 
-    fn short_metamer_buds(root:&mut Metamer, max_buds: u32){
-        if max_buds == 1{
+    pub(super) fn short_metamer_buds(root:&mut Metamer, max_buds: u32){
+        if max_buds <= 1{
             root.prune_terminal();
-            root.prune_auxillary();
+            root.prune_laterals();
             return;
         }
         if let Some(metamer) = root.terminal_metamer_mut(){
             Self::short_metamer_buds(metamer, max_buds-1);
         }
-        if let Some(metamer) = root.auxillary_metamer(){
+        for metamer in root.lateral_metamers_mut(){
             Self::short_metamer_buds(metamer, max_buds-1);
         }
     }
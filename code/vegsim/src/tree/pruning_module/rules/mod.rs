@@ -0,0 +1,473 @@
+/*
+ * Pruning protocols written as text, one rule per line:
+ *
+ *     # comment
+ *     <action> [where <condition> [and <condition>]...]
+ *
+ * actions:
+ *     thin out                    remove the selected shoot at its base
+ *     head back to <n> buds       shorten the selected shoot to n buds
+ *     remove terminal             remove the terminal shoot or bud of the selected metamer
+ *     remove axillary             remove the axillary shoot or bud of the selected metamer
+ *
+ * conditions:
+ *     trunk                       metamer is part of the trunk
+ *     axillary                    metamer is the first of a shoot grown from an axillary bud
+ *     on trunk                    metamer is the first of a shoot grown from a trunk bud
+ *     order <n>                   amount of axillary buds between the metamer and the seed
+ *     supported                   metamer is tied to a support pole
+ *     height between <a> and <b>  start of the metamer is between a and b, in m (or cm)
+ *     height above <a>, height below <a>
+ *     older than <n>, younger than <n>       age in growth iterations
+ *     longer than <n>, shorter than <n>      longest path of metamers from the metamer
+ *     bud points down, bud points up         direction of the axillary bud
+ *
+ * e.g. "thin out where axillary and on trunk and height between 0.4 m and 0.9 m"
+ */
+mod tests;
+
+use std::{
+    fmt::{self, Display},
+    fs, io,
+    path::Path,
+};
+
+use crate::{
+    tree::{metamer::Metamer, plant::Plant},
+    util::meter_to_real_length,
+};
+
+use super::PruningModule;
+
+// extension of rule files
+pub const RULES_EXTENSION: &str = "txt";
+
+#[derive(Debug)]
+pub enum RuleError {
+    Io(io::Error),
+    Syntax { line: usize, message: String },
+}
+
+impl std::error::Error for RuleError {}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Io(err) => write!(f, "could not access rule file: {}", err),
+            RuleError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl From<io::Error> for RuleError {
+    fn from(err: io::Error) -> Self {
+        RuleError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleAction {
+    ThinOut,
+    HeadBack(u32),
+    RemoveTerminal,
+    RemoveAxillary,
+}
+
+impl Display for RuleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleAction::ThinOut => write!(f, "thin out"),
+            RuleAction::HeadBack(buds) => write!(f, "head back to {} buds", buds),
+            RuleAction::RemoveTerminal => write!(f, "remove terminal"),
+            RuleAction::RemoveAxillary => write!(f, "remove axillary"),
+        }
+    }
+}
+
+// heights are stored in m
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Trunk,
+    Axillary,
+    OnTrunk,
+    Order(u32),
+    Supported,
+    HeightBetween(f32, f32),
+    HeightAbove(f32),
+    HeightBelow(f32),
+    OlderThan(u32),
+    YoungerThan(u32),
+    LongerThan(u32),
+    ShorterThan(u32),
+    BudPointsDown,
+    BudPointsUp,
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Trunk => write!(f, "trunk"),
+            Condition::Axillary => write!(f, "axillary"),
+            Condition::OnTrunk => write!(f, "on trunk"),
+            Condition::Order(order) => write!(f, "order {}", order),
+            Condition::Supported => write!(f, "supported"),
+            Condition::HeightBetween(min, max) => write!(f, "height between {} m and {} m", min, max),
+            Condition::HeightAbove(height) => write!(f, "height above {} m", height),
+            Condition::HeightBelow(height) => write!(f, "height below {} m", height),
+            Condition::OlderThan(age) => write!(f, "older than {}", age),
+            Condition::YoungerThan(age) => write!(f, "younger than {}", age),
+            Condition::LongerThan(length) => write!(f, "longer than {}", length),
+            Condition::ShorterThan(length) => write!(f, "shorter than {}", length),
+            Condition::BudPointsDown => write!(f, "bud points down"),
+            Condition::BudPointsUp => write!(f, "bud points up"),
+        }
+    }
+}
+
+// position of a metamer in the plant, needed to check conditions
+#[derive(Debug, Clone, Copy)]
+struct MetamerPlace {
+    order: u32,
+    axillary: bool,
+    parent_order: Option<u32>,
+}
+
+impl Condition {
+    fn matches(&self, metamer: &Metamer, place: MetamerPlace) -> bool {
+        let height = metamer.start_point().y;
+        match *self {
+            Condition::Trunk => place.order == 0,
+            Condition::Axillary => place.axillary,
+            Condition::OnTrunk => place.axillary && place.parent_order == Some(0),
+            Condition::Order(order) => place.order == order,
+            Condition::Supported => metamer.support_pole.is_some(),
+            Condition::HeightBetween(min, max) => {
+                height >= meter_to_real_length(min) && height <= meter_to_real_length(max)
+            }
+            Condition::HeightAbove(min) => height > meter_to_real_length(min),
+            Condition::HeightBelow(max) => height < meter_to_real_length(max),
+            Condition::OlderThan(age) => metamer.age > age,
+            Condition::YoungerThan(age) => metamer.age < age,
+            Condition::LongerThan(length) => metamer.longest_path() > length,
+            Condition::ShorterThan(length) => metamer.longest_path() < length,
+            Condition::BudPointsDown => metamer.auxillary_direction().y < 0.,
+            Condition::BudPointsUp => metamer.auxillary_direction().y > 0.,
+        }
+    }
+}
+
+/**
+ * Action performed on every metamer that meets all conditions
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruningRule {
+    pub action: RuleAction,
+    pub conditions: Vec<Condition>,
+}
+
+impl Display for PruningRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.action)?;
+        for (index, condition) in self.conditions.iter().enumerate() {
+            let separator = if index == 0 { "where" } else { "and" };
+            write!(f, " {} {}", separator, condition)?;
+        }
+        Ok(())
+    }
+}
+
+impl PruningRule {
+    fn matches(&self, metamer: &Metamer, place: MetamerPlace) -> bool {
+        self.conditions.iter().all(|condition| condition.matches(metamer, place))
+    }
+
+    pub fn apply(&self, plant: &mut Plant) {
+        let place = MetamerPlace {
+            order: 0,
+            axillary: false,
+            parent_order: None,
+        };
+        let root = plant.root_mut();
+        // the root can not be thinned out, the plant would be gone
+        if self.action != RuleAction::ThinOut && self.matches(root, place) {
+            self.act(root);
+        }
+        self.apply_children(root, place);
+    }
+
+    fn apply_children(&self, metamer: &mut Metamer, place: MetamerPlace) {
+        let terminal_place = MetamerPlace {
+            order: place.order,
+            axillary: false,
+            parent_order: Some(place.order),
+        };
+        let terminal_matches = metamer
+            .terminal_metamer()
+            .is_some_and(|terminal| self.matches(terminal, terminal_place));
+        if terminal_matches && self.action == RuleAction::ThinOut {
+            metamer.prune_terminal();
+        } else if let Some(terminal) = metamer.terminal_metamer_mut() {
+            if terminal_matches {
+                self.act(terminal);
+            }
+            self.apply_children(terminal, terminal_place);
+        }
+
+        let auxillary_place = MetamerPlace {
+            order: place.order + 1,
+            axillary: true,
+            parent_order: Some(place.order),
+        };
+        let auxillary_matches = metamer
+            .auxillary_metamer()
+            .is_some_and(|auxillary| self.matches(auxillary, auxillary_place));
+        if auxillary_matches && self.action == RuleAction::ThinOut {
+            metamer.prune_auxillary();
+        } else if let Some(auxillary) = metamer.auxillary_metamer_mut() {
+            if auxillary_matches {
+                self.act(auxillary);
+            }
+            self.apply_children(auxillary, auxillary_place);
+        }
+
+        // the other axillary buds of the node are in the same place
+        for bud in metamer.extra_buds_mut() {
+            let matches = bud.metamer().is_some_and(|auxillary| self.matches(auxillary, auxillary_place));
+            if matches && self.action == RuleAction::ThinOut {
+                bud.prune();
+            } else if let Some(auxillary) = bud.metamer_mut() {
+                if matches {
+                    self.act(auxillary);
+                }
+                self.apply_children(auxillary, auxillary_place);
+            }
+        }
+    }
+
+    fn act(&self, metamer: &mut Metamer) {
+        match self.action {
+            // thinning out is done by the parent metamer
+            RuleAction::ThinOut => {}
+            RuleAction::HeadBack(buds) => PruningModule::short_metamer_buds(metamer, buds),
+            RuleAction::RemoveTerminal => metamer.prune_terminal(),
+            RuleAction::RemoveAxillary => metamer.prune_laterals(),
+        }
+    }
+}
+
+/**
+ * Pruning rules performed in order
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruningProtocol {
+    pub name: String,
+    pub rules: Vec<PruningRule>,
+}
+
+impl PruningProtocol {
+    // the protocol is named after the file
+    pub fn load(path: &Path) -> Result<Self, RuleError> {
+        let text = fs::read_to_string(path)?;
+        let name = path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .unwrap_or("rules")
+            .to_string();
+        Self::parse(&name, &text)
+    }
+
+    pub fn parse(name: &str, text: &str) -> Result<Self, RuleError> {
+        let mut rules = vec![];
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let rule = RuleParser::new(line)
+                .rule()
+                .map_err(|message| RuleError::Syntax { line: index + 1, message })?;
+            rules.push(rule);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            rules,
+        })
+    }
+
+    pub fn apply(&self, plant: &mut Plant) {
+        for rule in &self.rules {
+            rule.apply(plant);
+        }
+    }
+}
+
+// reads a single rule word by word, words are compared in lower case
+struct RuleParser {
+    words: Vec<String>,
+    index: usize,
+}
+
+impl RuleParser {
+    fn new(line: &str) -> Self {
+        Self {
+            words: line.split_whitespace().map(|word| word.to_lowercase()).collect(),
+            index: 0,
+        }
+    }
+
+    fn rule(mut self) -> Result<PruningRule, String> {
+        let action = self.action()?;
+
+        let mut conditions = vec![];
+        if self.peek().is_some() {
+            self.expect("where")?;
+            conditions.push(self.condition()?);
+            while self.peek().is_some() {
+                self.expect("and")?;
+                conditions.push(self.condition()?);
+            }
+        }
+        Ok(PruningRule { action, conditions })
+    }
+
+    fn action(&mut self) -> Result<RuleAction, String> {
+        match self.next()?.as_str() {
+            "thin" => {
+                self.expect("out")?;
+                Ok(RuleAction::ThinOut)
+            }
+            "head" => {
+                self.expect("back")?;
+                self.expect("to")?;
+                let buds = self.count()?;
+                if buds == 0 {
+                    return Err("a shoot can not be headed back to 0 buds, use thin out".to_string());
+                }
+                self.unit(&["buds", "bud"]);
+                Ok(RuleAction::HeadBack(buds))
+            }
+            "remove" => match self.next()?.as_str() {
+                "terminal" => Ok(RuleAction::RemoveTerminal),
+                "axillary" => Ok(RuleAction::RemoveAxillary),
+                word => Err(format!("expected terminal or axillary after remove, found '{}'", word)),
+            },
+            word => Err(format!(
+                "unknown action '{}', expected thin out, head back, or remove",
+                word
+            )),
+        }
+    }
+
+    fn condition(&mut self) -> Result<Condition, String> {
+        match self.next()?.as_str() {
+            "trunk" => Ok(Condition::Trunk),
+            "axillary" => Ok(Condition::Axillary),
+            "on" => {
+                self.expect("trunk")?;
+                Ok(Condition::OnTrunk)
+            }
+            "order" => Ok(Condition::Order(self.count()?)),
+            "supported" => Ok(Condition::Supported),
+            "height" => match self.next()?.as_str() {
+                "between" => {
+                    let min = self.height()?;
+                    self.expect("and")?;
+                    let max = self.height()?;
+                    if min > max {
+                        return Err(format!("height {} m is above {} m", min, max));
+                    }
+                    Ok(Condition::HeightBetween(min, max))
+                }
+                "above" => Ok(Condition::HeightAbove(self.height()?)),
+                "below" => Ok(Condition::HeightBelow(self.height()?)),
+                word => Err(format!("expected between, above or below after height, found '{}'", word)),
+            },
+            "older" => {
+                self.expect("than")?;
+                let age = self.count()?;
+                self.unit(&["iterations", "iteration"]);
+                Ok(Condition::OlderThan(age))
+            }
+            "younger" => {
+                self.expect("than")?;
+                let age = self.count()?;
+                self.unit(&["iterations", "iteration"]);
+                Ok(Condition::YoungerThan(age))
+            }
+            "longer" => {
+                self.expect("than")?;
+                let length = self.count()?;
+                self.unit(&["metamers", "metamer"]);
+                Ok(Condition::LongerThan(length))
+            }
+            "shorter" => {
+                self.expect("than")?;
+                let length = self.count()?;
+                self.unit(&["metamers", "metamer"]);
+                Ok(Condition::ShorterThan(length))
+            }
+            "bud" => {
+                self.expect("points")?;
+                match self.next()?.as_str() {
+                    "down" => Ok(Condition::BudPointsDown),
+                    "up" => Ok(Condition::BudPointsUp),
+                    word => Err(format!("expected down or up, found '{}'", word)),
+                }
+            }
+            word => Err(format!("unknown condition '{}'", word)),
+        }
+    }
+
+    // height in m, cm values are converted
+    fn height(&mut self) -> Result<f32, String> {
+        let word = self.next()?;
+        let height: f32 = word
+            .parse()
+            .map_err(|_| format!("expected a height, found '{}'", word))?;
+        if !height.is_finite() {
+            return Err(format!("expected a height, found '{}'", word));
+        }
+        match self.peek() {
+            Some("m") => {
+                self.index += 1;
+                Ok(height)
+            }
+            Some("cm") => {
+                self.index += 1;
+                Ok(height / 100.)
+            }
+            _ => Ok(height),
+        }
+    }
+
+    fn count(&mut self) -> Result<u32, String> {
+        let word = self.next()?;
+        word.parse()
+            .map_err(|_| format!("expected a whole number, found '{}'", word))
+    }
+
+    // skip an optional unit after a number
+    fn unit(&mut self, units: &[&str]) {
+        if self.peek().is_some_and(|word| units.contains(&word)) {
+            self.index += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        let word = self.next()?;
+        if word != expected {
+            return Err(format!("expected '{}', found '{}'", expected, word));
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.words.get(self.index).map(|word| word.as_str())
+    }
+
+    fn next(&mut self) -> Result<String, String> {
+        let word = self.peek().ok_or("unexpected end of rule")?.to_string();
+        self.index += 1;
+        Ok(word)
+    }
+}
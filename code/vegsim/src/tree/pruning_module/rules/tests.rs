@@ -0,0 +1,125 @@
+#![cfg(test)]
+
+use crate::tree::{metamer::Metamer, pruning_module::PruneOperation, Simulation};
+
+use super::{Condition, PruningProtocol, RuleAction, RuleError};
+
+#[test]
+fn test_rule_parse() {
+    let text = "# trunk spacing\n\
+        Thin out where axillary and on trunk and height between 40 cm and 0.9 m\n\
+        \n\
+        head back to 3 buds where older than 2 iterations and bud points down\n\
+        remove terminal\n";
+    let protocol = PruningProtocol::parse("spacing", text).unwrap();
+    assert_eq!(protocol.rules.len(), 3);
+    assert_eq!(
+        protocol.rules[0].conditions,
+        vec![Condition::Axillary, Condition::OnTrunk, Condition::HeightBetween(0.4, 0.9)]
+    );
+    assert_eq!(protocol.rules[1].action, RuleAction::HeadBack(3));
+    assert!(protocol.rules[2].conditions.is_empty());
+
+    // written rules read back the same
+    let written: Vec<String> = protocol.rules.iter().map(|rule| rule.to_string()).collect();
+    let reparsed = PruningProtocol::parse("spacing", &written.join("\n")).unwrap();
+    assert_eq!(reparsed, protocol);
+}
+
+#[test]
+fn test_rule_errors() {
+    let error = |text: &str| match PruningProtocol::parse("error", text) {
+        Err(RuleError::Syntax { line, message }) => (line, message),
+        _ => panic!("{} should not parse", text),
+    };
+
+    assert_eq!(error("cut everything").1, "unknown action 'cut', expected thin out, head back, or remove");
+    assert_eq!(error("\nthin out where tall").0, 2);
+    assert_eq!(error("thin out where trunk or axillary").1, "expected 'and', found 'or'");
+    assert_eq!(error("head back to 0 buds").1, "a shoot can not be headed back to 0 buds, use thin out");
+    assert_eq!(error("thin out where height between 1 and").1, "unexpected end of rule");
+}
+
+#[test]
+fn test_rule_matches_prune_operation() {
+    let mut simulation = Simulation::with_seed(5);
+    for _ in 0..10 {
+        simulation.perform_growth_iteration();
+    }
+    let snapshot = simulation.snapshot();
+
+    // Spil_1 shortens the trunk to 90 cm
+    simulation.prune_by_rule(PruneOperation::Spil_1);
+    let expected = simulation.plant().root().to_string();
+
+    let protocol = PruningProtocol::parse("spil_1", "thin out where trunk and height above 0.9 m").unwrap();
+    let mut simulation = Simulation::from_snapshot(snapshot);
+    simulation.prune_by_protocol(&protocol);
+    assert_eq!(simulation.plant().root().to_string(), expected);
+}
+
+// ages of every metamer above the root
+fn ages(metamer: &Metamer) -> Vec<u32> {
+    let mut result = vec![];
+    let children = metamer
+        .terminal_metamer()
+        .into_iter()
+        .chain(metamer.auxillary_metamer())
+        .chain(metamer.extra_buds().iter().filter_map(|bud| bud.metamer()));
+    for child in children {
+        result.push(child.age);
+        result.extend(ages(child));
+    }
+    result
+}
+
+#[test]
+fn test_rule_age() {
+    let mut simulation = Simulation::with_seed(5);
+    for _ in 0..8 {
+        simulation.perform_growth_iteration();
+    }
+    assert_eq!(simulation.plant().root().age, 8);
+    assert!(ages(simulation.plant().root()).iter().any(|&age| age > 5));
+
+    // the oldest shoots are removed with everything growing on them
+    let metamers = ages(simulation.plant().root()).len();
+    let protocol = PruningProtocol::parse("old", "thin out where older than 5").unwrap();
+    simulation.prune_by_protocol(&protocol);
+    assert!(ages(simulation.plant().root()).len() < metamers);
+    assert!(ages(simulation.plant().root()).iter().all(|&age| age <= 5));
+
+    // shoots of the last growth iteration are removed
+    simulation.perform_growth_iteration();
+    assert!(ages(simulation.plant().root()).contains(&0));
+    let protocol = PruningProtocol::parse("new", "thin out where younger than 1").unwrap();
+    simulation.prune_by_protocol(&protocol);
+    assert!(!ages(simulation.plant().root()).contains(&0));
+}
+
+#[test]
+fn test_rule_on_trunk() {
+    let mut simulation = Simulation::with_seed(5);
+    for _ in 0..8 {
+        simulation.perform_growth_iteration();
+    }
+    // amount of trunk metamers and of shoots growing from them
+    let trunk = |simulation: &Simulation| {
+        let (mut length, mut shoots) = (0, 0);
+        let mut metamer = Some(simulation.plant().root());
+        while let Some(m) = metamer {
+            length += 1;
+            shoots += m.auxillary_metamer().is_some() as u32;
+            shoots += m.extra_buds().iter().filter(|bud| bud.metamer().is_some()).count() as u32;
+            metamer = m.terminal_metamer();
+        }
+        (length, shoots)
+    };
+    let (length, shoots) = trunk(&simulation);
+    assert!(shoots > 0);
+
+    // shoots from the trunk are removed, the trunk itself is kept
+    let protocol = PruningProtocol::parse("trunk", "thin out where on trunk").unwrap();
+    simulation.prune_by_protocol(&protocol);
+    assert_eq!(trunk(&simulation), (length, 0));
+}
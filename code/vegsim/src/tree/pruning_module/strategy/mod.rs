@@ -0,0 +1,430 @@
+mod tests;
+
+use std::{
+    fmt::{self, Debug, Display},
+    str::FromStr,
+};
+
+use patfile::{pscan, pwrite};
+use patutil::Vecf3;
+
+use crate::{
+    parameters,
+    tree::{calendar::Season, metamer::Metamer, plant::Plant},
+};
+
+use super::{AutopruneCentralLeader, AutopruneFan, AutopruneHedge, AutopruneSpalier, AutopruneVase, PruningModule};
+
+#[derive(Debug)]
+pub enum StrategyError {
+    UnknownStrategy(String),
+    UnknownParameter { strategy: String, name: String },
+    OutOfRange { name: String, value: f32, min: f32, max: f32 },
+    SeasonConflict { schedule: PruningSchedule, season: PruningSeason },
+}
+
+impl std::error::Error for StrategyError {}
+
+impl Display for StrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyError::UnknownStrategy(name) => write!(f, "unknown pruning strategy '{}'", name),
+            StrategyError::UnknownParameter { strategy, name } => {
+                write!(f, "pruning strategy {} has no parameter '{}'", strategy, name)
+            }
+            StrategyError::OutOfRange { name, value, min, max } => write!(
+                f,
+                "{} = {} is out of range, expected a value from {} to {}",
+                name, value, min, max
+            ),
+            StrategyError::SeasonConflict { schedule, season } => write!(
+                f,
+                "a {} schedule prunes at the end of the year in dormancy, it never runs for a strategy pruning in {}",
+                schedule, season
+            ),
+        }
+    }
+}
+
+/**
+ * Setting of a pruning strategy, whole numbers (metamer counts) use a step of 1
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyParameter {
+    pub name: &'static str,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+impl StrategyParameter {
+    pub const fn new(name: &'static str, value: f32, min: f32, max: f32, step: f32) -> Self {
+        Self { name, value, min, max, step }
+    }
+}
+
+/**
+ * Automatic training system that prunes and ties the plant into a shape
+ */
+pub trait PruningStrategy: Debug + Send {
+    // name used in the registry, the UI and snapshots
+    fn name(&self) -> &'static str;
+
+    // current value and range of every setting
+    fn parameters(&self) -> Vec<StrategyParameter>;
+
+    // called with a value that is already checked against the range of the parameter
+    fn update_parameter(&mut self, name: &str, value: f32);
+
+    fn update_plant(&mut self, plant: &mut Plant);
+
+    // part of the year the strategy prunes in when the simulation follows a calendar
+    fn season(&self) -> PruningSeason {
+        PruningSeason::Any
+    }
+
+    // what the strategy remembers of its earlier runs, kept in snapshots, empty for most strategies
+    fn state(&self) -> String {
+        String::new()
+    }
+
+    // @returns false when the text is not a state written by state()
+    fn set_state(&mut self, state: &str) -> bool {
+        state.is_empty()
+    }
+
+    fn clone_box(&self) -> Box<dyn PruningStrategy>;
+}
+
+impl dyn PruningStrategy {
+    pub fn parameter(&self, name: &str) -> Option<f32> {
+        self.parameters()
+            .into_iter()
+            .find(|param| param.name == name)
+            .map(|param| param.value)
+    }
+
+    pub fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), StrategyError> {
+        let param = self
+            .parameters()
+            .into_iter()
+            .find(|param| param.name == name)
+            .ok_or_else(|| StrategyError::UnknownParameter {
+                strategy: self.name().to_string(),
+                name: name.to_string(),
+            })?;
+
+        // written this way round so NaN is out of range as well
+        if !(value >= param.min && value <= param.max) {
+            return Err(StrategyError::OutOfRange {
+                name: name.to_string(),
+                value,
+                min: param.min,
+                max: param.max,
+            });
+        }
+        self.update_parameter(name, value);
+        Ok(())
+    }
+}
+
+impl Clone for Box<dyn PruningStrategy> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+// creates a strategy with its default settings
+pub type StrategyFactory = fn() -> Box<dyn PruningStrategy>;
+
+/**
+ * Every pruning strategy that can be chosen at runtime, by name
+ */
+pub struct StrategyRegistry {
+    strategies: Vec<(&'static str, StrategyFactory)>,
+}
+
+impl Default for StrategyRegistry {
+    // the strategies that come with vegsim
+    fn default() -> Self {
+        let mut registry = Self { strategies: vec![] };
+        registry.register("spalier", || Box::new(AutopruneSpalier::new()));
+        registry.register("fan", || Box::new(AutopruneFan::new()));
+        registry.register("central_leader", || Box::new(AutopruneCentralLeader::new()));
+        registry.register("slender_spindle", || Box::new(AutopruneCentralLeader::slender_spindle()));
+        registry.register("vase", || Box::new(AutopruneVase::new()));
+        registry.register("hedge", || Box::new(AutopruneHedge::new()));
+        registry
+    }
+}
+
+impl StrategyRegistry {
+    // a strategy registered with an existing name replaces the old one
+    pub fn register(&mut self, name: &'static str, create: StrategyFactory) {
+        self.strategies.retain(|(known, _)| *known != name);
+        self.strategies.push((name, create));
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.strategies.iter().map(|(name, _)| *name).collect()
+    }
+
+    // @returns the strategy with its default settings
+    pub fn create(&self, name: &str) -> Result<Box<dyn PruningStrategy>, StrategyError> {
+        self.strategies
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, create)| create())
+            .ok_or_else(|| StrategyError::UnknownStrategy(name.to_string()))
+    }
+}
+
+/**
+ * Season a pruning strategy runs in, only used when the simulation follows a calendar
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruningSeason {
+    Any,
+    // in the growing season
+    Summer,
+    // in dormancy
+    Winter,
+}
+
+impl PruningSeason {
+    pub fn allows(&self, season: Season) -> bool {
+        match self {
+            PruningSeason::Any => true,
+            PruningSeason::Summer => season == Season::Growing,
+            PruningSeason::Winter => season == Season::Dormant,
+        }
+    }
+}
+
+impl Display for PruningSeason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PruningSeason::Any => write!(f, "any season"),
+            PruningSeason::Summer => write!(f, "summer"),
+            PruningSeason::Winter => write!(f, "winter"),
+        }
+    }
+}
+
+impl FromStr for PruningSeason {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any season" | "any" => Ok(PruningSeason::Any),
+            "summer" => Ok(PruningSeason::Summer),
+            "winter" => Ok(PruningSeason::Winter),
+            _ => Err(()),
+        }
+    }
+}
+
+/**
+ * Growth iterations after which a pruning strategy runs
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruningSchedule {
+    EveryIteration,
+    Every(u32),
+    // after the last iteration of every year
+    Winter,
+}
+
+impl PruningSchedule {
+    /**
+     * @param growth_iteration amount of iterations grown so far
+     * @param year_length iterations in a year, ITERATIONS_PER_YEAR without a calendar
+     */
+    pub fn is_due(&self, growth_iteration: i32, year_length: u32) -> bool {
+        match self {
+            PruningSchedule::EveryIteration => true,
+            PruningSchedule::Every(n) => growth_iteration % (*n).max(1) as i32 == 0,
+            PruningSchedule::Winter => growth_iteration % year_length.max(1) as i32 == 0,
+        }
+    }
+}
+
+impl Display for PruningSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PruningSchedule::EveryIteration => write!(f, "every iteration"),
+            PruningSchedule::Every(n) => write!(f, "every {} iterations", n),
+            PruningSchedule::Winter => write!(f, "winter"),
+        }
+    }
+}
+
+impl FromStr for PruningSchedule {
+    type Err = ();
+
+    // "iterations" can be left out, e.g. "every 3"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words[..] {
+            ["every", "iteration"] => Ok(PruningSchedule::EveryIteration),
+            ["winter"] => Ok(PruningSchedule::Winter),
+            ["every", n] | ["every", n, "iterations"] => match n.parse() {
+                Ok(0) | Err(_) => Err(()),
+                Ok(1) => Ok(PruningSchedule::EveryIteration),
+                Ok(n) => Ok(PruningSchedule::Every(n)),
+            },
+            _ => Err(()),
+        }
+    }
+}
+
+/**
+ * Pruning strategy chosen for a simulation together with its schedule
+ */
+#[derive(Debug, Clone)]
+pub struct AutoPruning {
+    strategy: Box<dyn PruningStrategy>,
+    schedule: PruningSchedule,
+    // starts as the season of the strategy
+    season: PruningSeason,
+}
+
+impl AutoPruning {
+    // @returns an error when the schedule never runs in the season of the strategy
+    pub fn new(strategy: Box<dyn PruningStrategy>, schedule: PruningSchedule) -> Result<Self, StrategyError> {
+        let season = strategy.season();
+        Self::check_season(schedule, season)?;
+        Ok(Self { strategy, schedule, season })
+    }
+
+    // the end of the year falls in dormancy when following a calendar, where a summer strategy does not prune
+    fn check_season(schedule: PruningSchedule, season: PruningSeason) -> Result<(), StrategyError> {
+        if schedule == PruningSchedule::Winter && season == PruningSeason::Summer {
+            return Err(StrategyError::SeasonConflict { schedule, season });
+        }
+        Ok(())
+    }
+
+    pub fn strategy(&self) -> &dyn PruningStrategy {
+        self.strategy.as_ref()
+    }
+
+    pub fn strategy_mut(&mut self) -> &mut dyn PruningStrategy {
+        self.strategy.as_mut()
+    }
+
+    pub fn schedule(&self) -> PruningSchedule {
+        self.schedule
+    }
+
+    // the schedule is kept when the new one never runs in the season
+    pub fn set_schedule(&mut self, schedule: PruningSchedule) -> Result<(), StrategyError> {
+        Self::check_season(schedule, self.season)?;
+        self.schedule = schedule;
+        Ok(())
+    }
+
+    pub fn season(&self) -> PruningSeason {
+        self.season
+    }
+
+    // the season is kept when the schedule never runs in the new one
+    pub fn set_season(&mut self, season: PruningSeason) -> Result<(), StrategyError> {
+        Self::check_season(self.schedule, season)?;
+        self.season = season;
+        Ok(())
+    }
+
+    /**
+     * Check if the strategy is scheduled and in its season
+     * @param growth_iteration amount of iterations grown so far
+     * @param calendar length of a year and season of the last iteration, None without a calendar
+     */
+    pub fn is_due(&self, growth_iteration: i32, calendar: Option<(u32, Season)>) -> bool {
+        let year_length = calendar.map_or(parameters::ITERATIONS_PER_YEAR, |(year_length, _)| year_length);
+        self.schedule.is_due(growth_iteration, year_length)
+            && calendar.is_none_or(|(_, season)| self.season.allows(season))
+    }
+
+    // train the plant, the caller checks is_due
+    pub fn update_plant(&mut self, plant: &mut Plant) {
+        self.strategy.update_plant(plant);
+    }
+}
+
+macro_rules! AUTO_PRUNING_STRING_FORMAT {
+    () => {
+        "strategy{} schedule{} season{} parameters{} state{}"
+    };
+}
+
+// only strategies of the default registry can be read back
+impl FromStr for AutoPruning {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name = String::new();
+        let mut schedule = PruningSchedule::EveryIteration;
+        let mut season = PruningSeason::Any;
+        let mut parameters = String::new();
+        let mut state = String::new();
+
+        let mut it = s.bytes();
+        pscan!(&mut it => AUTO_PRUNING_STRING_FORMAT!(), name, schedule, season, parameters, state).map_err(|_| ())?;
+
+        let mut strategy = StrategyRegistry::default().create(&name).map_err(|_| ())?;
+        for parameter in parameters.split_whitespace() {
+            let (name, value) = parameter.split_once('=').ok_or(())?;
+            let value = value.parse().map_err(|_| ())?;
+            strategy.set_parameter(name, value).map_err(|_| ())?;
+        }
+        if !strategy.set_state(&state) {
+            return Err(());
+        }
+        Self::check_season(schedule, season).map_err(|_| ())?;
+        Ok(Self { strategy, schedule, season })
+    }
+}
+
+impl Display for AutoPruning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parameters: Vec<String> = self
+            .strategy
+            .parameters()
+            .iter()
+            .map(|param| format!("{}={}", param.name, param.value))
+            .collect();
+
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(AUTO_PRUNING_STRING_FORMAT!() => writer,
+            self.strategy.name(),
+            self.schedule,
+            self.season,
+            parameters.join(" "),
+            self.strategy.state()
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
+
+// direction of a pole for a scaffold with the given azimuth, the angle in degrees is measured from the vertical
+pub(super) fn scaffold_pole_dir(azimuth: f32, angle: f32) -> Vecf3 {
+    let angle = angle.to_radians();
+    Vecf3::new(azimuth.cos() * angle.sin(), angle.cos(), azimuth.sin() * angle.sin())
+}
+
+// keep the main shoot of a branch at length metamers and its side shoots at side_length metamers
+pub(super) fn branch_maintenance(root: &mut Metamer, length: u32, side_length: u32) {
+    PruningModule::short_metamer_length(root, length);
+    short_branch_aux(root, length, side_length);
+}
+
+fn short_branch_aux(root: &mut Metamer, length: u32, side_length: u32) {
+    for side in root.lateral_metamers_mut() {
+        PruningModule::short_metamer_length(side, side_length);
+    }
+    if let Some(term) = root.terminal_metamer_mut() {
+        branch_maintenance(term, length, side_length);
+    }
+}
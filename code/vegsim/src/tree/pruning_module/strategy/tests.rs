@@ -0,0 +1,287 @@
+#![cfg(test)]
+
+use std::f32::consts::PI;
+
+use patutil::Vecf3;
+
+use crate::{
+    parameters,
+    profile::ParameterProfile,
+    tree::{metamer::Metamer, snapshot::Snapshot, Simulation},
+    treeparameter::{GeneticParameter, TreeParameter},
+    util::meter_to_real_length,
+};
+
+use super::{AutoPruning, PruningSchedule, PruningSeason, StrategyError, StrategyRegistry};
+
+#[test]
+fn test_strategy_registry() {
+    let registry = StrategyRegistry::default();
+    assert_eq!(registry.names(), vec!["spalier", "fan", "central_leader", "slender_spindle", "vase", "hedge"]);
+    assert_eq!(registry.create("fan").unwrap().name(), "fan");
+    assert!(matches!(registry.create("espalier"), Err(StrategyError::UnknownStrategy(_))));
+
+    let mut strategy = registry.create("spalier").unwrap();
+    assert_eq!(strategy.parameter("pass_length"), Some(6.));
+    strategy.set_parameter("pass_length", 8.).unwrap();
+    assert_eq!(strategy.parameter("pass_length"), Some(8.));
+    assert!(matches!(
+        strategy.set_parameter("pass_length", 2.),
+        Err(StrategyError::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        strategy.set_parameter("ribs", 3.),
+        Err(StrategyError::UnknownParameter { .. })
+    ));
+}
+
+#[test]
+fn test_pruning_schedule() {
+    for schedule in [PruningSchedule::EveryIteration, PruningSchedule::Every(3), PruningSchedule::Winter] {
+        assert_eq!(schedule.to_string().parse(), Ok(schedule));
+    }
+    assert_eq!("every 3".parse(), Ok(PruningSchedule::Every(3)));
+    assert_eq!("every 1".parse(), Ok(PruningSchedule::EveryIteration));
+    assert!("every 0".parse::<PruningSchedule>().is_err());
+    assert!("summer".parse::<PruningSchedule>().is_err());
+
+    let year = parameters::ITERATIONS_PER_YEAR;
+    let due: Vec<i32> = (1..=6).filter(|&it| PruningSchedule::Every(3).is_due(it, year)).collect();
+    assert_eq!(due, vec![3, 6]);
+    assert!(PruningSchedule::Winter.is_due(year as i32, year));
+    assert!(!PruningSchedule::Winter.is_due(year as i32 + 1, year));
+    assert!(PruningSchedule::Winter.is_due(6, 3));
+}
+
+#[test]
+fn test_auto_pruning_string() {
+    let mut strategy = StrategyRegistry::default().create("fan").unwrap();
+    strategy.set_parameter("fan_angle", 90.5).unwrap();
+    let auto_pruning = AutoPruning::new(strategy, PruningSchedule::Every(2)).unwrap();
+
+    let text = auto_pruning.to_string();
+    assert!(text.starts_with("strategy{fan} schedule{every 2 iterations} season{summer} parameters{"));
+    let read: AutoPruning = text.parse().unwrap();
+    assert_eq!(read.schedule(), PruningSchedule::Every(2));
+    assert_eq!(read.season(), PruningSeason::Summer);
+    assert_eq!(read.strategy().parameters(), auto_pruning.strategy().parameters());
+
+    // a summer strategy never runs at the end of the year in dormancy
+    let strategy = StrategyRegistry::default().create("fan").unwrap();
+    assert!(AutoPruning::new(strategy, PruningSchedule::Winter).is_err());
+    let mut auto_pruning = read;
+    assert!(auto_pruning.set_schedule(PruningSchedule::Winter).is_err());
+    assert_eq!(auto_pruning.schedule(), PruningSchedule::Every(2));
+    assert!(auto_pruning.set_season(PruningSeason::Winter).is_ok());
+    assert!(auto_pruning.set_schedule(PruningSchedule::Winter).is_ok());
+    assert!(auto_pruning.set_season(PruningSeason::Summer).is_err());
+    assert!("strategy{fan} schedule{winter} season{summer} parameters{}".parse::<AutoPruning>().is_err());
+}
+
+#[test]
+fn test_auto_pruning_snapshot() {
+    let strategy = StrategyRegistry::default().create("spalier").unwrap();
+    let auto_pruning = AutoPruning::new(strategy, PruningSchedule::Every(2)).unwrap();
+
+    let mut simulation = Simulation::with_seed(5);
+    simulation.update_tree_param(TreeParameter::AutoPruning(Some(auto_pruning)));
+    for _ in 0..5 {
+        simulation.perform_growth_iteration();
+    }
+    let mut data = vec![];
+    simulation.snapshot().write(&mut data).unwrap();
+
+    for _ in 0..3 {
+        simulation.perform_growth_iteration();
+    }
+    let mut expected = vec![];
+    simulation.snapshot().write(&mut expected).unwrap();
+
+    // the strategy is stored in the snapshot and keeps pruning after loading
+    let mut loaded = Simulation::from_snapshot(Snapshot::read(&mut data.into_iter()).unwrap());
+    for _ in 0..3 {
+        loaded.perform_growth_iteration();
+    }
+    let mut actual = vec![];
+    loaded.snapshot().write(&mut actual).unwrap();
+    assert_eq!(actual, expected);
+}
+
+// every metamer growing on the given one
+fn descendants(metamer: &Metamer) -> Vec<&Metamer> {
+    let mut result = vec![];
+    let children = metamer
+        .terminal_metamer()
+        .into_iter()
+        .chain(metamer.auxillary_metamer())
+        .chain(metamer.extra_buds().iter().filter_map(|bud| bud.metamer()));
+    for child in children {
+        result.push(child);
+        result.extend(descendants(child));
+    }
+    result
+}
+
+#[test]
+fn test_central_leader() {
+    let strategy = StrategyRegistry::default().create("central_leader").unwrap();
+    let param = |name: &str| strategy.parameter(name).unwrap();
+    let trunk_height = meter_to_real_length(param("trunk_height"));
+    let leader_height = meter_to_real_length(param("leader_height"));
+    let tier_spacing = meter_to_real_length(param("tier_spacing"));
+    let tier_height = meter_to_real_length(param("tier_height"));
+    let tiers = param("tiers") as usize;
+    let scaffolds_per_tier = param("scaffolds_per_tier") as u32;
+    let competitor_angle = param("competitor_angle").to_radians();
+    let cone_radius = meter_to_real_length(param("cone_radius"));
+
+    let mut simulation = Simulation::with_seed(5);
+    simulation.update_tree_param(TreeParameter::AutoPruning(Some(AutoPruning::new(
+        strategy,
+        PruningSchedule::EveryIteration,
+    )
+    .unwrap())));
+    for _ in 0..20 {
+        simulation.perform_growth_iteration();
+    }
+
+    // the trunk is clear and only the last leader metamer can reach above the leader height
+    let base = simulation.plant().root().start_point();
+    let mut leader = Some(simulation.plant().root());
+    let mut scaffolds = vec![0; tiers];
+    let mut scaffold_metamers = 0;
+    while let Some(metamer) = leader {
+        let height = metamer.end_point().y - base.y;
+        leader = metamer.terminal_metamer();
+        if leader.is_some() {
+            assert!(height <= leader_height);
+        } else if height > leader_height {
+            break;
+        }
+        assert!(metamer.extra_buds().iter().all(|bud| bud.metamer().is_none()));
+        let scaffold = match metamer.auxillary_metamer() {
+            Some(scaffold) => scaffold,
+            None => continue,
+        };
+
+        // scaffolds only grow in the tiers, never more than allowed in one tier
+        assert!(height >= trunk_height);
+        let tier = ((height - trunk_height) / tier_spacing).floor() as usize;
+        assert!(tier < tiers && height - trunk_height - tier as f32 * tier_spacing <= tier_height);
+        scaffolds[tier] += 1;
+
+        // steep shoots competing with the leader are removed
+        let up = (scaffold.direction() * Vecf3::new(0., 1., 0.)).clamp(-1., 1.);
+        assert!(up.acos() >= competitor_angle);
+
+        // the scaffolds are headed back to the cone that narrows from the first tier to the top of the leader
+        for m in descendants(scaffold) {
+            let height = m.end_point().y - base.y;
+            let fraction = ((leader_height - height) / (leader_height - trunk_height)).clamp(0., 1.);
+            let offset = Vecf3::new(m.end_point().x - base.x, 0., m.end_point().z - base.z);
+            assert!(offset.length() <= cone_radius * fraction + 1.0e-4);
+            scaffold_metamers += 1;
+        }
+    }
+    assert!(scaffolds.iter().all(|&amount| amount <= scaffolds_per_tier));
+    assert!(scaffolds.iter().sum::<u32>() > 0 && scaffold_metamers > 0);
+}
+
+#[test]
+fn test_vase() {
+    let strategy = StrategyRegistry::default().create("vase").unwrap();
+    let scaffolds = strategy.parameter("scaffolds").unwrap() as usize;
+
+    // the trunk tilts as the branches bend
+    let mut simulation = Simulation::with_params(&[GeneticParameter::WoodStiffness(0.5)], 5);
+    simulation.update_tree_param(TreeParameter::AutoPruning(Some(AutoPruning::new(
+        strategy,
+        PruningSchedule::EveryIteration,
+    )
+    .unwrap())));
+    let auto_pruning = |simulation: &Simulation| match simulation.get_tree_param(TreeParameter::AutoPruning(None)) {
+        TreeParameter::AutoPruning(Some(auto_pruning)) => auto_pruning,
+        _ => panic!("no automatic pruning"),
+    };
+    let mut chosen = String::new();
+    for _ in 0..20 {
+        simulation.perform_growth_iteration();
+        if chosen.is_empty() {
+            chosen = auto_pruning(&simulation).strategy().state();
+        }
+    }
+
+    // the scaffolds chosen first are kept and stored with the strategy
+    assert!(!chosen.is_empty());
+    let auto_pruning = auto_pruning(&simulation);
+    assert_eq!(auto_pruning.strategy().state(), chosen);
+    let read: AutoPruning = auto_pruning.to_string().parse().unwrap();
+    assert_eq!(read.strategy().state(), chosen);
+    let ids: Vec<u32> = chosen.split_whitespace().map(|id| id.parse().unwrap()).collect();
+
+    // the trunk is headed back and carries the scaffolds only
+    let mut trunk = simulation.plant().root();
+    let mut azimuths = vec![];
+    loop {
+        if trunk.auxillary_metamer().is_some() {
+            assert!(ids.contains(&trunk.id()));
+            azimuths.push(trunk.auxillary_azimuth());
+        }
+        match trunk.terminal_metamer() {
+            Some(metamer) => trunk = metamer,
+            None => break,
+        }
+    }
+    // a chosen bud can still lack the resources to grow
+    assert!(azimuths.len() >= 2 && azimuths.len() <= scaffolds);
+
+    // the scaffolds are spread around the trunk
+    azimuths.sort_by(|a, b| a.total_cmp(b));
+    let mut gaps: Vec<f32> = azimuths.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    gaps.push(2. * PI + azimuths[0] - azimuths[azimuths.len() - 1]);
+    assert!(gaps.iter().all(|&gap| gap > PI / 4.));
+}
+
+#[test]
+fn test_fan_extra_buds() {
+    let strategy = StrategyRegistry::default().create("fan").unwrap();
+    let trunk_length = strategy.parameter("trunk_length").unwrap() as u32;
+    let rib_length = strategy.parameter("rib_length").unwrap() as u32;
+    let side_shoot_length = strategy.parameter("side_shoot_length").unwrap() as u32;
+
+    let mut profile = ParameterProfile::default();
+    profile.genetics.update_param(GeneticParameter::BudsPerNode(3.));
+    profile.genetics.update_param(GeneticParameter::BranchingAngle(0.7));
+    let mut simulation = Simulation::with_profile(profile, 5);
+    simulation.update_tree_param(TreeParameter::AutoPruning(Some(AutoPruning::new(
+        strategy,
+        PruningSchedule::EveryIteration,
+    )
+    .unwrap())));
+    for _ in 0..16 {
+        simulation.perform_growth_iteration();
+    }
+
+    // the side shoots of the other buds of a node are shortened like the axillary ones
+    let mut trunk = simulation.plant().root().terminal_metamer();
+    let mut side_shoots = 0;
+    for _ in 1..trunk_length {
+        trunk = trunk.and_then(|metamer| metamer.terminal_metamer());
+    }
+    while let Some(metamer) = trunk {
+        assert!(metamer.extra_buds().iter().all(|bud| bud.metamer().is_none()));
+        if let Some(rib) = metamer.auxillary_metamer() {
+            assert!(rib.longest_path() <= rib_length);
+            let mut node = Some(rib);
+            while let Some(m) = node {
+                for side in m.lateral_metamers() {
+                    assert!(side.longest_path() <= side_shoot_length);
+                    side_shoots += 1;
+                }
+                node = m.terminal_metamer();
+            }
+        }
+        trunk = metamer.terminal_metamer();
+    }
+    assert!(side_shoots > 0);
+}
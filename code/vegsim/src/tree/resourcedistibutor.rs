@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::{treeparameter::DistributionMode, parameters};
 
-use super::metamer::Metamer;
+use super::{flowering::Flowering, metamer::Metamer};
 
 pub struct ResourceDistibutor {
     mode: DistributionMode
@@ -23,9 +23,16 @@ impl ResourceDistibutor {
     pub fn distribute_resources(&self, total_resources: f32, metamer: &mut Metamer) {
         Self::reset_bud_resources(metamer);
 
+        // flowers and fruits draw more resources than their light, empty without flowering
+        let mut demand = HashMap::new();
+        let flowering = Flowering::new(&metamer.genetics.lock().unwrap());
+        if flowering.enabled() {
+            flowering.fruit_demand(metamer, &mut demand);
+        }
+
         match self.mode {
-            DistributionMode::BorchertHonda => {Self::distribute_resources_borchert_honda(total_resources, metamer);},
-            DistributionMode::PriorityList => Self::distribute_resources_priority_list(total_resources/2., metamer),
+            DistributionMode::BorchertHonda => {Self::distribute_resources_borchert_honda(total_resources, metamer, &demand);},
+            DistributionMode::PriorityList => Self::distribute_resources_priority_list(total_resources/2., metamer, &demand),
             DistributionMode::None => return,
         }
     }
@@ -39,9 +46,16 @@ impl ResourceDistibutor {
         }
 
         // distribute aux resources
-        if let Some(metamer) = metamer.auxillary_metamer() {
+        if let Some(metamer) = metamer.auxillary_metamer_mut() {
             Self::reset_bud_resources(metamer);
         }
+
+        for bud in metamer.extra_buds_mut() {
+            bud.last_resources = 0.;
+            if let Some(metamer) = bud.metamer_mut() {
+                Self::reset_bud_resources(metamer);
+            }
+        }
     }
 
     fn borchert_honda_split(resources: f32, q_m: f32,  q_l: f32, lambda: f32) -> [f32;2]{
@@ -52,10 +66,31 @@ impl ResourceDistibutor {
         return [v_m, v_l]
     }
 
-    fn distribute_resources_borchert_honda(total_resources: f32, metamer: &mut Metamer) -> f32 {
+    // extra light of a branch or flower from the fruit demand
+    fn demand_of(id: u32, demand: &HashMap<u32, f32>) -> f32 {
+        *demand.get(&id).unwrap_or(&0.)
+    }
+
+    fn terminal_demand(metamer: &Metamer, demand: &HashMap<u32, f32>) -> f32 {
+        match metamer.terminal_metamer() {
+            Some(m) => Self::demand_of(m.id(), demand),
+            None => Self::demand_of(metamer.terminal_bud_data.id(), demand),
+        }
+    }
+
+    fn branch_demand(metamer: Option<&Metamer>, demand: &HashMap<u32, f32>) -> f32 {
+        metamer.map_or(0., |m| Self::demand_of(m.id(), demand))
+    }
+
+    fn distribute_resources_borchert_honda(total_resources: f32, metamer: &mut Metamer, demand: &HashMap<u32, f32>) -> f32 {
         let lambda =  metamer.genetics.lock().unwrap().borchert_honda_lambda();
-        let q_m = metamer.last_terminal_light_generated;
-        let q_l = metamer.last_aux_light_generated;
+        let q_m = metamer.last_terminal_light_generated + Self::terminal_demand(metamer, demand);
+        // every axillary bud of the node counts as lateral, the leaves of the node feed its axillary buds
+        let leaf_share = metamer.leaf_light() / (metamer.extra_buds().len() + 1) as f32;
+        let mut q_l = metamer.lateral_light() + metamer.leaf_light() + Self::branch_demand(metamer.auxillary_metamer(), demand);
+        for bud in metamer.extra_buds() {
+            q_l += Self::branch_demand(bud.metamer(), demand);
+        }
 
         if q_m + q_l <= 0. {
             // no light gathered, prevent division by zero
@@ -65,7 +100,23 @@ impl ResourceDistibutor {
         [metamer.last_terminal_resources, metamer.last_aux_resources] = ResourceDistibutor::borchert_honda_split(total_resources, q_m, q_l, lambda);
         let mut bonus = 0.0f32;
 
-        
+        // split the lateral resources between the buds of the node by their light
+        if !metamer.extra_buds().is_empty() && q_l > 0. {
+            let v_l = metamer.last_aux_resources;
+            let q_aux = metamer.last_aux_light_generated + leaf_share + Self::branch_demand(metamer.auxillary_metamer(), demand);
+            metamer.last_aux_resources = v_l * q_aux / q_l;
+            let mut damaged = 0.;
+            for bud in metamer.extra_buds_mut() {
+                let q_bud = bud.last_light_generated + leaf_share + Self::branch_demand(bud.metamer(), demand);
+                bud.last_resources = v_l * q_bud / q_l;
+                if bud.damage > 0. {
+                    damaged += bud.last_resources;
+                    bud.last_resources = 0.;
+                }
+            }
+            metamer.last_terminal_resources += damaged;
+        }
+
         if metamer.auxillary_bud_damage > 0.{
             // give all resources to terminal bud
             metamer.last_terminal_resources += metamer.last_aux_resources;
@@ -82,7 +133,7 @@ impl ResourceDistibutor {
         // distribute terminal resources
         let v_m = metamer.last_terminal_resources;
         if let Some(m) = metamer.terminal_metamer_mut() {
-            bonus +=  Self::distribute_resources_borchert_honda(v_m, m);
+            bonus +=  Self::distribute_resources_borchert_honda(v_m, m, demand);
         }
 
 
@@ -90,8 +141,14 @@ impl ResourceDistibutor {
 
         // distribute aux resources
         let v_l = metamer.last_aux_resources;
-        if let Some(m) = metamer.auxillary_metamer() {
-            Self::distribute_resources_borchert_honda(v_l, m);
+        if let Some(m) = metamer.auxillary_metamer_mut() {
+            Self::distribute_resources_borchert_honda(v_l, m, demand);
+        }
+        for bud in metamer.extra_buds_mut() {
+            let v_l = bud.last_resources;
+            if let Some(m) = bud.metamer_mut() {
+                Self::distribute_resources_borchert_honda(v_l, m, demand);
+            }
         }
         return bonus*0.5;
     }
@@ -131,15 +188,20 @@ impl ResourceDistibutor {
         return W_MAX - ((i / (total * K)) * (W_MAX - W_MIN));
     }
 
-    fn create_priority_list(metamer: &mut Metamer) -> Vec<BudInfo>{
+    fn create_priority_list(metamer: &mut Metamer, demand: &HashMap<u32, f32>) -> Vec<BudInfo>{
         let mut priority_list: Vec<BudInfo> = vec![];
 
         // iterate over metamers of the branch
         let mut branch_metamer: &mut Metamer = metamer;
         loop {
+            // the leaves of the node feed its axillary buds
+            let leaf_share = branch_metamer.leaf_light() / (branch_metamer.extra_buds().len() + 1) as f32;
+
             // gather auxillary bud data
             let auxbud = BudInfo{
-                light_collected: branch_metamer.last_aux_light_generated,
+                light_collected: branch_metamer.last_aux_light_generated
+                    + leaf_share
+                    + Self::branch_demand(branch_metamer.auxillary_metamer(), demand),
                 bud_id: branch_metamer.aux_bud_data.id(),
                 total_buds: branch_metamer.auxillary_metamer().as_ref().map_or(1, |b|b.total_metamers()),
             };
@@ -149,6 +211,18 @@ impl ResourceDistibutor {
                 Self::priority_list_insert(&mut priority_list, auxbud);
             }
 
+            // the other buds of the node
+            for bud in branch_metamer.extra_buds() {
+                if bud.damage == 0. {
+                    let extrabud = BudInfo{
+                        light_collected: bud.last_light_generated + leaf_share + Self::branch_demand(bud.metamer(), demand),
+                        bud_id: bud.bud_data.id(),
+                        total_buds: bud.metamer().map_or(1, |b|b.total_metamers()),
+                    };
+                    Self::priority_list_insert(&mut priority_list, extrabud);
+                }
+            }
+
             // advance to next metamer in branch
             if branch_metamer.terminal_metamer().is_some(){
                 branch_metamer = branch_metamer.terminal_metamer_mut().unwrap();
@@ -160,7 +234,8 @@ impl ResourceDistibutor {
         // insert terminal bud into priority list
         if branch_metamer.terminal_bud_damage == 0.{
             let terminalbud = BudInfo{
-                light_collected: branch_metamer.last_terminal_light_generated,
+                light_collected: branch_metamer.last_terminal_light_generated
+                    + Self::terminal_demand(branch_metamer, demand),
                 bud_id: branch_metamer.terminal_bud_data.id(),
                 total_buds: 1,
             };
@@ -169,7 +244,7 @@ impl ResourceDistibutor {
         return priority_list;
     }
 
-    fn distribute_prioritylist_map(metamer: &mut Metamer, resource_map: HashMap<u32, f32>){
+    fn distribute_prioritylist_map(metamer: &mut Metamer, resource_map: HashMap<u32, f32>, demand: &HashMap<u32, f32>){
 
         // iterate over every bud of the branch
         let mut branch_metamer: &mut Metamer = metamer;
@@ -179,8 +254,16 @@ impl ResourceDistibutor {
             branch_metamer.last_aux_resources = resources;
 
             // distribute auxillary resources
-            if let Some(m) = &mut branch_metamer.auxillary_metamer(){
-                Self::distribute_resources_priority_list(resources, m);
+            if let Some(m) = &mut branch_metamer.auxillary_metamer_mut(){
+                Self::distribute_resources_priority_list(resources, m, demand);
+            }
+
+            for bud in branch_metamer.extra_buds_mut() {
+                let resources = *resource_map.get(&bud.bud_data.id()).unwrap_or(&0.);
+                bud.last_resources = resources;
+                if let Some(m) = bud.metamer_mut() {
+                    Self::distribute_resources_priority_list(resources, m, demand);
+                }
             }
 
             // advance to next metamer in branch
@@ -196,14 +279,14 @@ impl ResourceDistibutor {
         branch_metamer.last_terminal_resources = *resource_map.get(&branch_metamer.terminal_bud_data.id()).unwrap_or(&0.);
     }
 
-    fn distribute_resources_priority_list(total_resources: f32, metamer: &mut Metamer) {
+    fn distribute_resources_priority_list(total_resources: f32, metamer: &mut Metamer, demand: &HashMap<u32, f32>) {
         if metamer.last_light_generated <= 0. {
             // no light gathered
             return;
         }
         
         // create the priority list
-        let priority_list = Self::create_priority_list(metamer);
+        let priority_list = Self::create_priority_list(metamer, demand);
 
         // calculate sum of priorities
         let mut priority_sum = 0.;
@@ -223,7 +306,7 @@ impl ResourceDistibutor {
         }
 
         // give calculated resources to buds
-        Self::distribute_prioritylist_map(metamer, resource_map);
+        Self::distribute_prioritylist_map(metamer, resource_map, demand);
     }
 
     pub fn set_mode(&mut self, mode: DistributionMode) {
@@ -1,6 +1,8 @@
 use patutil::{Color, Vec3, Vecf3, Vecu3};
 
-use crate::{util::BoundingVolume, parameters};
+use crate::util::BoundingVolume;
+
+use super::environment::EnvironmentParameters;
 
 type ShadowVoxel = f32;
 
@@ -9,16 +11,26 @@ pub struct ShadowVoxelSet {
     boudingbox: BoundingVolume,
     resolution: Vecu3,
     voxels: Vec<f32>,
+    a: f32,
+    b: f32,
+    c: f32,
+    max_shadow: f32,
+    piramid_layers: i32,
 }
 
 impl ShadowVoxelSet {
-    pub fn new(boudingbox: BoundingVolume, resolution: Vecu3) -> Self {
+    pub fn new(boudingbox: BoundingVolume, resolution: Vecu3, parameters: &EnvironmentParameters) -> Self {
         let mut voxels = vec![];
         voxels.resize((resolution.x * resolution.y * resolution.z) as usize, 0.);
         Self {
             boudingbox,
             resolution,
             voxels,
+            a: parameters.shadow_voxel_a,
+            b: parameters.shadow_voxel_b,
+            c: parameters.shadow_voxel_c,
+            max_shadow: parameters.shadow_voxel_max_shadow,
+            piramid_layers: parameters.shadow_voxel_piramid_layers,
         }
     }
 
@@ -29,23 +41,29 @@ impl ShadowVoxelSet {
     }
 
     pub fn add_shadow(&mut self, pos: Vecf3) {
+        self.add_shade(pos, 1.);
+    }
+
+    // add a shadow scaled by amount, a metamer casts a shadow of 1
+    pub fn add_shade(&mut self, pos: Vecf3, amount: f32) {
         let voxel_p = self
             .boudingbox
             .reverse_interpolate(pos, self.resolution, false);
-        let layers = parameters::SHADOW_VOXEL_PIRAMID_LAYERS.min(voxel_p.y as i32 + 1);
+        let layers = self.piramid_layers.min(voxel_p.y as i32 + 1);
         for layer in 0..layers {
             let min_p = voxel_p.to_i32()
                 - Vec3::new(layer, 0, layer);
             let max_p = voxel_p.to_i32()
                 + Vec3::new(layer + 1, 0, layer + 1);
 
+            let shadow = self.a * self.b.powf(-layer as f32) * amount;
             let y = voxel_p.y - layer as u32;
             for x in min_p.x..max_p.x {
                 for z in min_p.z..max_p.z {
                     let pos = Vecu3::new(x as u32, y, z as u32);
                     let voxel = self.get_voxel_mut(pos);
                     if let Some(voxel) = voxel{
-                        *voxel += parameters::SHADOW_VOXEL_A * parameters::SHADOW_VOXEL_B.powf(-layer as f32);
+                        *voxel += shadow;
                     }
                 }
             }
@@ -66,6 +84,33 @@ impl ShadowVoxelSet {
         return Some(optimal_dir.norm());
     }
 
+    /**
+     * Direction the light comes from, the average of the directions to the voxels around the position weighted by their exposure.
+     * Its length grows with the difference in exposure around the position,
+     * @returns the zero vector under even light and when no light reaches the position
+     */
+    pub fn light_direction(&self, pos: Vecf3, r: f32) -> Vecf3 {
+        let mut light_dir = Vecf3::new(0., 0., 0.);
+        let mut samples = 0;
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    if x == 0 && y == 0 && z == 0 {
+                        continue;
+                    }
+                    let dir = Vecf3::new(x as f32, y as f32, z as f32).norm();
+                    light_dir += dir * self.get_light_exposure(pos + dir * r);
+                    samples += 1;
+                }
+            }
+        }
+
+        if light_dir.length() < 1.0e-6 {
+            return Vecf3::new(0., 0., 0.);
+        }
+        return light_dir / samples as f32;
+    }
+
     pub fn get_light_exposure(&self, pos: Vecf3) -> f32 {
         let voxel = self
             .get_voxel(
@@ -73,9 +118,9 @@ impl ShadowVoxelSet {
                     .reverse_interpolate(pos, self.resolution, false),
             );
 
-        let shadow = *voxel.unwrap_or(&parameters::SHADOW_VOXEL_MAX_SHADOW);
+        let shadow = *voxel.unwrap_or(&self.max_shadow);
 
-        return f32::max(parameters::SHADOW_VOXEL_C - shadow + parameters::SHADOW_VOXEL_A, 0.);
+        return f32::max(self.c - shadow + self.a, 0.);
     }
 
 
@@ -125,7 +170,7 @@ impl ShadowVoxelSet {
                     if let Some(voxel) = self.get_voxel(self.boudingbox.reverse_interpolate(p, self.resolution, false)){
                         voxels.push((p, voxel));
                     } else {
-                        voxels.push((p, &parameters::SHADOW_VOXEL_MAX_SHADOW));
+                        voxels.push((p, &self.max_shadow));
                     }
                     p.z += step.z;
                 }
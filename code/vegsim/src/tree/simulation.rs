@@ -0,0 +1,408 @@
+use std::sync::{Arc, Mutex};
+
+use patutil::Vecf3;
+
+use crate::{parameters, profile::ParameterProfile, treeparameter::TreeParameter, util::random::Random};
+
+use super::{
+    calendar::{Calendar, Season, SeasonHistory},
+    environment::Environment,
+    flowering::{FruitHistory, FruitStats},
+    metamer::Metamer,
+    plant::Plant,
+    plantgenetics::PlantGenetics,
+    pruning_module::{
+        hedge::PruningVolume,
+        history::{PlantState, PruningHistory},
+        rules::PruningProtocol,
+        strategy::AutoPruning,
+        PruneOperation, PruningModule,
+    },
+    snapshot::Snapshot,
+};
+
+/**
+ * Plant and environment of one simulation run, without any drawing
+ */
+pub struct Simulation {
+    profile: ParameterProfile,
+    environment: Environment,
+    plant_genetics: Arc<Mutex<PlantGenetics>>,
+    plant: Plant,
+    random: Random,
+    growth_iteration: i32,
+    auto_pruning: Option<AutoPruning>,
+    pruning_history: PruningHistory,
+    fruit_history: FruitHistory,
+    // None: every iteration is a growth iteration
+    calendar: Option<Calendar>,
+    season_history: SeasonHistory,
+}
+
+impl Simulation {
+    pub fn new() -> Self {
+        Self::with_seed(parameters::SEED)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_profile(ParameterProfile::default(), seed)
+    }
+
+    pub fn with_profile(profile: ParameterProfile, seed: u64) -> Self {
+        let mut random = Random::new(seed);
+
+        // create plant
+        let genetics = Arc::new(Mutex::new(profile.genetics.clone()));
+        let mut plant = Plant::new(Self::seed_pos(&profile), genetics.clone(), &mut random);
+        plant.set_resource_distibution_mode(profile.distribution_mode);
+
+        Self {
+            environment: Environment::new(&profile.environment, &mut random),
+            profile,
+            plant,
+            random,
+            plant_genetics: genetics,
+            growth_iteration: 0,
+            auto_pruning: None,
+            pruning_history: PruningHistory::default(),
+            fruit_history: FruitHistory::default(),
+            calendar: None,
+            season_history: SeasonHistory::default(),
+        }
+    }
+
+    // the default profile with the given genetic parameters
+    #[cfg(test)]
+    pub(crate) fn with_params(params: &[crate::treeparameter::GeneticParameter], seed: u64) -> Self {
+        let mut profile = ParameterProfile::default();
+        for param in params {
+            profile.genetics.update_param(param.clone());
+        }
+        Self::with_profile(profile, seed)
+    }
+
+    // recreate a simulation that continues exactly like the one the snapshot was taken from
+    pub fn from_snapshot(snapshot: Snapshot) -> Self {
+        // markers are placed randomly, create them from the same seed as the original run
+        let profile = ParameterProfile {
+            name: snapshot.profile_name.clone(),
+            distribution_mode: snapshot.distribution_mode,
+            genetics: snapshot.genetics.clone(),
+            environment: snapshot.environment.clone(),
+        };
+        let mut simulation = Self::with_profile(profile, snapshot.seed);
+        simulation.set_state(snapshot);
+        simulation
+    }
+
+    /**
+     * Go back to an earlier state of this simulation, the profile stays the same.
+     * The environment is kept, it is only rebuilt for a snapshot of another seed or space.
+     */
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        if snapshot.seed != self.random.seed() || snapshot.environment != self.profile.environment {
+            let profile = self.profile.clone();
+            *self = Self::from_snapshot(snapshot);
+            self.profile = profile;
+            return;
+        }
+        self.set_state(snapshot);
+    }
+
+    // the plant, random numbers and histories of a snapshot, the markers are not part of the state
+    fn set_state(&mut self, snapshot: Snapshot) {
+        *self.plant_genetics.lock().unwrap() = snapshot.genetics;
+        self.plant.set_root(snapshot.root);
+        self.plant.ids_mut().set_next_id(snapshot.next_id);
+        self.plant.set_resource_distibution_mode(snapshot.distribution_mode);
+        self.environment.set_mode(snapshot.space_mode);
+        self.growth_iteration = snapshot.growth_iteration;
+        self.auto_pruning = snapshot.auto_pruning;
+        self.pruning_history = snapshot.pruning_history;
+        self.fruit_history = snapshot.fruit_history;
+        self.calendar = snapshot.calendar;
+        self.season_history = snapshot.season_history;
+        self.random = Random::from_state(snapshot.seed, snapshot.random_draws);
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            growth_iteration: self.growth_iteration,
+            seed: self.random.seed(),
+            random_draws: self.random.draws(),
+            next_id: self.plant.ids().next_id(),
+            space_mode: self.environment.mode(),
+            distribution_mode: self.plant.resource_distibution_mode(),
+            auto_pruning: self.auto_pruning.clone(),
+            pruning_history: self.pruning_history.clone(),
+            fruit_history: self.fruit_history.clone(),
+            calendar: self.calendar,
+            season_history: self.season_history.clone(),
+            genetics: self.plant_genetics.lock().unwrap().clone(),
+            profile_name: self.profile.name.clone(),
+            environment: self.profile.environment.clone(),
+            root: self.plant.root().clone(),
+        }
+    }
+
+    fn seed_pos(profile: &ParameterProfile) -> Vecf3 {
+        let bounding_volume = profile.environment.bounding_volume();
+        let min_p = bounding_volume.min_pos();
+        let max_p = bounding_volume.max_pos();
+        let mut seed_pos = min_p + (max_p - min_p) / 2.;
+        seed_pos.y = 0.;
+        seed_pos
+    }
+
+    pub fn perform_growth_iteration(&mut self) {
+        println!("--Growth iteration {}", self.growth_iteration);
+        let iteration = self.growth_iteration;
+        let season = self.calendar.map(|calendar| calendar.season(iteration));
+        let metamers_start = match season {
+            Some(_) => self.plant.root().count_metamers(),
+            None => 0,
+        };
+
+        let mut leaves_fallen = 0;
+        match season {
+            Some(Season::Dormant) => leaves_fallen = self.plant.perform_dormant_iteration(),
+            _ => self.plant.perform_growth_iteration(&mut self.environment, &mut self.random),
+        }
+
+        self.growth_iteration += 1;
+        if self.plant_genetics.lock().unwrap().spur_length() > 0 {
+            self.fruit_history.push(FruitStats {
+                iteration: self.growth_iteration,
+                ..self.plant.fruit_stats().clone()
+            });
+        }
+
+        // train the plant with the chosen strategy when it is scheduled and in its season
+        let calendar = self.calendar.zip(season).map(|(calendar, season)| (calendar.year_length(), season));
+        if let Some(auto_pruning) = self
+            .auto_pruning
+            .as_mut()
+            .filter(|auto_pruning| auto_pruning.is_due(self.growth_iteration, calendar))
+        {
+            let before = PlantState::new(self.plant.root());
+            auto_pruning.update_plant(&mut self.plant);
+            let source = auto_pruning.strategy().name();
+            self.pruning_history.record(self.growth_iteration, source, &before, self.plant.root());
+            self.plant.bend_branches();
+        }
+
+        if let Some(calendar) = &self.calendar {
+            self.season_history
+                .record(calendar, iteration, metamers_start, self.plant.root(), leaves_fallen);
+        }
+    }
+
+    // reset plant and environment to the state before the first growth iteration
+    pub fn reset(&mut self) {
+        self.random = Random::new(self.random.seed());
+        self.growth_iteration = 0;
+        self.pruning_history.clear();
+        self.fruit_history.clear();
+        self.season_history.clear();
+
+        // reset plant
+        self.plant.reset(Self::seed_pos(&self.profile), &mut self.random);
+
+        // reset environment
+        let mode = self.environment.mode();
+        self.environment = Environment::new(&self.profile.environment, &mut self.random);
+        self.environment.set_mode(mode);
+    }
+
+    // reset and perform the same amount of growth iterations again
+    pub fn recalculate(&mut self) {
+        let it = self.growth_iteration;
+        self.reset();
+        for _ in 0..it {
+            self.perform_growth_iteration();
+        }
+    }
+
+    // replace the plant by an imported metamer tree and start counting iterations again
+    pub fn import_plant(&mut self, root: Metamer) {
+        self.plant.set_root(root);
+        self.growth_iteration = 0;
+        self.pruning_history.clear();
+        self.fruit_history.clear();
+        self.season_history.clear();
+    }
+
+    pub fn profile(&self) -> &ParameterProfile {
+        &self.profile
+    }
+
+    // the profile with the genetics and modes the plant grows with now, e.g. after changing them in the UI
+    pub fn current_profile(&self, name: &str) -> ParameterProfile {
+        let mut profile = self.profile.clone();
+        profile.name = name.to_string();
+        profile.genetics = self.plant_genetics.lock().unwrap().clone();
+        profile.distribution_mode = self.plant.resource_distibution_mode();
+        profile.environment.space_div_mode = self.environment.mode();
+        profile
+    }
+
+    // grow the same amount of iterations again with the parameters of another profile
+    pub fn apply_profile(&mut self, profile: ParameterProfile) {
+        *self.plant_genetics.lock().unwrap() = profile.genetics.clone();
+        self.plant.set_resource_distibution_mode(profile.distribution_mode);
+        self.environment.set_mode(profile.environment.space_div_mode);
+        self.profile = profile;
+
+        self.recalculate();
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.random.seed()
+    }
+
+    // grow the same amount of iterations again from another seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.random = Random::new(seed);
+        self.recalculate();
+    }
+
+    pub fn update_markers(&mut self) {
+        self.environment.markers_mut().reset();
+        self.plant.place_markers(self.environment.markers_mut());
+    }
+
+    pub fn prune_id(&mut self, id: u32) {
+        let before = PlantState::new(self.plant.root());
+        self.plant.prune_id(id);
+        self.record_prune("manual", &before);
+    }
+
+    // @returns true when the metamer was found
+    pub fn cut_id(&mut self, id: u32, fraction: f32) -> bool {
+        println!("Cut metamer {} at {:.2}", id, fraction);
+        let before = PlantState::new(self.plant.root());
+        let found = self.plant.cut_id(id, fraction);
+        self.record_prune("cut", &before);
+        found
+    }
+
+    pub fn prune_by_rule(&mut self, rule_index: PruneOperation) {
+        println!("Prune {}", rule_index);
+        let before = PlantState::new(self.plant.root());
+        PruningModule::prune_by_rule(rule_index.clone(), &mut self.plant, &mut self.random);
+        self.record_prune(&rule_index.to_string(), &before);
+    }
+
+    pub fn prune_by_protocol(&mut self, protocol: &PruningProtocol) {
+        println!("Prune by rules {}", protocol.name);
+        let before = PlantState::new(self.plant.root());
+        protocol.apply(&mut self.plant);
+        self.record_prune(&protocol.name, &before);
+    }
+
+    // @returns amount of metamers removed
+    pub fn trim_to_volume(&mut self, volume: &PruningVolume) -> u32 {
+        let before = PlantState::new(self.plant.root());
+        let removed = volume.trim(&mut self.plant);
+        self.record_prune("hedge", &before);
+        println!("Trimmed {} metamers outside the volume", removed);
+        removed
+    }
+
+    // record a prune, the branches rise with the load it took away
+    fn record_prune(&mut self, source: &str, before: &PlantState) {
+        self.pruning_history.record(self.growth_iteration, source, before, self.plant.root());
+        self.plant.bend_branches();
+    }
+
+    pub fn pruning_history(&self) -> &PruningHistory {
+        &self.pruning_history
+    }
+
+    pub fn fruit_history(&self) -> &FruitHistory {
+        &self.fruit_history
+    }
+
+    pub fn calendar(&self) -> Option<Calendar> {
+        self.calendar
+    }
+
+    // season of the last iteration performed, None without a calendar
+    pub fn last_season(&self) -> Option<Season> {
+        self.calendar.map(|calendar| calendar.season(self.growth_iteration - 1))
+    }
+
+    pub fn season_history(&self) -> &SeasonHistory {
+        &self.season_history
+    }
+
+    pub fn get_metamer_by_id(&self, id: u32) -> Option<Metamer> {
+        self.plant.get_metamer_by_id(id)
+    }
+
+    pub fn plant(&self) -> &Plant {
+        &self.plant
+    }
+
+    pub fn plant_mut(&mut self) -> &mut Plant {
+        &mut self.plant
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn plant_genetics(&self) -> &Mutex<PlantGenetics> {
+        &self.plant_genetics
+    }
+
+    pub fn shared_plant_genetics(&self) -> Arc<Mutex<PlantGenetics>> {
+        self.plant_genetics.clone()
+    }
+
+    pub fn growth_iteration(&self) -> i32 {
+        self.growth_iteration
+    }
+
+    pub fn update_tree_param(&mut self, param: TreeParameter) {
+        match param {
+            TreeParameter::Genetic(param) => {
+                self.plant_genetics.lock().unwrap().update_param(param)
+            }
+            TreeParameter::ResourceDistributionMode(mode) => {
+                self.plant.set_resource_distibution_mode(mode)
+            }
+            TreeParameter::SpaceDividingMode(mode) => {
+                self.environment.set_mode(mode)
+            }
+            TreeParameter::AutoPruning(auto_pruning) =>{
+                self.auto_pruning = auto_pruning;
+            }
+            TreeParameter::Calendar(calendar) => {
+                self.calendar = calendar;
+            }
+        }
+    }
+    // set genetics and modes back to the values of the profile, takes effect on the next growth iteration
+    pub fn reset_tree_params(&mut self) {
+        *self.plant_genetics.lock().unwrap() = self.profile.genetics.clone();
+        self.plant.set_resource_distibution_mode(self.profile.distribution_mode);
+        self.environment.set_mode(self.profile.environment.space_div_mode);
+    }
+
+    pub fn get_tree_param(&self, param: TreeParameter) -> TreeParameter{
+        match param {
+            TreeParameter::Genetic(param) => {
+                TreeParameter::Genetic(self.plant_genetics.lock().unwrap().get_param(param))
+            }
+            TreeParameter::ResourceDistributionMode(_) => {
+                TreeParameter::ResourceDistributionMode(self.plant.resource_distibution_mode())
+            }
+            TreeParameter::SpaceDividingMode(_) => {
+                TreeParameter::SpaceDividingMode(self.environment.mode())
+            }
+            TreeParameter::AutoPruning(_) => TreeParameter::AutoPruning(self.auto_pruning.clone()),
+            TreeParameter::Calendar(_) => TreeParameter::Calendar(self.calendar),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+mod tests;
+
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io::{self, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use patfile::{pscan, pwrite};
+
+use crate::treeparameter::{DistributionMode, SpaceDividingMode};
+
+use super::{
+    calendar::{Calendar, SeasonHistory},
+    environment::EnvironmentParameters, flowering::FruitHistory, metamer::Metamer, plantgenetics::PlantGenetics,
+    pruning_module::{history::PruningHistory, strategy::AutoPruning},
+};
+
+// increase when the snapshot format changes
+pub const SNAPSHOT_VERSION: u32 = 15;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Format(patfile::Error),
+    Version(u32),
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "could not access snapshot file: {}", err),
+            SnapshotError::Format(patfile::Error::Parse(data, _)) => {
+                // nested values can be very long, only show the start
+                let start: String = data.chars().take(60).collect();
+                write!(f, "invalid snapshot value: {}...", start)
+            }
+            SnapshotError::Format(err) => write!(f, "invalid snapshot: {}", err),
+            SnapshotError::Version(version) => write!(
+                f,
+                "snapshot version {} is not supported, expected version {}",
+                version, SNAPSHOT_VERSION
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<patfile::Error> for SnapshotError {
+    fn from(err: patfile::Error) -> Self {
+        SnapshotError::Format(err)
+    }
+}
+
+/**
+ * Optional value in a snapshot, written as "none" when missing
+ */
+pub struct SnapshotOption<T>(pub Option<T>);
+
+impl<T: Display> Display for SnapshotOption<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, "none"),
+        }
+    }
+}
+
+impl<T: FromStr> FromStr for SnapshotOption<T> {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "none" {
+            return Ok(SnapshotOption(None));
+        }
+        s.parse().map(|value| SnapshotOption(Some(value))).map_err(|_| ())
+    }
+}
+
+/**
+ * Everything needed to continue a simulation deterministically
+ */
+#[derive(Clone)]
+pub struct Snapshot {
+    pub growth_iteration: i32,
+    pub seed: u64,
+    pub random_draws: u64,
+    pub next_id: u32,
+    pub space_mode: SpaceDividingMode,
+    pub distribution_mode: DistributionMode,
+    pub auto_pruning: Option<AutoPruning>,
+    pub pruning_history: PruningHistory,
+    pub fruit_history: FruitHistory,
+    pub calendar: Option<Calendar>,
+    pub season_history: SeasonHistory,
+    pub genetics: PlantGenetics,
+    pub profile_name: String,
+    pub environment: EnvironmentParameters,
+    pub root: Metamer,
+}
+
+impl Snapshot {
+    pub fn save(&self, path: &Path) -> Result<(), SnapshotError> {
+        let mut file = File::create(path)?;
+        self.write(&mut file)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SnapshotError> {
+        let mut data = vec![];
+        File::open(path)?.read_to_end(&mut data)?;
+        Self::read(&mut data.into_iter())
+    }
+
+    pub fn write(&self, out: &mut dyn io::Write) -> Result<(), SnapshotError> {
+        pwrite!(out, "vegsim snapshot version{}\n", SNAPSHOT_VERSION)?;
+        pwrite!(out, "iteration{} seed{} draws{} nextid{}\n",
+            self.growth_iteration,
+            self.seed,
+            self.random_draws,
+            self.next_id
+        )?;
+        pwrite!(out, "spacemode{} distribution{} autopruning{}\n",
+            self.space_mode,
+            self.distribution_mode,
+            SnapshotOption(self.auto_pruning.as_ref())
+        )?;
+        pwrite!(out, "genetics{}\n", &self.genetics)?;
+        pwrite!(out, "profile{}\n", &self.profile_name)?;
+        pwrite!(out, "environment{}\n", &self.environment)?;
+        pwrite!(out, "history{}\n", &self.pruning_history)?;
+        pwrite!(out, "fruit{}\n", &self.fruit_history)?;
+        pwrite!(out, "calendar{} seasons{}\n", SnapshotOption(self.calendar.as_ref()), &self.season_history)?;
+        pwrite!(out, "plant{}\n", &self.root)?;
+        Ok(())
+    }
+
+    pub fn read(input: &mut dyn Iterator<Item = u8>) -> Result<Self, SnapshotError> {
+        let mut version: u32 = 0;
+        pscan!(input => "vegsim snapshot version{}\n", version)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::Version(version));
+        }
+
+        let mut growth_iteration: i32 = 0;
+        let mut seed: u64 = 0;
+        let mut random_draws: u64 = 0;
+        let mut next_id: u32 = 0;
+        pscan!(input => "iteration{} seed{} draws{} nextid{}\n",
+            growth_iteration,
+            seed,
+            random_draws,
+            next_id
+        )?;
+
+        let mut space_mode = SpaceDividingMode::None;
+        let mut distribution_mode = DistributionMode::None;
+        let mut auto_pruning = SnapshotOption::<AutoPruning>(None);
+        pscan!(input => "spacemode{} distribution{} autopruning{}\n",
+            space_mode,
+            distribution_mode,
+            auto_pruning
+        )?;
+
+        let mut genetics = PlantGenetics::new();
+        pscan!(input => "genetics{}\n", genetics)?;
+
+        let mut profile_name = String::new();
+        pscan!(input => "profile{}\n", profile_name)?;
+
+        let mut environment = EnvironmentParameters::default();
+        pscan!(input => "environment{}\n", environment)?;
+
+        let mut pruning_history = PruningHistory::default();
+        pscan!(input => "history{}\n", pruning_history)?;
+
+        let mut fruit_history = FruitHistory::default();
+        pscan!(input => "fruit{}\n", fruit_history)?;
+
+        let mut calendar = SnapshotOption::<Calendar>(None);
+        let mut season_history = SeasonHistory::default();
+        pscan!(input => "calendar{} seasons{}\n", calendar, season_history)?;
+
+        let mut root = SnapshotOption::<Metamer>(None);
+        pscan!(input => "plant{}\n", root)?;
+        let root = root.0.ok_or(SnapshotError::Format(patfile::Error::MissingMatch))?;
+
+        Ok(Self {
+            growth_iteration,
+            seed,
+            random_draws,
+            next_id,
+            space_mode,
+            distribution_mode,
+            auto_pruning: auto_pruning.0,
+            pruning_history,
+            fruit_history,
+            calendar: calendar.0,
+            season_history,
+            genetics,
+            profile_name,
+            environment,
+            root,
+        })
+    }
+}
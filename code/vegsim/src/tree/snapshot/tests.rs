@@ -0,0 +1,106 @@
+#![cfg(test)]
+
+use std::f32::consts::FRAC_PI_2;
+
+use crate::{
+    profile::ParameterProfile,
+    tree::{Simulation},
+    treeparameter::GeneticParameter,
+};
+
+use super::Snapshot;
+
+fn to_bytes(snapshot: &Snapshot) -> Vec<u8> {
+    let mut data = vec![];
+    snapshot.write(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_snapshot_continue() {
+    let mut simulation = Simulation::new();
+    for _ in 0..6 {
+        simulation.perform_growth_iteration();
+    }
+
+    // written snapshot reads back the same
+    let data = to_bytes(&simulation.snapshot());
+    let loaded = Snapshot::read(&mut data.clone().into_iter()).unwrap();
+    assert_eq!(to_bytes(&loaded), data);
+
+    // a loaded simulation grows exactly like the original
+    simulation.perform_growth_iteration();
+    let expected = to_bytes(&simulation.snapshot());
+
+    let mut simulation = Simulation::from_snapshot(loaded);
+    simulation.perform_growth_iteration();
+    assert_eq!(to_bytes(&simulation.snapshot()), expected);
+}
+
+#[test]
+fn test_snapshot_version() {
+    let data = "vegsim snapshot version{0}\n".bytes().collect::<Vec<u8>>();
+    assert!(matches!(
+        Snapshot::read(&mut data.into_iter()),
+        Err(super::SnapshotError::Version(0))
+    ));
+}
+
+#[test]
+fn test_snapshot_seed() {
+    let points = |simulation: &Simulation| -> Vec<String> {
+        simulation
+            .plant()
+            .collect_branchdata()
+            .iter()
+            .map(|data| format!("{}{}", data.start_point(), data.end_point()))
+            .collect()
+    };
+
+    // simulations with the same seed grow the same, even when growing side by side
+    let mut first = Simulation::with_seed(7);
+    let mut second = Simulation::with_seed(7);
+    for _ in 0..4 {
+        first.perform_growth_iteration();
+        second.perform_growth_iteration();
+    }
+    assert_eq!(points(&first), points(&second));
+    assert_eq!(first.snapshot().seed, 7);
+    assert_eq!(first.snapshot().random_draws, second.snapshot().random_draws);
+
+    let mut other = Simulation::with_seed(8);
+    for _ in 0..4 {
+        other.perform_growth_iteration();
+    }
+    assert_ne!(points(&first), points(&other));
+}
+
+#[test]
+fn test_snapshot_extra_buds() {
+    let mut profile = ParameterProfile::default();
+    for param in [
+        GeneticParameter::BudsPerNode(2.),
+        GeneticParameter::DivergenceAngle(FRAC_PI_2),
+        GeneticParameter::BranchingAngle(0.7),
+    ] {
+        profile.genetics.update_param(param);
+    }
+    let mut simulation = Simulation::with_profile(profile, 1);
+    for _ in 0..6 {
+        simulation.perform_growth_iteration();
+    }
+
+    // decussate buds are on opposite sides of the axis, the next node is turned a quarter
+    let root = simulation.plant().root();
+    let second = root.terminal_metamer().unwrap();
+    assert_eq!(root.extra_buds().len(), 1);
+    let sides = root.auxillary_direction() + root.extra_buds()[0].direction;
+    assert!(sides.cross(root.direction()).length() < 1e-4);
+    assert!((second.phyllotaxis_angle() - root.phyllotaxis_angle() - FRAC_PI_2).abs() < 1e-4);
+
+    // extra buds and their branches are written and read back
+    let data = to_bytes(&simulation.snapshot());
+    let loaded = Snapshot::read(&mut data.clone().into_iter()).unwrap();
+    assert_eq!(to_bytes(&loaded), data);
+    assert_eq!(loaded.root.total_metamers(), root.total_metamers());
+}
@@ -1,3 +1,6 @@
+use std::{fmt::{self, Display}, str::FromStr};
+
+use patfile::{pscan, pwrite};
 use patutil::{Vecf3, Color};
 
 use crate::util::meter_to_real_length;
@@ -15,14 +18,14 @@ pub struct SupportPole {
 }
 
 impl SupportPole {
-    pub fn new(mut length: f32, start_point: Vecf3, dir: Vecf3, visible: bool) -> Self {
+    pub fn new(mut length: f32, start_point: Vecf3, dir: Vecf3, visible: bool, id: u32) -> Self {
         length = meter_to_real_length(length);
         const WIDTH: f32 = 0.0005;
         Self {
             length,
             start_point,
             dir,
-            model: BranchData::new(start_point, start_point + (dir*length), WIDTH, WIDTH, Color::new(0, 255, 0, 255), 0),
+            model: BranchData::new(start_point, start_point + (dir*length), WIDTH, WIDTH, Color::new(0, 255, 0, 255), id),
             visible
         }
     }
@@ -40,6 +43,10 @@ impl SupportPole {
         self.dir
     }
 
+    pub fn model(&self) -> &BranchData {
+        &self.model
+    }
+
     pub fn model_mut(&mut self) -> &mut BranchData {
         &mut self.model
     }
@@ -53,3 +60,41 @@ impl SupportPole {
         self.model_mut().set_start_width(width);
     }
 }
+
+macro_rules! SUPPORTPOLE_STRING_FORMAT {
+    () => {
+        "supportpole length{} start{} dir{} visible{} model{}"
+    };
+}
+
+impl FromStr for SupportPole {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut length: f32 = 0.;
+        let mut start_point = Vecf3::default();
+        let mut dir = Vecf3::default();
+        let mut visible = false;
+        let mut model = BranchData::new(Vecf3::default(), Vecf3::default(), 0., 0., Color::default(), 0);
+
+        let mut it = s.bytes().into_iter();
+        pscan!(&mut it => SUPPORTPOLE_STRING_FORMAT!(), length, start_point, dir, visible, model)
+            .map_err(|_| ())?;
+
+        Ok(SupportPole { length, start_point, dir, model, visible })
+    }
+}
+
+impl Display for SupportPole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let writer: &mut dyn std::fmt::Write = f;
+        pwrite!(SUPPORTPOLE_STRING_FORMAT!() => writer,
+            self.length,
+            self.start_point,
+            self.dir,
+            self.visible,
+            &self.model
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
@@ -0,0 +1,245 @@
+mod tests;
+
+use std::fmt::{self, Display};
+
+use super::{pruning_module::PruneOperation, snapshot::Snapshot};
+
+// states kept in a timeline, every state holds a copy of the whole plant
+pub const MAX_TIMELINE_STATES: usize = 100;
+
+/**
+ * Change made to the simulation between two states of the timeline
+ */
+#[derive(Debug, Clone)]
+pub enum TimelineAction {
+    Start,
+    Grow,
+    Prune(PruneOperation),
+    PruneId(u32),
+    // metamer id and fraction of its length
+    Cut(u32, f32),
+    PruneRules(String),
+    // name of the volume file
+    Hedge(String),
+}
+
+impl Display for TimelineAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimelineAction::Start => write!(f, "start"),
+            TimelineAction::Grow => write!(f, "grow"),
+            TimelineAction::Prune(op) => write!(f, "prune {}", op),
+            TimelineAction::PruneId(id) => write!(f, "prune metamer {}", id),
+            TimelineAction::Cut(id, fraction) => write!(f, "cut metamer {} at {:.2}", id, fraction),
+            TimelineAction::PruneRules(name) => write!(f, "prune by rules {}", name),
+            TimelineAction::Hedge(name) => write!(f, "trim to volume {}", name),
+        }
+    }
+}
+
+struct TimelineState {
+    parent: Option<usize>,
+    action: TimelineAction,
+    snapshot: Snapshot,
+}
+
+/**
+ * Last state of a branch in the timeline, used to switch between alternatives
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineBranch {
+    pub id: usize,
+    pub label: String,
+}
+
+impl Display for TimelineBranch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/**
+ * Tree of simulation states, every action adds a state after the current one.
+ * Acting on an earlier state starts a new branch and keeps the old one.
+ * When the timeline is full the oldest states are dropped.
+ */
+pub struct Timeline {
+    states: Vec<TimelineState>,
+    max_states: usize,
+    // state shown at the moment
+    current: usize,
+    // last state of the branch the current state is on
+    head: usize,
+}
+
+impl Timeline {
+    pub fn new(snapshot: Snapshot) -> Self {
+        Self::with_max_states(snapshot, MAX_TIMELINE_STATES)
+    }
+
+    // @param max_states at least 2, the start and the state after it
+    pub fn with_max_states(snapshot: Snapshot, max_states: usize) -> Self {
+        Self {
+            states: vec![TimelineState {
+                parent: None,
+                action: TimelineAction::Start,
+                snapshot,
+            }],
+            max_states: max_states.max(2),
+            current: 0,
+            head: 0,
+        }
+    }
+
+    // add the state after an action, later states of the current branch are kept in their own branch
+    pub fn record(&mut self, action: TimelineAction, snapshot: Snapshot) {
+        self.states.push(TimelineState {
+            parent: Some(self.current),
+            action,
+            snapshot,
+        });
+        self.current = self.states.len() - 1;
+        self.head = self.current;
+
+        while self.states.len() > self.max_states {
+            self.drop_oldest();
+        }
+    }
+
+    /**
+     * Drop the oldest state that can go without cutting the current branch in two:
+     * the end of another branch or the start when a single state follows it
+     */
+    fn drop_oldest(&mut self) {
+        let path = self.path();
+        let children = |id: usize| self.states.iter().filter(|state| state.parent == Some(id)).count();
+        let id = (0..self.states.len()).find(|&id| match self.states[id].parent {
+            None => children(id) == 1,
+            Some(_) => children(id) == 0 && !path.contains(&id),
+        });
+        let id = match id {
+            Some(id) => id,
+            None => return,
+        };
+
+        self.states.remove(id);
+        for state in &mut self.states {
+            state.parent = match state.parent {
+                Some(parent) if parent == id => None,
+                Some(parent) if parent > id => Some(parent - 1),
+                parent => parent,
+            };
+        }
+        if self.current > id {
+            self.current -= 1;
+        }
+        if self.head > id {
+            self.head -= 1;
+        }
+    }
+
+    pub fn current(&self) -> &Snapshot {
+        &self.states[self.current].snapshot
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.position() > 0
+    }
+    pub fn can_redo(&self) -> bool {
+        self.position() + 1 < self.steps()
+    }
+
+    // @returns the state before the last action, if any
+    pub fn undo(&mut self) -> Option<&Snapshot> {
+        let position = self.position().checked_sub(1)?;
+        self.scrub(position)
+    }
+
+    // @returns the state after the action that was undone last, if any
+    pub fn redo(&mut self) -> Option<&Snapshot> {
+        self.scrub(self.position() + 1)
+    }
+
+    // move to a position on the current branch, 0 is the start
+    pub fn scrub(&mut self, position: usize) -> Option<&Snapshot> {
+        let id = *self.path().get(position)?;
+        self.current = id;
+        Some(&self.states[id].snapshot)
+    }
+
+    // position of the current state on its branch
+    pub fn position(&self) -> usize {
+        self.path().iter().position(|&id| id == self.current).unwrap_or(0)
+    }
+
+    // amount of states on the current branch
+    pub fn steps(&self) -> usize {
+        self.path().len()
+    }
+
+    // action that led to the current state
+    pub fn current_action(&self) -> &TimelineAction {
+        &self.states[self.current].action
+    }
+
+    // every branch, the oldest first
+    pub fn branches(&self) -> Vec<TimelineBranch> {
+        let mut branches = vec![];
+        for id in 0..self.states.len() {
+            if self.states.iter().any(|state| state.parent == Some(id)) {
+                continue;
+            }
+
+            let path = self.path_to(id);
+            let prunes = path
+                .iter()
+                .filter(|&&id| {
+                    matches!(
+                        self.states[id].action,
+                        TimelineAction::Prune(_)
+                            | TimelineAction::PruneId(_)
+                            | TimelineAction::Cut(..)
+                            | TimelineAction::PruneRules(_)
+                            | TimelineAction::Hedge(_)
+                    )
+                })
+                .count();
+            let label = format!(
+                "Branch {}: iteration {}, {} prunes",
+                branches.len() + 1,
+                self.states[id].snapshot.growth_iteration,
+                prunes
+            );
+            branches.push(TimelineBranch { id, label });
+        }
+        branches
+    }
+
+    pub fn current_branch(&self) -> Option<TimelineBranch> {
+        self.branches().into_iter().find(|branch| branch.id == self.head)
+    }
+
+    // @returns the last state of the branch
+    pub fn switch_branch(&mut self, branch: &TimelineBranch) -> Option<&Snapshot> {
+        let state = self.states.get(branch.id)?;
+        self.head = branch.id;
+        self.current = branch.id;
+        Some(&state.snapshot)
+    }
+
+    fn path(&self) -> Vec<usize> {
+        self.path_to(self.head)
+    }
+
+    // ids of all states from the start to the given state
+    fn path_to(&self, id: usize) -> Vec<usize> {
+        let mut path = vec![id];
+        let mut id = id;
+        while let Some(parent) = self.states[id].parent {
+            path.push(parent);
+            id = parent;
+        }
+        path.reverse();
+        path
+    }
+}
@@ -0,0 +1,120 @@
+#![cfg(test)]
+
+use crate::tree::{pruning_module::PruneOperation, snapshot::Snapshot, Simulation};
+
+use super::{Timeline, TimelineAction};
+
+fn to_bytes(snapshot: &Snapshot) -> Vec<u8> {
+    let mut data = vec![];
+    snapshot.write(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_timeline_undo_redo() {
+    let mut simulation = Simulation::with_seed(3);
+    let mut timeline = Timeline::new(simulation.snapshot());
+    for _ in 0..4 {
+        simulation.perform_growth_iteration();
+        timeline.record(TimelineAction::Grow, simulation.snapshot());
+    }
+    let grown = to_bytes(&simulation.snapshot());
+    assert_eq!(timeline.steps(), 5);
+    assert!(!timeline.can_redo());
+
+    // an undone growth iteration grows the same again
+    simulation.restore(timeline.undo().unwrap().clone());
+    assert_eq!(simulation.growth_iteration(), 3);
+    simulation.perform_growth_iteration();
+    assert_eq!(to_bytes(&simulation.snapshot()), grown);
+
+    // redo and scrub stay on the same branch
+    assert_eq!(to_bytes(timeline.redo().unwrap()), grown);
+    assert_eq!(timeline.scrub(0).unwrap().growth_iteration, 0);
+    assert!(!timeline.can_undo());
+    assert!(timeline.scrub(5).is_none());
+    assert_eq!(timeline.steps(), 5);
+}
+
+#[test]
+fn test_timeline_branches() {
+    let mut simulation = Simulation::with_seed(3);
+    let mut timeline = Timeline::new(simulation.snapshot());
+    for _ in 0..3 {
+        simulation.perform_growth_iteration();
+        timeline.record(TimelineAction::Grow, simulation.snapshot());
+    }
+
+    // prune at iteration 2 instead of growing further
+    simulation.restore(timeline.scrub(2).unwrap().clone());
+    simulation.prune_by_rule(PruneOperation::Op1);
+    timeline.record(TimelineAction::Prune(PruneOperation::Op1), simulation.snapshot());
+    let pruned = to_bytes(&simulation.snapshot());
+
+    let branches = timeline.branches();
+    assert_eq!(branches.len(), 2);
+    assert_eq!(branches[0].label, "Branch 1: iteration 3, 0 prunes");
+    assert_eq!(branches[1].label, "Branch 2: iteration 2, 1 prunes");
+    assert_eq!(timeline.current_branch(), Some(branches[1].clone()));
+
+    // both alternatives can still be reached
+    assert_eq!(timeline.switch_branch(&branches[0]).unwrap().growth_iteration, 3);
+    assert_eq!(timeline.steps(), 4);
+    assert_eq!(to_bytes(timeline.switch_branch(&branches[1]).unwrap()), pruned);
+}
+
+#[test]
+fn test_timeline_cut() {
+    let mut simulation = Simulation::with_seed(3);
+    let mut timeline = Timeline::new(simulation.snapshot());
+    for _ in 0..4 {
+        simulation.perform_growth_iteration();
+        timeline.record(TimelineAction::Grow, simulation.snapshot());
+    }
+
+    // a cut shortens the metamer, removes everything above it and moves its buds to the cut
+    let metamer = simulation.plant().root().terminal_metamer().unwrap().clone();
+    assert!(simulation.cut_id(metamer.id(), 0.5));
+    timeline.record(TimelineAction::Cut(metamer.id(), 0.5), simulation.snapshot());
+    let cut = simulation.get_metamer_by_id(metamer.id()).unwrap();
+    assert!((cut.length() - metamer.length() * 0.5).abs() < 1.0e-4);
+    assert!(cut.terminal_metamer().is_none() && cut.auxillary_metamer().is_none());
+    assert!((cut.terminal_bud_data.start_point() - cut.end_point()).length() < 1.0e-4);
+    assert_eq!(cut.terminal_bud_damage, 0.);
+    assert!(cut.end_width() <= metamer.end_width().max(metamer.start_width()));
+    assert!(!simulation.cut_id(u32::MAX, 0.5));
+
+    assert_eq!(timeline.branches()[0].label, "Branch 1: iteration 4, 1 prunes");
+    let data = to_bytes(&simulation.snapshot());
+    simulation.restore(timeline.undo().unwrap().clone());
+    assert_eq!(to_bytes(timeline.redo().unwrap()), data);
+}
+
+#[test]
+fn test_timeline_max_states() {
+    let mut simulation = Simulation::with_seed(3);
+    let mut timeline = Timeline::with_max_states(simulation.snapshot(), 4);
+    for _ in 0..3 {
+        simulation.perform_growth_iteration();
+        timeline.record(TimelineAction::Grow, simulation.snapshot());
+    }
+
+    // the start goes first
+    simulation.restore(timeline.scrub(1).unwrap().clone());
+    simulation.prune_by_rule(PruneOperation::Op1);
+    timeline.record(TimelineAction::Prune(PruneOperation::Op1), simulation.snapshot());
+    let pruned = to_bytes(&simulation.snapshot());
+    assert_eq!(timeline.branches().len(), 2);
+    assert_eq!(timeline.steps(), 2);
+
+    // then the old branch, the current branch stays whole
+    for _ in 0..2 {
+        simulation.perform_growth_iteration();
+        timeline.record(TimelineAction::Grow, simulation.snapshot());
+    }
+    assert_eq!(timeline.branches().len(), 1);
+    assert_eq!(timeline.steps(), 4);
+    assert_eq!(timeline.scrub(0).unwrap().growth_iteration, 1);
+    assert_eq!(to_bytes(timeline.scrub(1).unwrap()), pruned);
+    assert_eq!(timeline.redo().unwrap().growth_iteration, 2);
+}
@@ -0,0 +1,71 @@
+/*
+ * Tropisms bend a growing shoot towards gravity and light.
+ * Gravitropism pulls a shoot to its setpoint angle from the vertical: the trunk is usually orthotropic (0, up),
+ * branches plagiotropic (PI/2, horizontal) and angles above PI/2 let branches hang down.
+ * Phototropism pulls a shoot towards the direction most of the light comes from.
+ * The trunk and the first order branches have their own weights, every further order
+ * multiplies the weights of the order before by the tropism order factor.
+ */
+mod tests;
+
+use patutil::Vecf3;
+
+use super::{environment::Environment, plantgenetics::PlantGenetics};
+
+pub struct Tropism {
+    gravitropism: f32,
+    // setpoint angle from the vertical
+    setpoint: f32,
+    phototropism: f32,
+}
+
+impl Tropism {
+    // @param order branch order of the shoot, 0 for the trunk
+    pub fn new(genetics: &PlantGenetics, order: u32) -> Self {
+        if order == 0 {
+            return Self {
+                gravitropism: genetics.gravitropism(),
+                setpoint: genetics.gravitropism_angle(),
+                phototropism: genetics.phototropism(),
+            };
+        }
+
+        let factor = genetics.tropism_order_factor().powi(order as i32 - 1);
+        Self {
+            gravitropism: genetics.lateral_gravitropism() * factor,
+            setpoint: genetics.lateral_gravitropism_angle(),
+            phototropism: genetics.lateral_phototropism() * factor,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.gravitropism > 0. || self.phototropism > 0.
+    }
+
+    // direction at the setpoint angle from the vertical on the side the shoot grows to
+    pub fn gravitropic_direction(&self, direction: Vecf3) -> Vecf3 {
+        let mut side = Vecf3::new(direction.x, 0., direction.z);
+        if side.length() < 1.0e-4 {
+            // growing straight up or down, no side to bend to
+            side = Vecf3::new(0., 0., 0.);
+        } else {
+            side = side.norm();
+        }
+        side * self.setpoint.sin() + Vecf3::new(0., 1., 0.) * self.setpoint.cos()
+    }
+
+    /**
+     * Pull of gravity and light on a shoot growing from the position in the direction
+     * @returns the vector to add to the direction of the shoot
+     */
+    pub fn response(&self, environment: &Environment, genetics: &PlantGenetics, position: Vecf3, direction: Vecf3) -> Vecf3 {
+        let mut response = Vecf3::new(0., 0., 0.);
+        if self.gravitropism > 0. {
+            response += self.gravitropic_direction(direction) * self.gravitropism;
+        }
+        if self.phototropism > 0. {
+            response += environment.light_direction(position, genetics) * self.phototropism;
+        }
+        response
+    }
+}
@@ -0,0 +1,112 @@
+#![cfg(test)]
+
+use std::f32::consts::PI;
+
+use patutil::Vecf3;
+
+use crate::{
+    profile::ParameterProfile,
+    tree::{environment::Environment, metamer::Metamer, plantgenetics::PlantGenetics, Simulation},
+    treeparameter::{GeneticParameter, SpaceDividingMode},
+    util::random::Random,
+};
+
+use super::Tropism;
+
+// upward part of the directions of all branch metamers, the trunk is left out
+fn branch_rise(metamer: &Metamer, order: u32, rise: &mut Vec<f32>) {
+    if order > 0 {
+        rise.push(metamer.direction().y);
+    }
+    if let Some(m) = metamer.terminal_metamer() {
+        branch_rise(m, order, rise);
+    }
+    if let Some(m) = metamer.auxillary_metamer() {
+        branch_rise(m, order + 1, rise);
+    }
+    for m in metamer.extra_buds().iter().filter_map(|bud| bud.metamer()) {
+        branch_rise(m, order + 1, rise);
+    }
+}
+
+fn average_branch_rise(angle: f32) -> f32 {
+    let mut profile = ParameterProfile::default();
+    for param in [
+        GeneticParameter::LateralGravitropism(2.),
+        GeneticParameter::LateralGravitropismAngle(angle),
+    ] {
+        profile.genetics.update_param(param);
+    }
+    let mut simulation = Simulation::with_profile(profile, 5);
+    for _ in 0..10 {
+        simulation.perform_growth_iteration();
+    }
+
+    let mut rise = vec![];
+    branch_rise(simulation.plant().root(), 0, &mut rise);
+    assert!(!rise.is_empty());
+    rise.iter().sum::<f32>() / rise.len() as f32
+}
+
+#[test]
+fn test_gravitropic_direction() {
+    let mut genetics = PlantGenetics::new();
+    genetics.update_param(GeneticParameter::LateralGravitropism(1.));
+    genetics.update_param(GeneticParameter::TropismOrderFactor(0.5));
+
+    genetics.update_param(GeneticParameter::LateralGravitropismAngle(PI / 2.));
+    let plagiotropic = Tropism::new(&genetics, 1);
+    let dir = plagiotropic.gravitropic_direction(Vecf3::new(1., 1., 0.).norm());
+    assert!((dir - Vecf3::new(1., 0., 0.)).length() < 1.0e-4);
+
+    genetics.update_param(GeneticParameter::LateralGravitropismAngle(PI));
+    let pendulous = Tropism::new(&genetics, 1);
+    let dir = pendulous.gravitropic_direction(Vecf3::new(0., 0., 1.));
+    assert!((dir - Vecf3::new(0., -1., 0.)).length() < 1.0e-4);
+
+    // the trunk is off by default, higher orders get weaker
+    assert!(!Tropism::new(&genetics, 0).enabled());
+    assert_eq!(Tropism::new(&genetics, 3).gravitropism, 0.25);
+}
+
+#[test]
+fn test_plagiotropic_branches() {
+    let orthotropic = average_branch_rise(0.);
+    let plagiotropic = average_branch_rise(PI / 2.);
+    assert!(orthotropic > plagiotropic + 0.2, "{} {}", orthotropic, plagiotropic);
+    assert!(plagiotropic.abs() < 0.3, "{}", plagiotropic);
+}
+
+#[test]
+fn test_phototropic_direction() {
+    let mut genetics = PlantGenetics::new();
+    genetics.update_param(GeneticParameter::Phototropism(1.));
+    let tropism = Tropism::new(&genetics, 0);
+    assert!(tropism.enabled());
+
+    let up = Vecf3::new(0., 1., 0.);
+    let pos = Vecf3::new(0., 10., 25.);
+    let shade = pos - Vecf3::new(genetics.bud_perception_radius_factor(), 0., 0.);
+    for mode in [SpaceDividingMode::ShadowVoxels, SpaceDividingMode::Markers] {
+        let mut profile = ParameterProfile::default();
+        profile.environment.space_div_mode = mode;
+        let mut environment = Environment::new(&profile.environment, &mut Random::new(3));
+        let even = tropism.response(&environment, &genetics, pos, up);
+
+        // shade one side, the shoot bends away from it and the pull grows
+        match mode {
+            SpaceDividingMode::ShadowVoxels => environment.shadowvoxels_mut().add_shade(shade, 10.),
+            _ => environment.markers_mut().remove_markers_in_sphere(shade, 0.8),
+        }
+        let response = tropism.response(&environment, &genetics, pos, up);
+        let pull = response - even;
+        assert!(pull.x > 0.05 && pull.x > pull.y.abs() && pull.x > pull.z.abs(), "{:?} {:?}", mode, pull);
+        assert!(response.length() <= 1.);
+    }
+
+    // even light does not pull
+    let mut profile = ParameterProfile::default();
+    profile.environment.space_div_mode = SpaceDividingMode::ShadowVoxels;
+    let environment = Environment::new(&profile.environment, &mut Random::new(3));
+    assert!(tropism.response(&environment, &genetics, pos, up).length() < 1.0e-4);
+}
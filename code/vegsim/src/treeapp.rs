@@ -0,0 +1,299 @@
+use std::{path::Path, sync::Mutex};
+
+use patutil::{mesh, points, Color, Vecf3};
+use vegsim::{
+    export::{
+        gltf::export_glb,
+        obj::{export_obj, ObjOptions},
+        skeleton::{import_skeleton, skeleton_to_metamer, SkeletonError},
+    },
+    tree::{
+        calendar::SeasonHistory,
+        flowering::FruitHistory,
+        metamer::Metamer,
+        plantgenetics::PlantGenetics,
+        pruning_module::{hedge::PruningVolume, history::PruningHistory, rules::PruningProtocol, PruneOperation},
+        snapshot::{Snapshot, SnapshotError},
+        timeline::{Timeline, TimelineAction, TimelineBranch},
+        Simulation,
+    },
+    parameters,
+    profile::{ParameterProfile, ProfileError},
+    treeparameter::TreeParameter,
+    util::closest_fraction_to_ray,
+};
+
+use crate::treerender::TreeRender;
+
+pub struct TreeApp {
+    simulation: Simulation,
+    timeline: Timeline,
+    tree_render: TreeRender,
+    pointslist: points::PointsListRef,
+    selected_id: Option<u32>,
+}
+
+impl TreeApp {
+    pub fn new(scene: mesh::SceneRef, mut point_scene: points::SceneRef, profile: ParameterProfile) -> Self {
+        // create simulation
+        let simulation = Simulation::with_profile(profile, parameters::SEED);
+
+        // create render for 3D models
+        let tree_render = TreeRender::new(scene.clone());
+
+        // create point list for marker debugging
+        let pointslist: points::PointsListRef = glrender::GLPointsList::new(vec![]).into();
+        point_scene.controller().add_list(pointslist.clone());
+
+        let mut this = Self {
+            timeline: Timeline::new(simulation.snapshot()),
+            simulation,
+            tree_render,
+            pointslist,
+            selected_id: None,
+        };
+        this.update_draw();
+        this.update_markers();
+        this
+    }
+
+    pub fn perform_growth_iteration(&mut self) {
+        self.simulation.perform_growth_iteration();
+        self.timeline.record(TimelineAction::Grow, self.simulation.snapshot());
+
+        // update draw data
+        self.update_draw();
+
+        // update debug marker points
+        self.update_markers();
+    }
+
+    fn update_draw(&mut self) {
+        self.tree_render.sync(self.simulation.plant(), self.selected_id);
+    }
+
+    fn update_markers(&mut self) {
+        self.simulation.update_markers();
+        // update marker point draw
+        let mut points = vec![];
+        for marker in self.simulation.environment().markers().get_all_marked_points() {
+            let pos = marker.position;
+            // println!("Pos:{:?} bud: {}",p, bud_id);
+            let color = Color::new(255, 0, 0, 80);
+            // let color = Color::new(((bud_id)%255) as u8, ((bud_id/255)%255) as u8, ((bud_id/(255*255))%255) as u8, 50);
+            points.push(points::Point {
+                pos,
+                color,
+                size: 4.,
+            });
+        }
+        self.pointslist.lock().update_points(points);
+    }
+
+    pub fn debug_texture(&self, index: u32) -> Vec<Color> {
+        return self.simulation.environment().shadowvoxels().debug_texture(index);
+    }
+
+    pub fn set_selected_id(&mut self, id: Option<u32>) {
+        self.selected_id = id;
+        self.update_draw();
+    }
+
+    pub fn prune_id(&mut self, id: u32) {
+        self.simulation.prune_id(id);
+        self.timeline.record(TimelineAction::PruneId(id), self.simulation.snapshot());
+        self.update_draw();
+    }
+
+    // cut the metamer at the point closest to a ray, e.g. the picking ray through the cursor
+    pub fn cut_at_ray(&mut self, id: u32, origin: Vecf3, dir: Vecf3) {
+        let metamer = match self.simulation.get_metamer_by_id(id) {
+            Some(metamer) => metamer,
+            None => return,
+        };
+        let fraction = closest_fraction_to_ray(metamer.start_point(), metamer.end_point(), origin, dir);
+        if self.simulation.cut_id(id, fraction) {
+            self.timeline.record(TimelineAction::Cut(id, fraction), self.simulation.snapshot());
+            self.update_draw();
+        }
+    }
+
+    pub fn get_metamer_by_id(&self, id: u32) -> Option<Metamer> {
+        self.simulation.get_metamer_by_id(id)
+    }
+
+    pub fn prune_by_rule(&mut self, rule_index: PruneOperation) {
+        self.simulation.prune_by_rule(rule_index.clone());
+        self.timeline.record(TimelineAction::Prune(rule_index), self.simulation.snapshot());
+        self.update_draw();
+    }
+
+    pub fn prune_by_protocol(&mut self, protocol: &PruningProtocol) {
+        self.simulation.prune_by_protocol(protocol);
+        self.timeline.record(TimelineAction::PruneRules(protocol.name.clone()), self.simulation.snapshot());
+        self.update_draw();
+    }
+
+    pub fn trim_to_volume(&mut self, name: &str, volume: &PruningVolume) {
+        self.simulation.trim_to_volume(volume);
+        self.timeline.record(TimelineAction::Hedge(name.to_string()), self.simulation.snapshot());
+        self.update_draw();
+    }
+
+    pub fn reset_plants(&mut self) {
+        self.simulation.reset();
+        self.restart_timeline();
+
+        self.update_draw();
+        self.update_markers();
+    }
+
+    pub fn recalculate_plants(&mut self) {
+        self.simulation.recalculate();
+        self.restart_timeline();
+
+        // update draw data
+        self.update_draw();
+
+        // update marker point draw
+        self.update_markers();
+    }
+
+    pub fn save_snapshot(&self, path: &Path) -> Result<(), SnapshotError> {
+        self.simulation.snapshot().save(path)
+    }
+
+    // replace the simulation with the one stored in a snapshot file
+    pub fn load_snapshot(&mut self, path: &Path) -> Result<(), SnapshotError> {
+        let snapshot = Snapshot::load(path)?;
+        self.simulation = Simulation::from_snapshot(snapshot);
+        self.selected_id = None;
+        self.restart_timeline();
+
+        self.update_draw();
+        self.update_markers();
+        Ok(())
+    }
+
+    pub fn import_skeleton(&mut self, path: &Path) -> Result<(), SkeletonError> {
+        let nodes = import_skeleton(path)?;
+        let genetics = self.simulation.shared_plant_genetics();
+        let root = skeleton_to_metamer(&nodes, genetics, self.simulation.plant_mut().ids_mut())?;
+        self.simulation.import_plant(root);
+        self.selected_id = None;
+        self.restart_timeline();
+
+        self.update_draw();
+        self.update_markers();
+        Ok(())
+    }
+
+    pub fn profile_name(&self) -> &str {
+        &self.simulation.profile().name
+    }
+
+    // save the parameters the plant grows with now as a profile with the given name
+    pub fn save_profile(&self, name: &str, path: &Path) -> Result<(), ProfileError> {
+        self.simulation.current_profile(name).save(path)
+    }
+
+    // regrow the plant with the parameters of another profile
+    pub fn apply_profile(&mut self, profile: ParameterProfile) {
+        self.simulation.apply_profile(profile);
+        self.selected_id = None;
+        self.restart_timeline();
+
+        self.update_draw();
+        self.update_markers();
+    }
+
+    // the current state becomes the start of a new timeline
+    fn restart_timeline(&mut self) {
+        self.timeline = Timeline::new(self.simulation.snapshot());
+    }
+
+    pub fn timeline(&self) -> &Timeline {
+        &self.timeline
+    }
+
+    pub fn pruning_history(&self) -> &PruningHistory {
+        self.simulation.pruning_history()
+    }
+
+    pub fn fruit_history(&self) -> &FruitHistory {
+        self.simulation.fruit_history()
+    }
+
+    pub fn season_history(&self) -> &SeasonHistory {
+        self.simulation.season_history()
+    }
+
+    pub fn undo(&mut self) {
+        let snapshot = self.timeline.undo().cloned();
+        self.restore(snapshot);
+    }
+
+    pub fn redo(&mut self) {
+        let snapshot = self.timeline.redo().cloned();
+        self.restore(snapshot);
+    }
+
+    // show the state at a position of the current timeline branch
+    pub fn scrub(&mut self, position: usize) {
+        if position == self.timeline.position() {
+            return;
+        }
+        let snapshot = self.timeline.scrub(position).cloned();
+        self.restore(snapshot);
+    }
+
+    pub fn switch_branch(&mut self, branch: &TimelineBranch) {
+        let snapshot = self.timeline.switch_branch(branch).cloned();
+        self.restore(snapshot);
+    }
+
+    fn restore(&mut self, snapshot: Option<Snapshot>) {
+        if let Some(snapshot) = snapshot {
+            self.simulation.restore(snapshot);
+            self.selected_id = None;
+
+            self.update_draw();
+            self.update_markers();
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.simulation.seed()
+    }
+
+    // regrow the plant from another seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.simulation.set_seed(seed);
+        self.restart_timeline();
+
+        self.update_draw();
+        self.update_markers();
+    }
+
+    pub fn export_obj(&self, path: &Path) -> std::io::Result<()> {
+        export_obj(self.simulation.plant(), path, &ObjOptions::default())
+    }
+
+    pub fn export_glb(&self, path: &Path) -> std::io::Result<()> {
+        export_glb(self.simulation.plant(), path, ObjOptions::default().sides)
+    }
+
+    pub fn plant_genetics(&self) -> &Mutex<PlantGenetics> {
+        self.simulation.plant_genetics()
+    }
+
+    pub fn update_tree_param(&mut self, param: TreeParameter) {
+        self.simulation.update_tree_param(param);
+    }
+    pub fn reset_tree_params(&mut self) {
+        self.simulation.reset_tree_params();
+    }
+    pub fn get_tree_param(&self, param: TreeParameter) -> TreeParameter{
+        self.simulation.get_tree_param(param)
+    }
+}
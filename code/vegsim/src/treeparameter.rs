@@ -1,3 +1,7 @@
+use std::f32::consts::PI;
+
+use crate::tree::{calendar::Calendar, pruning_module::strategy::AutoPruning};
+
 
 
 
@@ -15,6 +19,19 @@ impl std::fmt::Display for DistributionMode {
     }
 }
 
+impl std::str::FromStr for DistributionMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "BorchertHonda" => Ok(DistributionMode::BorchertHonda),
+            "PriorityList" => Ok(DistributionMode::PriorityList),
+            "None" => Ok(DistributionMode::None),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SpaceDividingMode {
     Markers,
@@ -28,12 +45,332 @@ impl std::fmt::Display for SpaceDividingMode {
     }
 }
 
+impl std::str::FromStr for SpaceDividingMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Markers" => Ok(SpaceDividingMode::Markers),
+            "ShadowVoxels" => Ok(SpaceDividingMode::ShadowVoxels),
+            "None" => Ok(SpaceDividingMode::None),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum GeneticParameter{
     BorchertHondaLambda(f32),
     BorchertHondaAlpha(f32),
     PoleLength(f32),
-    AuxShootReq(f32)
+    AuxShootReq(f32),
+    TermShootReq(f32),
+    MetamerBaseLength(f32),
+    BudPerceptionAngle(f32),
+    BudPerceptionRadiusFactor(f32),
+    OccupancyRadiusFactor(f32),
+    AxillaryPerturbationAngle(f32),
+    OptimalGrowthDirectionWeight(f32),
+    ShedTreshhold(f32),
+    WidthGrowExponent(f32),
+    WidthMinValue(f32),
+    BudRecoverySpeed(f32),
+    BudDormancyAge(f32),
+    BudReleaseDistance(f32),
+    BudReleaseLight(f32),
+    BudLifespan(f32),
+    AuxinProduction(f32),
+    AuxinDecay(f32),
+    AuxinThreshold(f32),
+    BudsPerNode(f32),
+    DivergenceAngle(f32),
+    BranchingAngle(f32),
+    LeafSize(f32),
+    LeafAngle(f32),
+    LeafLifespan(f32),
+    LeafAbscissionLight(f32),
+    LeafEfficiency(f32),
+    SpurLength(f32),
+    FlowerAge(f32),
+    FlowerLight(f32),
+    FruitSink(f32),
+    FruitSetRequirement(f32),
+    FruitGrowth(f32),
+    FruitMaturity(f32),
+    Gravitropism(f32),
+    GravitropismAngle(f32),
+    LateralGravitropism(f32),
+    LateralGravitropismAngle(f32),
+    Phototropism(f32),
+    LateralPhototropism(f32),
+    TropismOrderFactor(f32),
+    WoodStiffness(f32),
+    WoodDensity(f32),
+    LeafMass(f32),
+}
+
+impl GeneticParameter {
+    // every genetic parameter, values are ignored
+    pub const ALL: [GeneticParameter; 47] = [
+        GeneticParameter::BorchertHondaLambda(0.),
+        GeneticParameter::BorchertHondaAlpha(0.),
+        GeneticParameter::PoleLength(0.),
+        GeneticParameter::AuxShootReq(0.),
+        GeneticParameter::TermShootReq(0.),
+        GeneticParameter::MetamerBaseLength(0.),
+        GeneticParameter::BudPerceptionAngle(0.),
+        GeneticParameter::BudPerceptionRadiusFactor(0.),
+        GeneticParameter::OccupancyRadiusFactor(0.),
+        GeneticParameter::AxillaryPerturbationAngle(0.),
+        GeneticParameter::OptimalGrowthDirectionWeight(0.),
+        GeneticParameter::ShedTreshhold(0.),
+        GeneticParameter::WidthGrowExponent(0.),
+        GeneticParameter::WidthMinValue(0.),
+        GeneticParameter::BudRecoverySpeed(0.),
+        GeneticParameter::BudDormancyAge(0.),
+        GeneticParameter::BudReleaseDistance(0.),
+        GeneticParameter::BudReleaseLight(0.),
+        GeneticParameter::BudLifespan(0.),
+        GeneticParameter::AuxinProduction(0.),
+        GeneticParameter::AuxinDecay(0.),
+        GeneticParameter::AuxinThreshold(0.),
+        GeneticParameter::BudsPerNode(0.),
+        GeneticParameter::DivergenceAngle(0.),
+        GeneticParameter::BranchingAngle(0.),
+        GeneticParameter::LeafSize(0.),
+        GeneticParameter::LeafAngle(0.),
+        GeneticParameter::LeafLifespan(0.),
+        GeneticParameter::LeafAbscissionLight(0.),
+        GeneticParameter::LeafEfficiency(0.),
+        GeneticParameter::SpurLength(0.),
+        GeneticParameter::FlowerAge(0.),
+        GeneticParameter::FlowerLight(0.),
+        GeneticParameter::FruitSink(0.),
+        GeneticParameter::FruitSetRequirement(0.),
+        GeneticParameter::FruitGrowth(0.),
+        GeneticParameter::FruitMaturity(0.),
+        GeneticParameter::Gravitropism(0.),
+        GeneticParameter::GravitropismAngle(0.),
+        GeneticParameter::LateralGravitropism(0.),
+        GeneticParameter::LateralGravitropismAngle(0.),
+        GeneticParameter::Phototropism(0.),
+        GeneticParameter::LateralPhototropism(0.),
+        GeneticParameter::TropismOrderFactor(0.),
+        GeneticParameter::WoodStiffness(0.),
+        GeneticParameter::WoodDensity(0.),
+        GeneticParameter::LeafMass(0.),
+    ];
+
+    // name used as key in parameter profiles
+    pub fn name(&self) -> &'static str {
+        match self {
+            GeneticParameter::BorchertHondaLambda(_) => "borchert_honda_lambda",
+            GeneticParameter::BorchertHondaAlpha(_) => "borchert_honda_alpha",
+            GeneticParameter::PoleLength(_) => "pole_length",
+            GeneticParameter::AuxShootReq(_) => "aux_shoot_requirement",
+            GeneticParameter::TermShootReq(_) => "term_shoot_requirement",
+            GeneticParameter::MetamerBaseLength(_) => "metamer_base_length",
+            GeneticParameter::BudPerceptionAngle(_) => "bud_perception_angle",
+            GeneticParameter::BudPerceptionRadiusFactor(_) => "bud_perception_radius_factor",
+            GeneticParameter::OccupancyRadiusFactor(_) => "occupancy_radius_factor",
+            GeneticParameter::AxillaryPerturbationAngle(_) => "axillary_perturbation_angle",
+            GeneticParameter::OptimalGrowthDirectionWeight(_) => "optimal_growth_direction_weight",
+            GeneticParameter::ShedTreshhold(_) => "shed_treshhold",
+            GeneticParameter::WidthGrowExponent(_) => "width_grow_exponent",
+            GeneticParameter::WidthMinValue(_) => "width_min_value",
+            GeneticParameter::BudRecoverySpeed(_) => "bud_recovery_speed",
+            GeneticParameter::BudDormancyAge(_) => "bud_dormancy_age",
+            GeneticParameter::BudReleaseDistance(_) => "bud_release_distance",
+            GeneticParameter::BudReleaseLight(_) => "bud_release_light",
+            GeneticParameter::BudLifespan(_) => "bud_lifespan",
+            GeneticParameter::AuxinProduction(_) => "auxin_production",
+            GeneticParameter::AuxinDecay(_) => "auxin_decay",
+            GeneticParameter::AuxinThreshold(_) => "auxin_threshold",
+            GeneticParameter::BudsPerNode(_) => "buds_per_node",
+            GeneticParameter::DivergenceAngle(_) => "divergence_angle",
+            GeneticParameter::BranchingAngle(_) => "branching_angle",
+            GeneticParameter::LeafSize(_) => "leaf_size",
+            GeneticParameter::LeafAngle(_) => "leaf_angle",
+            GeneticParameter::LeafLifespan(_) => "leaf_lifespan",
+            GeneticParameter::LeafAbscissionLight(_) => "leaf_abscission_light",
+            GeneticParameter::LeafEfficiency(_) => "leaf_efficiency",
+            GeneticParameter::SpurLength(_) => "spur_length",
+            GeneticParameter::FlowerAge(_) => "flower_age",
+            GeneticParameter::FlowerLight(_) => "flower_light",
+            GeneticParameter::FruitSink(_) => "fruit_sink",
+            GeneticParameter::FruitSetRequirement(_) => "fruit_set_requirement",
+            GeneticParameter::FruitGrowth(_) => "fruit_growth",
+            GeneticParameter::FruitMaturity(_) => "fruit_maturity",
+            GeneticParameter::Gravitropism(_) => "gravitropism",
+            GeneticParameter::GravitropismAngle(_) => "gravitropism_angle",
+            GeneticParameter::LateralGravitropism(_) => "lateral_gravitropism",
+            GeneticParameter::LateralGravitropismAngle(_) => "lateral_gravitropism_angle",
+            GeneticParameter::Phototropism(_) => "phototropism",
+            GeneticParameter::LateralPhototropism(_) => "lateral_phototropism",
+            GeneticParameter::TropismOrderFactor(_) => "tropism_order_factor",
+            GeneticParameter::WoodStiffness(_) => "wood_stiffness",
+            GeneticParameter::WoodDensity(_) => "wood_density",
+            GeneticParameter::LeafMass(_) => "leaf_mass",
+        }
+    }
+
+    pub fn value(&self) -> f32 {
+        match *self {
+            GeneticParameter::BorchertHondaLambda(value)
+            | GeneticParameter::BorchertHondaAlpha(value)
+            | GeneticParameter::PoleLength(value)
+            | GeneticParameter::AuxShootReq(value)
+            | GeneticParameter::TermShootReq(value)
+            | GeneticParameter::MetamerBaseLength(value)
+            | GeneticParameter::BudPerceptionAngle(value)
+            | GeneticParameter::BudPerceptionRadiusFactor(value)
+            | GeneticParameter::OccupancyRadiusFactor(value)
+            | GeneticParameter::AxillaryPerturbationAngle(value)
+            | GeneticParameter::OptimalGrowthDirectionWeight(value)
+            | GeneticParameter::ShedTreshhold(value)
+            | GeneticParameter::WidthGrowExponent(value)
+            | GeneticParameter::WidthMinValue(value)
+            | GeneticParameter::BudRecoverySpeed(value)
+            | GeneticParameter::BudDormancyAge(value)
+            | GeneticParameter::BudReleaseDistance(value)
+            | GeneticParameter::BudReleaseLight(value)
+            | GeneticParameter::BudLifespan(value)
+            | GeneticParameter::AuxinProduction(value)
+            | GeneticParameter::AuxinDecay(value)
+            | GeneticParameter::AuxinThreshold(value)
+            | GeneticParameter::BudsPerNode(value)
+            | GeneticParameter::DivergenceAngle(value)
+            | GeneticParameter::BranchingAngle(value)
+            | GeneticParameter::LeafSize(value)
+            | GeneticParameter::LeafAngle(value)
+            | GeneticParameter::LeafLifespan(value)
+            | GeneticParameter::LeafAbscissionLight(value)
+            | GeneticParameter::LeafEfficiency(value)
+            | GeneticParameter::SpurLength(value)
+            | GeneticParameter::FlowerAge(value)
+            | GeneticParameter::FlowerLight(value)
+            | GeneticParameter::FruitSink(value)
+            | GeneticParameter::FruitSetRequirement(value)
+            | GeneticParameter::FruitGrowth(value)
+            | GeneticParameter::FruitMaturity(value)
+            | GeneticParameter::Gravitropism(value)
+            | GeneticParameter::GravitropismAngle(value)
+            | GeneticParameter::LateralGravitropism(value)
+            | GeneticParameter::LateralGravitropismAngle(value)
+            | GeneticParameter::Phototropism(value)
+            | GeneticParameter::LateralPhototropism(value)
+            | GeneticParameter::TropismOrderFactor(value)
+            | GeneticParameter::WoodStiffness(value)
+            | GeneticParameter::WoodDensity(value)
+            | GeneticParameter::LeafMass(value) => value,
+        }
+    }
+
+    // same parameter with another value
+    pub fn with_value(&self, value: f32) -> Self {
+        match self {
+            GeneticParameter::BorchertHondaLambda(_) => GeneticParameter::BorchertHondaLambda(value),
+            GeneticParameter::BorchertHondaAlpha(_) => GeneticParameter::BorchertHondaAlpha(value),
+            GeneticParameter::PoleLength(_) => GeneticParameter::PoleLength(value),
+            GeneticParameter::AuxShootReq(_) => GeneticParameter::AuxShootReq(value),
+            GeneticParameter::TermShootReq(_) => GeneticParameter::TermShootReq(value),
+            GeneticParameter::MetamerBaseLength(_) => GeneticParameter::MetamerBaseLength(value),
+            GeneticParameter::BudPerceptionAngle(_) => GeneticParameter::BudPerceptionAngle(value),
+            GeneticParameter::BudPerceptionRadiusFactor(_) => GeneticParameter::BudPerceptionRadiusFactor(value),
+            GeneticParameter::OccupancyRadiusFactor(_) => GeneticParameter::OccupancyRadiusFactor(value),
+            GeneticParameter::AxillaryPerturbationAngle(_) => GeneticParameter::AxillaryPerturbationAngle(value),
+            GeneticParameter::OptimalGrowthDirectionWeight(_) => GeneticParameter::OptimalGrowthDirectionWeight(value),
+            GeneticParameter::ShedTreshhold(_) => GeneticParameter::ShedTreshhold(value),
+            GeneticParameter::WidthGrowExponent(_) => GeneticParameter::WidthGrowExponent(value),
+            GeneticParameter::WidthMinValue(_) => GeneticParameter::WidthMinValue(value),
+            GeneticParameter::BudRecoverySpeed(_) => GeneticParameter::BudRecoverySpeed(value),
+            GeneticParameter::BudDormancyAge(_) => GeneticParameter::BudDormancyAge(value),
+            GeneticParameter::BudReleaseDistance(_) => GeneticParameter::BudReleaseDistance(value),
+            GeneticParameter::BudReleaseLight(_) => GeneticParameter::BudReleaseLight(value),
+            GeneticParameter::BudLifespan(_) => GeneticParameter::BudLifespan(value),
+            GeneticParameter::AuxinProduction(_) => GeneticParameter::AuxinProduction(value),
+            GeneticParameter::AuxinDecay(_) => GeneticParameter::AuxinDecay(value),
+            GeneticParameter::AuxinThreshold(_) => GeneticParameter::AuxinThreshold(value),
+            GeneticParameter::BudsPerNode(_) => GeneticParameter::BudsPerNode(value),
+            GeneticParameter::DivergenceAngle(_) => GeneticParameter::DivergenceAngle(value),
+            GeneticParameter::BranchingAngle(_) => GeneticParameter::BranchingAngle(value),
+            GeneticParameter::LeafSize(_) => GeneticParameter::LeafSize(value),
+            GeneticParameter::LeafAngle(_) => GeneticParameter::LeafAngle(value),
+            GeneticParameter::LeafLifespan(_) => GeneticParameter::LeafLifespan(value),
+            GeneticParameter::LeafAbscissionLight(_) => GeneticParameter::LeafAbscissionLight(value),
+            GeneticParameter::LeafEfficiency(_) => GeneticParameter::LeafEfficiency(value),
+            GeneticParameter::SpurLength(_) => GeneticParameter::SpurLength(value),
+            GeneticParameter::FlowerAge(_) => GeneticParameter::FlowerAge(value),
+            GeneticParameter::FlowerLight(_) => GeneticParameter::FlowerLight(value),
+            GeneticParameter::FruitSink(_) => GeneticParameter::FruitSink(value),
+            GeneticParameter::FruitSetRequirement(_) => GeneticParameter::FruitSetRequirement(value),
+            GeneticParameter::FruitGrowth(_) => GeneticParameter::FruitGrowth(value),
+            GeneticParameter::FruitMaturity(_) => GeneticParameter::FruitMaturity(value),
+            GeneticParameter::Gravitropism(_) => GeneticParameter::Gravitropism(value),
+            GeneticParameter::GravitropismAngle(_) => GeneticParameter::GravitropismAngle(value),
+            GeneticParameter::LateralGravitropism(_) => GeneticParameter::LateralGravitropism(value),
+            GeneticParameter::LateralGravitropismAngle(_) => GeneticParameter::LateralGravitropismAngle(value),
+            GeneticParameter::Phototropism(_) => GeneticParameter::Phototropism(value),
+            GeneticParameter::LateralPhototropism(_) => GeneticParameter::LateralPhototropism(value),
+            GeneticParameter::TropismOrderFactor(_) => GeneticParameter::TropismOrderFactor(value),
+            GeneticParameter::WoodStiffness(_) => GeneticParameter::WoodStiffness(value),
+            GeneticParameter::WoodDensity(_) => GeneticParameter::WoodDensity(value),
+            GeneticParameter::LeafMass(_) => GeneticParameter::LeafMass(value),
+        }
+    }
+
+    // inclusive range of values the model can work with
+    pub fn range(&self) -> (f32, f32) {
+        match self {
+            GeneticParameter::BorchertHondaLambda(_) => (0., 1.),
+            GeneticParameter::BorchertHondaAlpha(_) => (0., 10.),
+            GeneticParameter::PoleLength(_) => (0., 50.),
+            GeneticParameter::AuxShootReq(_) => (0., 10.),
+            GeneticParameter::TermShootReq(_) => (0., 10.),
+            GeneticParameter::MetamerBaseLength(_) => (0.01, 5.),
+            GeneticParameter::BudPerceptionAngle(_) => (0., PI),
+            GeneticParameter::BudPerceptionRadiusFactor(_) => (0., 10.),
+            GeneticParameter::OccupancyRadiusFactor(_) => (0., 10.),
+            GeneticParameter::AxillaryPerturbationAngle(_) => (0., PI),
+            GeneticParameter::OptimalGrowthDirectionWeight(_) => (0., 10.),
+            GeneticParameter::ShedTreshhold(_) => (0., 10.),
+            GeneticParameter::WidthGrowExponent(_) => (1., 5.),
+            GeneticParameter::WidthMinValue(_) => (0., 1.),
+            GeneticParameter::BudRecoverySpeed(_) => (0., 1.),
+            GeneticParameter::BudDormancyAge(_) => (0., 100.),
+            GeneticParameter::BudReleaseDistance(_) => (0., 100.),
+            GeneticParameter::BudReleaseLight(_) => (0., 10.),
+            GeneticParameter::BudLifespan(_) => (0., 1000.),
+            GeneticParameter::AuxinProduction(_) => (0., 10.),
+            GeneticParameter::AuxinDecay(_) => (0., 1.),
+            GeneticParameter::AuxinThreshold(_) => (0., 100.),
+            GeneticParameter::BudsPerNode(_) => (1., 6.),
+            GeneticParameter::DivergenceAngle(_) => (0., 2. * PI),
+            GeneticParameter::BranchingAngle(_) => (0., PI / 2.),
+            GeneticParameter::LeafSize(_) => (0., 1.),
+            GeneticParameter::LeafAngle(_) => (0., PI),
+            GeneticParameter::LeafLifespan(_) => (0., 1000.),
+            GeneticParameter::LeafAbscissionLight(_) => (0., 10.),
+            GeneticParameter::LeafEfficiency(_) => (0., 10.),
+            GeneticParameter::SpurLength(_) => (0., 20.),
+            GeneticParameter::FlowerAge(_) => (0., 100.),
+            GeneticParameter::FlowerLight(_) => (0., 10.),
+            GeneticParameter::FruitSink(_) => (0., 100.),
+            GeneticParameter::FruitSetRequirement(_) => (0., 10.),
+            GeneticParameter::FruitGrowth(_) => (0., 10.),
+            GeneticParameter::FruitMaturity(_) => (1., 100.),
+            GeneticParameter::Gravitropism(_) => (0., 10.),
+            GeneticParameter::GravitropismAngle(_) => (0., PI),
+            GeneticParameter::LateralGravitropism(_) => (0., 10.),
+            GeneticParameter::LateralGravitropismAngle(_) => (0., PI),
+            GeneticParameter::Phototropism(_) => (0., 10.),
+            GeneticParameter::LateralPhototropism(_) => (0., 10.),
+            GeneticParameter::TropismOrderFactor(_) => (0., 2.),
+            GeneticParameter::WoodStiffness(_) => (0., 50.),
+            GeneticParameter::WoodDensity(_) => (100., 1500.),
+            GeneticParameter::LeafMass(_) => (0., 2.),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -41,5 +378,6 @@ pub enum TreeParameter{
     Genetic(GeneticParameter),
     ResourceDistributionMode(DistributionMode),
     SpaceDividingMode(SpaceDividingMode),
-    PruneModOn(bool)
+    AutoPruning(Option<AutoPruning>),
+    Calendar(Option<Calendar>),
 }
\ No newline at end of file
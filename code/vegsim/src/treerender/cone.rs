@@ -3,7 +3,7 @@ use patutil::{
     Color, Matf4, Vecf3,
 };
 
-use crate::util::{rot_from_dir, scale_from_size, translation_from_pos};
+use vegsim::util::{rot_from_dir, scale_from_size, translation_from_pos};
 
 #[derive(Debug)]
 pub struct Cone {
@@ -16,8 +16,6 @@ pub struct Cone {
     dir: Vecf3,
 
     scene: mesh::SceneRef,
-
-    model_owner: bool
 }
 
 impl Cone {
@@ -48,7 +46,6 @@ impl Cone {
             pos: Vecf3::default(),
             dir: Vecf3::default(),
             scene,
-            model_owner: true
         };
         this.update_matrix();
         this
@@ -123,25 +120,8 @@ impl Cone {
     }
 }
 
-impl Clone for Cone {
-    fn clone(&self) -> Self {
-        Self {
-            model: self.model.clone(),
-            length: self.length.clone(),
-            width_base: self.width_base.clone(),
-            width_tip: self.width_tip.clone(),
-            pos: self.pos.clone(),
-            dir: self.dir.clone(),
-            scene: self.scene.clone(),
-            model_owner: false
-        }
-    }
-}
-
 impl Drop for Cone {
     fn drop(&mut self) {
-        if self.model_owner{
-            self.scene.controller().remove_model(&self.model);
-        }
+        self.scene.controller().remove_model(&self.model);
     }
 }
@@ -0,0 +1,51 @@
+use patutil::{
+    mesh::{self, ModelRef},
+    Color, Matf4,
+};
+
+use vegsim::tree::leaf::{Leaf, LEAF_WIDTH_RATIO};
+
+#[derive(Debug)]
+pub struct LeafQuad {
+    model: ModelRef,
+    scene: mesh::SceneRef,
+}
+
+impl LeafQuad {
+    // @param model textured leaf plane that is already added to the scene
+    pub fn new(model: ModelRef, scene: mesh::SceneRef, leaf: &Leaf, color: Color) -> Self {
+        model.lock().set_color(color);
+        model.lock().set_id(leaf.id());
+
+        let mut this = Self { model, scene };
+        this.update(leaf);
+        this
+    }
+
+    // place the quad on the blade of the leaf, the plane mesh spans -1 to 1 in x and y with its normal along z
+    pub fn update(&mut self, leaf: &Leaf) {
+        let length = leaf.direction() * (leaf.size() / 2.);
+        let width = leaf.normal().cross(leaf.direction()).norm() * (leaf.size() * LEAF_WIDTH_RATIO / 2.);
+        let normal = leaf.normal();
+        let center = leaf.center();
+
+        let mut model_mat = Matf4::new();
+        for (column, v) in [width, length, normal, center].iter().enumerate() {
+            model_mat.set(column, 0, v.x);
+            model_mat.set(column, 1, v.y);
+            model_mat.set(column, 2, v.z);
+        }
+
+        self.model.lock().set_model_mat(model_mat);
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.model.lock().set_color(color);
+    }
+}
+
+impl Drop for LeafQuad {
+    fn drop(&mut self) {
+        self.scene.controller().remove_model(&self.model);
+    }
+}
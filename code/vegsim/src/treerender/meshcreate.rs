@@ -1,5 +1,12 @@
+use std::f32::consts::PI;
+
 use glrender::GLMesh;
-use patutil::mesh::MeshRef;
+use patutil::{mesh::MeshRef, Color};
+
+// width and height of the leaf texture in pixels
+const LEAF_TEXTURE_SIZE: u32 = 32;
+// veins are a bit darker than the blade
+const LEAF_VEIN_COLOR: Color = Color::new(200, 215, 180, 255);
 
 
 
@@ -174,6 +181,44 @@ pub fn create_cone_mesh(base_width: f32, tip_width: f32) -> MeshRef {
     return mesh.into();
 }
 
-pub fn create_plane_mesh() -> MeshRef {
-    GLMesh::new_plane().into()
+// square from -1 to 1 in x and y facing z with a leaf blade texture, the base of the blade is at the bottom
+pub fn create_leaf_mesh() -> MeshRef {
+    let vertices: Vec<f32> = vec![
+        -1., -1., 0., 0., 0., 1., 0., 0., // bottom left
+        -1., 1., 0., 0., 0., 1., 0., 1., // top left
+        1., 1., 0., 0., 0., 1., 1., 1., // top right
+        -1., -1., 0., 0., 0., 1., 0., 0., // bottom left
+        1., 1., 0., 0., 0., 1., 1., 1., // top right
+        1., -1., 0., 0., 0., 1., 1., 0., // bottom right
+    ];
+    let size = [LEAF_TEXTURE_SIZE, LEAF_TEXTURE_SIZE];
+    GLMesh::with_texture(vertices, size.into(), create_leaf_texture()).into()
+}
+
+/**
+ * Blade with a midrib and side veins, white so it is tinted by the leaf color,
+ * transparent around the blade. The first row is the base of the blade.
+ */
+fn create_leaf_texture() -> Vec<Color> {
+    let size = LEAF_TEXTURE_SIZE as f32;
+    let mut pixels = vec![];
+    for y in 0..LEAF_TEXTURE_SIZE {
+        for x in 0..LEAF_TEXTURE_SIZE {
+            let along = (y as f32 + 0.5) / size;
+            let across = ((x as f32 + 0.5) / size - 0.5).abs() * 2.;
+
+            // widest below the middle of the blade and pointed at the tip
+            let half_width = (PI * along.powf(0.8)).sin();
+            let vein = across < 0.08 || ((along - across * 0.4) * 6.).fract() < 0.12;
+            let pixel = if across > half_width {
+                Color::new(0, 0, 0, 0)
+            } else if vein {
+                LEAF_VEIN_COLOR
+            } else {
+                Color::new(255, 255, 255, 255)
+            };
+            pixels.push(pixel);
+        }
+    }
+    pixels
 }
\ No newline at end of file
@@ -0,0 +1,121 @@
+mod cone;
+mod leafquad;
+mod meshcreate;
+mod partcreator;
+
+use std::collections::{HashMap, HashSet};
+
+use glrender::FIELD_OF_VIEW;
+use patutil::{camera::Camera, mesh, Color, Pos, Rect, Size, Vecf3};
+use vegsim::{
+    tree::{
+        branchdata::BranchData,
+        leaf::{Leaf, LEAF_COLOR},
+        plant::Plant,
+    },
+    util::width_to_radius,
+};
+
+use self::{cone::Cone, leafquad::LeafQuad, partcreator::PartCreator};
+
+const SELECTED_COLOR: Color = Color::new(0, 0, 255, 255);
+
+/**
+ * Ray from the camera through the cursor, the scene is drawn into rect with its origin at the bottom left
+ * @returns the origin and normalized direction of the ray
+ */
+pub fn picking_ray(camera: &dyn Camera, cursor: Pos, rect: Rect, screen: Size) -> (Vecf3, Vecf3) {
+    let to_vec3 = |v: patutil::Vecf4| Vecf3::new(v.x, v.y, v.z);
+    let front = to_vec3(camera.front()).norm();
+    let right = Vecf3::new(0., 1., 0.).cross(front).norm();
+    let up = front.cross(right);
+
+    // cursor positions start at the top left of the screen
+    let x = ((cursor.x - rect.x) as f32 / rect.w as f32) * 2. - 1.;
+    let y = ((screen.h as i32 - cursor.y - rect.y) as f32 / rect.h as f32) * 2. - 1.;
+    let scale = (FIELD_OF_VIEW / 2.).tan();
+    let aspect = rect.w as f32 / rect.h as f32;
+
+    let dir = front + right * (x * scale * aspect) + up * (y * scale);
+    (to_vec3(camera.pos()), dir.norm())
+}
+
+/**
+ * Keeps the models of a mesh scene in sync with the branches of a plant
+ */
+pub struct TreeRender {
+    partcreator: PartCreator,
+    cones: HashMap<u32, Cone>,
+    leaves: HashMap<u32, LeafQuad>,
+}
+
+impl TreeRender {
+    pub fn new(scene: mesh::SceneRef) -> Self {
+        Self {
+            partcreator: PartCreator::new(scene),
+            cones: HashMap::new(),
+            leaves: HashMap::new(),
+        }
+    }
+
+    // create, update and remove cones so the scene matches the plant
+    pub fn sync(&mut self, plant: &Plant, selected_id: Option<u32>) {
+        let mut seen = HashSet::new();
+        for data in plant.collect_branchdata() {
+            let color = if Some(data.id()) == selected_id {
+                SELECTED_COLOR
+            } else {
+                data.color()
+            };
+            self.update_cone(data, color);
+            seen.insert(data.id());
+        }
+
+        for leaf in plant.root().collect_leaves() {
+            let color = if Some(leaf.id()) == selected_id {
+                SELECTED_COLOR
+            } else {
+                LEAF_COLOR
+            };
+            self.update_leaf(leaf, color);
+            seen.insert(leaf.id());
+        }
+
+        // removed cones and leaves are taken out of the scene when dropped
+        self.cones.retain(|id, _| seen.contains(id));
+        self.leaves.retain(|id, _| seen.contains(id));
+    }
+
+    fn update_leaf(&mut self, leaf: &Leaf, color: Color) {
+        if let Some(quad) = self.leaves.get_mut(&leaf.id()) {
+            quad.update(leaf);
+            quad.set_color(color);
+        } else {
+            let model = self.partcreator.new_leaf();
+            let quad = LeafQuad::new(model, self.partcreator.scene(), leaf, color);
+            self.leaves.insert(leaf.id(), quad);
+        }
+    }
+
+    fn update_cone(&mut self, data: &BranchData, color: Color) {
+        let mut dir = data.end_point() - data.start_point();
+        let length = dir.length();
+        dir = dir.norm();
+        let base_width = width_to_radius(data.start_width());
+        let tip_width = width_to_radius(data.end_width());
+
+        if let Some(cone) = self.cones.get_mut(&data.id()) {
+            cone.set_length(length);
+            cone.set_width_base(base_width);
+            cone.set_width_tip(tip_width);
+            cone.set_dir(dir);
+            cone.set_pos(data.start_point());
+            cone.set_color(color);
+        } else {
+            let mut cone = self.partcreator.new_cone(length, base_width, tip_width, color, data.id());
+            cone.set_dir(dir);
+            cone.set_pos(data.start_point());
+            self.cones.insert(data.id(), cone);
+        }
+    }
+}
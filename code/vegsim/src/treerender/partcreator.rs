@@ -1,13 +1,11 @@
 use patutil::{mesh::{MeshRef, SceneRef, ModelRef}, Color};
 
-use crate::util::{create_cone_mesh, create_plane_mesh};
-
-use super::{cone::Cone};
+use super::{cone::Cone, meshcreate::{create_cone_mesh, create_leaf_mesh}};
 
 #[derive(Debug, Clone)]
 pub struct PartCreator {
     cone: MeshRef,
-    plane: MeshRef,
+    leaf: MeshRef,
     scene: SceneRef
 }
 
@@ -16,19 +14,22 @@ impl PartCreator {
 
         let cone = create_cone_mesh(1., 1.);
 
-        let plane = create_plane_mesh();
+        let leaf = create_leaf_mesh();
         
-        Self { cone, plane, scene }
+        Self { cone, leaf, scene }
     }
 
     pub fn new_cone(&self, length: f32, width_base: f32, width_tip: f32, color: Color, id: u32) -> Cone{
         Cone::new(self.cone.clone(), self.scene.clone(), length, width_base, width_tip, color, id)
     }
-    pub fn new_plane(&mut self) -> ModelRef{
-        let model: ModelRef = self.plane.clone().into();
+    pub fn new_leaf(&mut self) -> ModelRef{
+        let model: ModelRef = self.leaf.clone().into();
         self.scene.controller().add_model(model.clone());
         model
     }
+    pub fn scene(&self) -> SceneRef {
+        self.scene.clone()
+    }
 
     pub fn clear(&mut self){
         self.scene.controller().clear();
@@ -2,15 +2,14 @@ use std::sync::{Arc, Mutex};
 
 use iced_glow::{Alignment, Length, Renderer};
 use iced_glutin::{
-    widget::{checkbox, radio, Row, Text},
+    widget::{radio, Button, Row, Scrollable, Text},
     widget::{Column, Slider},
     Element,
 };
 
-use crate::{
-    controller::Controller,
-    treeparameter::{DistributionMode, GeneticParameter, SpaceDividingMode, TreeParameter},
-};
+use vegsim::treeparameter::{DistributionMode, GeneticParameter, SpaceDividingMode, TreeParameter};
+
+use crate::controller::Controller;
 
 use super::Message;
 
@@ -42,63 +41,36 @@ impl GeneticsView {
             _ => SpaceDividingMode::None,
         };
 
-        let prune_mod = match treedata.get_tree_param(TreeParameter::PruneModOn(false)) {
-            TreeParameter::PruneModOn(mode) => mode,
-            _ => false,
-        };
+        let mut sliders = Column::new().spacing(5).padding(5);
+        for param in GeneticParameter::ALL {
+            let param = genetics.get_param(param);
+            let (label, unit, step) = Self::slider_settings(&param);
+
+            // the slider covers the range a profile may hold, angles are stored in radians but shown in degrees
+            let degrees = unit == "°";
+            let (min, max) = param.range();
+            let shown = |value: f32| if degrees { value.to_degrees() } else { value };
+            let value = shown(param.value());
+            let decimals = (-step.log10()).ceil().max(0.) as usize;
+
+            sliders = sliders
+                .push(Text::new(format!("{}: {:.*} {}", label, decimals, value, unit)))
+                .push(
+                    Slider::new(shown(min)..=shown(max), value, move |value| {
+                        // converting back to radians can step just outside the range
+                        let value = if degrees { value.to_radians() } else { value };
+                        Message::ParamUpdate(TreeParameter::Genetic(param.with_value(value.clamp(min, max))))
+                    })
+                    .step(step),
+                );
+        }
 
         return Column::new()
             .spacing(5)
             .padding(10)
             .width(Length::Fill)
-            .push(Text::new(
-                format! {"Borchert honda lambda: {:.2}",genetics.borchert_honda_lambda()},
-            ))
-            .push(
-                Slider::new(0.4..=0.6, genetics.borchert_honda_lambda(), move |value| {
-                    Message::ParamUpdate(TreeParameter::Genetic(
-                        GeneticParameter::BorchertHondaLambda(value),
-                    ))
-                })
-                .step(0.01),
-            )
-            .push(Text::new(
-                format! {"Borchert honda alpha: {:.2}",genetics.borchert_honda_alpha()},
-            ))
-            .push(
-                Slider::new(0.5..=3.0, genetics.borchert_honda_alpha(), move |value| {
-                    Message::ParamUpdate(TreeParameter::Genetic(
-                        GeneticParameter::BorchertHondaAlpha(value),
-                    ))
-                })
-                .step(0.1),
-            )
-            .push(Text::new(
-                format! {"Pole length: {:.2}",genetics.base_pole_lenght()},
-            ))
-            .push(
-                Slider::new(0.0..=3.0, genetics.base_pole_lenght(), move |value| {
-                    Message::ParamUpdate(TreeParameter::Genetic(GeneticParameter::PoleLength(
-                        value,
-                    )))
-                })
-                .step(0.1),
-            )
-            .push(Text::new(
-                format! {"Auxillary shoot req: {:.2}",genetics.aux_shoot_requirement(None)},
-            ))
-            .push(
-                Slider::new(
-                    0.5..=3.0,
-                    genetics.aux_shoot_requirement(None),
-                    move |value| {
-                        Message::ParamUpdate(TreeParameter::Genetic(GeneticParameter::AuxShootReq(
-                            value,
-                        )))
-                    },
-                )
-                .step(0.1),
-            )
+            .push(Scrollable::new(sliders).height(Length::Units(250)))
+            .push(Button::new("Reset to profile defaults").on_press(Message::ResetParams))
             .push(
                 Row::new()
                     .push(radio(
@@ -129,10 +101,60 @@ impl GeneticsView {
                         |v| Message::ParamUpdate(TreeParameter::SpaceDividingMode(v)),
                     )),
             )
-            .push(Row::new().push(checkbox("Spalier", prune_mod, |v| {
-                Message::ParamUpdate(TreeParameter::PruneModOn(v))
-            })))
             .align_items(Alignment::Center)
             .into();
     }
+
+    // label, unit and slider step of a genetic parameter
+    fn slider_settings(param: &GeneticParameter) -> (&'static str, &'static str, f32) {
+        match param {
+            GeneticParameter::BorchertHondaLambda(_) => ("Borchert honda lambda", "", 0.01),
+            GeneticParameter::BorchertHondaAlpha(_) => ("Borchert honda alpha", "", 0.1),
+            GeneticParameter::PoleLength(_) => ("Pole length", "units", 0.1),
+            GeneticParameter::AuxShootReq(_) => ("Auxillary shoot req", "resources", 0.1),
+            GeneticParameter::TermShootReq(_) => ("Terminal shoot req", "resources", 0.1),
+            GeneticParameter::MetamerBaseLength(_) => ("Metamer base length", "units", 0.01),
+            GeneticParameter::BudPerceptionAngle(_) => ("Bud perception angle", "°", 1.),
+            GeneticParameter::BudPerceptionRadiusFactor(_) => ("Bud perception radius", "units", 0.1),
+            GeneticParameter::OccupancyRadiusFactor(_) => ("Occupancy radius", "units", 0.1),
+            GeneticParameter::AxillaryPerturbationAngle(_) => ("Axillary perturbation angle", "°", 0.5),
+            GeneticParameter::OptimalGrowthDirectionWeight(_) => ("Optimal growth dir weight", "", 0.01),
+            GeneticParameter::ShedTreshhold(_) => ("Shed treshhold", "light", 0.005),
+            GeneticParameter::WidthGrowExponent(_) => ("Width grow exponent", "", 0.05),
+            GeneticParameter::WidthMinValue(_) => ("Min width", "units", 0.000001),
+            GeneticParameter::BudRecoverySpeed(_) => ("Bud recovery speed", "per iteration", 0.01),
+            GeneticParameter::BudDormancyAge(_) => ("Bud dormancy age", "iterations", 1.0),
+            GeneticParameter::BudReleaseDistance(_) => ("Bud release distance", "metamers", 1.0),
+            GeneticParameter::BudReleaseLight(_) => ("Bud release light", "light", 0.01),
+            GeneticParameter::BudLifespan(_) => ("Bud lifespan", "iterations", 1.0),
+            GeneticParameter::AuxinProduction(_) => ("Auxin production", "per bud", 0.05),
+            GeneticParameter::AuxinDecay(_) => ("Auxin decay", "per metamer", 0.01),
+            GeneticParameter::AuxinThreshold(_) => ("Auxin threshold", "auxin", 0.05),
+            GeneticParameter::BudsPerNode(_) => ("Buds per node", "buds", 1.0),
+            GeneticParameter::DivergenceAngle(_) => ("Divergence angle", "°", 0.5),
+            GeneticParameter::BranchingAngle(_) => ("Branching angle", "°", 1.),
+            GeneticParameter::LeafSize(_) => ("Leaf size", "units", 0.01),
+            GeneticParameter::LeafAngle(_) => ("Leaf angle", "°", 1.),
+            GeneticParameter::LeafLifespan(_) => ("Leaf lifespan", "iterations", 1.0),
+            GeneticParameter::LeafAbscissionLight(_) => ("Leaf abscission", "light", 0.01),
+            GeneticParameter::LeafEfficiency(_) => ("Leaf efficiency", "light", 0.05),
+            GeneticParameter::SpurLength(_) => ("Spur length", "metamers", 1.0),
+            GeneticParameter::FlowerAge(_) => ("Flower age", "iterations", 1.0),
+            GeneticParameter::FlowerLight(_) => ("Flower light", "light", 0.05),
+            GeneticParameter::FruitSink(_) => ("Fruit sink", "x", 0.1),
+            GeneticParameter::FruitSetRequirement(_) => ("Fruit set requirement", "resources", 0.05),
+            GeneticParameter::FruitGrowth(_) => ("Fruit growth", "kg", 0.005),
+            GeneticParameter::FruitMaturity(_) => ("Fruit maturity", "iterations", 1.0),
+            GeneticParameter::Gravitropism(_) => ("Gravitropism", "", 0.01),
+            GeneticParameter::GravitropismAngle(_) => ("Gravitropism angle", "°", 1.),
+            GeneticParameter::LateralGravitropism(_) => ("Lateral gravitropism", "", 0.01),
+            GeneticParameter::LateralGravitropismAngle(_) => ("Lateral gravitropism angle", "°", 1.),
+            GeneticParameter::Phototropism(_) => ("Phototropism", "", 0.01),
+            GeneticParameter::LateralPhototropism(_) => ("Lateral phototropism", "", 0.01),
+            GeneticParameter::TropismOrderFactor(_) => ("Tropism order factor", "x", 0.01),
+            GeneticParameter::WoodStiffness(_) => ("Wood stiffness", "GPa", 0.1),
+            GeneticParameter::WoodDensity(_) => ("Wood density", "kg/m3", 10.0),
+            GeneticParameter::LeafMass(_) => ("Leaf mass", "kg/m2", 0.01),
+        }
+    }
 }
@@ -0,0 +1,104 @@
+use std::sync::{Arc, Mutex};
+
+use iced_glow::{Alignment, Color, Length, Renderer};
+use iced_glutin::{
+    widget::{Button, Column, Text},
+    Element,
+};
+
+use crate::controller::Controller;
+
+use super::Message;
+
+// amount of the latest prunes shown
+const SHOWN_RECORDS: usize = 5;
+
+pub struct HistoryView {
+    controller: Arc<Mutex<Controller>>,
+}
+
+impl HistoryView {
+    pub fn new(controller: Arc<Mutex<Controller>>) -> Self {
+        Self { controller }
+    }
+
+    pub fn get_ui(&self) -> Element<Message, Renderer> {
+        let controller = self.controller.lock().unwrap();
+        let treedata = controller.treedata().lock().unwrap();
+        let history = treedata.pruning_history();
+
+        let mut column = Column::new()
+            .spacing(5)
+            .width(Length::Fill)
+            .align_items(Alignment::Center)
+            .push(Text::new("Pruning history").style(Color::WHITE));
+
+        // totals per rule or strategy
+        for total in history.summary() {
+            column = column.push(
+                Text::new(format!(
+                    "{}: {} prunes, {} metamers, {:.2} dm3, light {:.1}",
+                    total.source,
+                    total.prunes,
+                    total.metamers,
+                    total.biomass * 1000.,
+                    total.light
+                ))
+                .size(14)
+                .style(Color::WHITE),
+            );
+        }
+
+        let records = history.records();
+        for record in records.iter().skip(records.len().saturating_sub(SHOWN_RECORDS)) {
+            column = column.push(
+                Text::new(format!(
+                    "iteration {} {}: {} metamers, {:.3} dm3",
+                    record.iteration,
+                    record.source,
+                    record.removed_ids.len(),
+                    record.biomass * 1000.
+                ))
+                .size(14)
+                .style(Color::WHITE),
+            );
+        }
+
+        // fruit load after the last iteration and the harvest so far
+        let fruit = treedata.fruit_history();
+        if let Some(load) = fruit.last() {
+            let (harvested, harvested_mass) = fruit.total_yield();
+            column = column.push(
+                Text::new(format!(
+                    "Fruit: {} on the plant, {:.2} kg, yield {} fruits, {:.2} kg",
+                    load.fruits, load.mass, harvested, harvested_mass
+                ))
+                .size(14)
+                .style(Color::WHITE),
+            );
+        }
+
+        // the season that is going on
+        let seasons = treedata.season_history().summaries(history, fruit);
+        if let Some(summary) = seasons.last() {
+            column = column.push(
+                Text::new(format!(
+                    "Year {} {}: {} iterations, {} prunes, {} fruits harvested",
+                    summary.record.year,
+                    summary.record.season,
+                    summary.record.iterations,
+                    summary.prunes,
+                    summary.harvested
+                ))
+                .size(14)
+                .style(Color::WHITE),
+            );
+        }
+
+        let mut export = Button::new("Export CSV");
+        if !records.is_empty() {
+            export = export.on_press(Message::ExportPruningHistory);
+        }
+        column.push(export).into()
+    }
+}
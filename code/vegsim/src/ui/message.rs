@@ -1,11 +1,30 @@
-use crate::{treeparameter::TreeParameter, tree::pruning_module::PruneOperation};
+use vegsim::{treeparameter::TreeParameter, tree::{pruning_module::PruneOperation, timeline::TimelineBranch}};
 
 #[derive(Debug, Clone)]
 pub enum Message {
     IndexChanged(u32),
     CheckboxToggled(bool),
+    CutModeToggled(bool),
     PruneButton(PruneOperation),
     Reset,
     Recalculate,
+    SaveSnapshot,
+    LoadSnapshot,
+    ImportSkeleton,
+    SeedChanged(String),
+    SeedSubmitted,
+    ProfileSelected(String),
+    ProfileNameChanged(String),
+    SaveProfile,
+    RulesSelected(String),
+    PruneByRules,
+    VolumeSelected(String),
+    TrimToVolume,
+    ExportPruningHistory,
+    Undo,
+    Redo,
+    TimelineChanged(u32),
+    BranchSelected(TimelineBranch),
     ParamUpdate(TreeParameter),
+    ResetParams,
 }
@@ -58,6 +58,28 @@ impl MetamerInfo {
                         .size(20)
                         .style(Color::WHITE),
                 )
+                .push(
+                    Text::new(format!(
+                        "term bud {}, aux bud {}, auxin {:.3}, fruit {:.3} kg",
+                        metamer.terminal_bud_fate.state(),
+                        metamer.aux_bud_fate.state(),
+                        metamer.auxin,
+                        metamer.fruit.as_ref().map_or(0., |fruit| fruit.mass())
+                    ))
+                    .size(20)
+                    .style(Color::WHITE),
+                )
+                .push(
+                    Text::new(format!(
+                        "buds at node {}, phyllotaxis {:.1}°, leaves {}, bending {:.1}°",
+                        metamer.extra_buds().len() + 1,
+                        metamer.phyllotaxis_angle().to_degrees(),
+                        metamer.leaves().len(),
+                        metamer.bending.length().to_degrees()
+                    ))
+                    .size(17)
+                    .style(Color::WHITE),
+                )
                 .push(
                     Text::new(format!(
                         "Supportpole {:.2?}",
@@ -1,40 +1,86 @@
 mod geneticsview;
+mod historyview;
 mod message;
 mod metamerinfo;
 mod prunebuttons;
+mod strategyview;
+mod timelineview;
 
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 use crate::controller::Controller;
+use vegsim::profile::PROFILE_EXTENSION;
+use vegsim::tree::pruning_module::{hedge::VOLUME_EXTENSION, rules::RULES_EXTENSION};
+use vegsim::util::list_files;
 use iced_glow::Renderer;
-use iced_glutin::widget::{Button, Checkbox, Slider};
+use iced_glutin::widget::{Button, Checkbox, PickList, Slider, TextInput};
 use iced_glutin::widget::{Column, Row, Text};
 use iced_glutin::{Alignment, Color, Command, Element, Length, Program};
 
 use self::geneticsview::GeneticsView;
+use self::historyview::HistoryView;
 use self::message::Message;
 use self::metamerinfo::MetamerInfo;
 use self::prunebuttons::PruneButtons;
+use self::strategyview::StrategyView;
+use self::timelineview::TimelineView;
+
+const SNAPSHOT_PATH: &str = "snapshot.txt";
+const SKELETON_PATH: &str = "skeleton.csv";
+const PRUNING_HISTORY_PATH: &str = "pruning_history.csv";
+const PROFILE_DIR: &str = "profiles";
+const RULES_DIR: &str = "rules";
+const VOLUMES_DIR: &str = "volumes";
 
 pub struct Controls {
     controller: Arc<Mutex<Controller>>,
     index: u32,
     show_markers: bool,
+    // right click cuts the branch under the cursor instead of selecting it
+    cut_mode: bool,
+    seed_input: String,
+    profiles: Vec<String>,
+    profile: Option<String>,
+    // name the current parameters are saved under in the profile directory
+    profile_name_input: String,
+    rule_files: Vec<String>,
+    rule_file: Option<String>,
+    volume_files: Vec<String>,
+    volume_file: Option<String>,
 
     metamer_info_element: MetamerInfo,
     prune_buttons_element: PruneButtons,
     genetics_view: GeneticsView,
+    strategy_view: StrategyView,
+    timeline_view: TimelineView,
+    history_view: HistoryView,
 }
 
 impl Controls {
     pub fn new(controller: Arc<Mutex<Controller>>) -> Controls {
+        let seed = controller.lock().unwrap().seed();
+        let profile = controller.lock().unwrap().profile_name();
+        let profiles = list_text_files(PROFILE_DIR, PROFILE_EXTENSION);
         Controls {
             index: 0,
             show_markers: false,
+            cut_mode: false,
+            seed_input: seed.to_string(),
+            profile_name_input: profile.clone(),
+            profile: profiles.contains(&profile).then_some(profile),
+            profiles,
+            rule_files: list_text_files(RULES_DIR, RULES_EXTENSION),
+            rule_file: None,
+            volume_files: list_text_files(VOLUMES_DIR, VOLUME_EXTENSION),
+            volume_file: None,
 
             metamer_info_element: MetamerInfo::new(controller.clone()),
             prune_buttons_element: PruneButtons::new(),
             genetics_view: GeneticsView::new(controller.clone()),
+            strategy_view: StrategyView::new(controller.clone()),
+            timeline_view: TimelineView::new(controller.clone()),
+            history_view: HistoryView::new(controller.clone()),
 
             controller,
         }
@@ -49,9 +95,15 @@ impl Controls {
             .spacing(10)
             // .push(self.mode_select())
             .push(self.reset_button())
+            .push(self.timeline_view.get_ui())
             .push(self.genetics_view.get_ui())
+            .push(self.strategy_view.get_ui())
             .push(self.prune_buttons_element.get_ui())
+            .push(self.rules_select())
+            .push(self.volume_select())
+            .push(self.history_view.get_ui())
             .push(self.metamer_info_element.get_ui())
+            .push(self.cut_mode_check())
             .push(self.marker_check())
             .push(self.slider());
 
@@ -76,6 +128,81 @@ impl Controls {
             .align_items(Alignment::Center)
             .push(Button::new("Reset").on_press(Message::Reset))
             .push(Button::new("Recalculate").on_press(Message::Recalculate))
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .push(Button::new("Save snapshot").on_press(Message::SaveSnapshot))
+                    .push(Button::new("Load snapshot").on_press(Message::LoadSnapshot)),
+            )
+            .push(Button::new("Import skeleton").on_press(Message::ImportSkeleton))
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                    .push(Text::new("Seed").style(Color::WHITE))
+                    .push(
+                        TextInput::new("seed", &self.seed_input, Message::SeedChanged)
+                            .on_submit(Message::SeedSubmitted)
+                            .width(Length::Units(150)),
+                    )
+                    .push(Button::new("Apply").on_press(Message::SeedSubmitted)),
+            )
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                    .push(Text::new("Profile").style(Color::WHITE))
+                    .push(
+                        PickList::new(self.profiles.clone(), self.profile.clone(), Message::ProfileSelected)
+                            .placeholder("default")
+                            .width(Length::Units(150)),
+                    ),
+            )
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                    .push(
+                        TextInput::new("profile name", &self.profile_name_input, Message::ProfileNameChanged)
+                            .on_submit(Message::SaveProfile)
+                            .width(Length::Units(150)),
+                    )
+                    .push(Button::new("Save profile").on_press(Message::SaveProfile)),
+            )
+            .into();
+    }
+
+    fn rules_select(&self) -> Element<Message, Renderer> {
+        let mut prune = Button::new("Prune by rules");
+        if self.rule_file.is_some() {
+            prune = prune.on_press(Message::PruneByRules);
+        }
+        return Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(
+                PickList::new(self.rule_files.clone(), self.rule_file.clone(), Message::RulesSelected)
+                    .placeholder("rule file")
+                    .width(Length::Units(150)),
+            )
+            .push(prune)
+            .into();
+    }
+
+    fn volume_select(&self) -> Element<Message, Renderer> {
+        let mut trim = Button::new("Trim to volume");
+        if self.volume_file.is_some() {
+            trim = trim.on_press(Message::TrimToVolume);
+        }
+        return Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(
+                PickList::new(self.volume_files.clone(), self.volume_file.clone(), Message::VolumeSelected)
+                    .placeholder("volume file")
+                    .width(Length::Units(150)),
+            )
+            .push(trim)
             .into();
     }
 
@@ -99,6 +226,10 @@ impl Controls {
         return Checkbox::new(self.show_markers, "Show markers", Message::CheckboxToggled).into();
     }
 
+    fn cut_mode_check(&self) -> Element<Message, Renderer> {
+        return Checkbox::new(self.cut_mode, "Cut mode (right click)", Message::CutModeToggled).into();
+    }
+
     fn slider(&self) -> Element<Message, Renderer> {
         let index = self.index;
 
@@ -120,6 +251,18 @@ impl Controls {
     pub fn show_markers(&self) -> bool {
         self.show_markers
     }
+
+    pub fn cut_mode(&self) -> bool {
+        self.cut_mode
+    }
+}
+
+// files to choose from in the UI, a missing directory gives an empty list
+fn list_text_files(dir: &str, extension: &str) -> Vec<String> {
+    list_files(Path::new(dir), extension).unwrap_or_else(|err| {
+        println!("No files found in {}: {}", dir, err);
+        vec![]
+    })
 }
 
 impl Program for Controls {
@@ -134,6 +277,9 @@ impl Program for Controls {
             Message::CheckboxToggled(toggle) => {
                 self.show_markers = toggle;
             }
+            Message::CutModeToggled(toggle) => {
+                self.cut_mode = toggle;
+            }
             Message::PruneButton(op) => {
                 self.controller.lock().unwrap().perform_prune(op);
             }
@@ -143,9 +289,109 @@ impl Program for Controls {
             Message::Recalculate => {
                 self.controller.lock().unwrap().recalculate_plants();
             }
+            Message::SaveSnapshot => {
+                let path = Path::new(SNAPSHOT_PATH);
+                match self.controller.lock().unwrap().save_snapshot(path) {
+                    Ok(()) => println!("Snapshot saved to {}", SNAPSHOT_PATH),
+                    Err(err) => println!("Failed to save snapshot: {}", err),
+                }
+            }
+            Message::LoadSnapshot => {
+                let path = Path::new(SNAPSHOT_PATH);
+                match self.controller.lock().unwrap().load_snapshot(path) {
+                    Ok(()) => println!("Snapshot loaded from {}", SNAPSHOT_PATH),
+                    Err(err) => println!("Failed to load snapshot: {}", err),
+                }
+            }
+            Message::ImportSkeleton => {
+                let path = Path::new(SKELETON_PATH);
+                match self.controller.lock().unwrap().import_skeleton(path) {
+                    Ok(()) => println!("Skeleton imported from {}", SKELETON_PATH),
+                    Err(err) => println!("Failed to import skeleton: {}", err),
+                }
+            }
+            Message::SeedChanged(seed) => {
+                self.seed_input = seed;
+            }
+            Message::SeedSubmitted => match self.seed_input.trim().parse() {
+                Ok(seed) => self.controller.lock().unwrap().set_seed(seed),
+                Err(_) => println!("Invalid seed {}", self.seed_input),
+            },
+            Message::ProfileSelected(name) => {
+                let path = Path::new(PROFILE_DIR).join(format!("{}.{}", name, PROFILE_EXTENSION));
+                match self.controller.lock().unwrap().load_profile(&path) {
+                    Ok(()) => {
+                        println!("Profile loaded from {}", path.display());
+                        self.profile = Some(name);
+                    }
+                    Err(err) => println!("Failed to load profile {}: {}", path.display(), err),
+                }
+            }
+            Message::ProfileNameChanged(name) => {
+                self.profile_name_input = name;
+            }
+            Message::SaveProfile => {
+                let name = self.profile_name_input.trim().to_string();
+                if name.is_empty() || name.contains(|c: char| c == '/' || c == '\\') {
+                    println!("Invalid profile name {}", self.profile_name_input);
+                    return Command::none();
+                }
+                let path = Path::new(PROFILE_DIR).join(format!("{}.{}", name, PROFILE_EXTENSION));
+                match self.controller.lock().unwrap().save_profile(&name, &path) {
+                    Ok(()) => {
+                        println!("Profile saved to {}", path.display());
+                        self.profiles = list_text_files(PROFILE_DIR, PROFILE_EXTENSION);
+                    }
+                    Err(err) => println!("Failed to save profile {}: {}", path.display(), err),
+                }
+            }
+            Message::RulesSelected(name) => {
+                self.rule_file = Some(name);
+            }
+            Message::PruneByRules => {
+                if let Some(name) = &self.rule_file {
+                    let path = Path::new(RULES_DIR).join(format!("{}.{}", name, RULES_EXTENSION));
+                    if let Err(err) = self.controller.lock().unwrap().prune_by_rules(&path) {
+                        println!("Failed to prune by {}: {}", path.display(), err);
+                    }
+                }
+            }
+            Message::VolumeSelected(name) => {
+                self.volume_file = Some(name);
+            }
+            Message::TrimToVolume => {
+                if let Some(name) = &self.volume_file {
+                    let path = Path::new(VOLUMES_DIR).join(format!("{}.{}", name, VOLUME_EXTENSION));
+                    if let Err(err) = self.controller.lock().unwrap().trim_to_volume(&path) {
+                        println!("Failed to trim to {}: {}", path.display(), err);
+                    }
+                }
+            }
+            Message::ExportPruningHistory => {
+                let path = Path::new(PRUNING_HISTORY_PATH);
+                match self.controller.lock().unwrap().export_pruning_history(path) {
+                    Ok(()) => println!("Pruning history saved to {}", PRUNING_HISTORY_PATH),
+                    Err(err) => println!("Failed to save pruning history: {}", err),
+                }
+            }
+            Message::Undo => {
+                self.controller.lock().unwrap().undo();
+            }
+            Message::Redo => {
+                self.controller.lock().unwrap().redo();
+            }
+            Message::TimelineChanged(position) => {
+                self.controller.lock().unwrap().scrub(position as usize);
+            }
+            Message::BranchSelected(branch) => {
+                self.controller.lock().unwrap().switch_branch(&branch);
+            }
             Message::ParamUpdate(param) =>{
                 self.controller.lock().unwrap().update_tree_param(param);
             }
+            Message::ResetParams => {
+                self.controller.lock().unwrap().reset_tree_params();
+            }
         }
 
         Command::none()
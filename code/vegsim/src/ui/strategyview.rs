@@ -0,0 +1,160 @@
+use std::sync::{Arc, Mutex};
+
+use iced_glow::{Alignment, Color, Length, Renderer};
+use iced_glutin::{
+    widget::{Column, PickList, Row, Slider, Text},
+    Element,
+};
+
+use vegsim::{
+    tree::{
+        calendar::Calendar,
+        pruning_module::strategy::{AutoPruning, PruningSchedule, PruningSeason, StrategyRegistry},
+    },
+    treeparameter::TreeParameter,
+};
+
+use crate::controller::Controller;
+
+use super::Message;
+
+// shown in the strategy list to turn automatic pruning off
+const NO_STRATEGY: &str = "none";
+
+const SCHEDULES: [PruningSchedule; 5] = [
+    PruningSchedule::EveryIteration,
+    PruningSchedule::Every(2),
+    PruningSchedule::Every(3),
+    PruningSchedule::Every(5),
+    PruningSchedule::Winter,
+];
+
+const SEASONS: [PruningSeason; 3] = [PruningSeason::Any, PruningSeason::Summer, PruningSeason::Winter];
+
+// shown in the calendar list to grow without seasons
+const NO_CALENDAR: &str = "no calendar";
+const CALENDARS: [&str; 3] = ["3 growing 1 dormant", "4 growing 2 dormant", "6 growing 2 dormant"];
+
+pub struct StrategyView {
+    controller: Arc<Mutex<Controller>>,
+}
+
+impl StrategyView {
+    pub fn new(controller: Arc<Mutex<Controller>>) -> Self {
+        Self { controller }
+    }
+
+    pub fn get_ui(&self) -> Element<Message, Renderer> {
+        let controller = self.controller.lock().unwrap();
+        let auto_pruning = match controller.get_tree_param(TreeParameter::AutoPruning(None)) {
+            TreeParameter::AutoPruning(auto_pruning) => auto_pruning,
+            _ => None,
+        };
+
+        let mut names = vec![NO_STRATEGY.to_string()];
+        names.extend(StrategyRegistry::default().names().iter().map(|name| name.to_string()));
+        let selected = match &auto_pruning {
+            Some(auto_pruning) => auto_pruning.strategy().name().to_string(),
+            None => NO_STRATEGY.to_string(),
+        };
+        let schedule = auto_pruning
+            .as_ref()
+            .map_or(PruningSchedule::EveryIteration, |auto_pruning| auto_pruning.schedule());
+        let season = auto_pruning
+            .as_ref()
+            .map_or(PruningSeason::Any, |auto_pruning| auto_pruning.season());
+
+        let calendar = match controller.get_tree_param(TreeParameter::Calendar(None)) {
+            TreeParameter::Calendar(calendar) => calendar,
+            _ => None,
+        };
+        let mut calendars = vec![NO_CALENDAR.to_string()];
+        calendars.extend(CALENDARS.iter().map(|calendar| calendar.to_string()));
+        let selected_calendar = calendar.map_or(NO_CALENDAR.to_string(), |calendar| calendar.to_string());
+
+        let mut column = Column::new()
+            .spacing(5)
+            .padding(10)
+            .width(Length::Fill)
+            .align_items(Alignment::Center)
+            .push(Text::new("Automatic pruning").style(Color::WHITE))
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .push(
+                        PickList::new(names, Some(selected), move |name| {
+                            let auto_pruning = StrategyRegistry::default()
+                                .create(&name)
+                                .ok()
+                                .and_then(|strategy| {
+                                    // summer strategies cannot keep a winter schedule
+                                    AutoPruning::new(strategy.clone(), schedule)
+                                        .or_else(|_| AutoPruning::new(strategy, PruningSchedule::EveryIteration))
+                                        .ok()
+                                });
+                            Message::ParamUpdate(TreeParameter::AutoPruning(auto_pruning))
+                        })
+                        .width(Length::Units(120)),
+                    )
+                    .push(
+                        PickList::new(SCHEDULES.to_vec(), Some(schedule), {
+                            let auto_pruning = auto_pruning.clone();
+                            move |schedule| {
+                                let mut auto_pruning = auto_pruning.clone();
+                                if let Some(auto_pruning) = &mut auto_pruning {
+                                    // a schedule that never runs in the season is not taken
+                                    auto_pruning.set_schedule(schedule).ok();
+                                }
+                                Message::ParamUpdate(TreeParameter::AutoPruning(auto_pruning))
+                            }
+                        })
+                        .width(Length::Units(170)),
+                    ),
+            )
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .push(
+                        PickList::new(calendars, Some(selected_calendar), |calendar| {
+                            Message::ParamUpdate(TreeParameter::Calendar(calendar.parse::<Calendar>().ok()))
+                        })
+                        .width(Length::Units(170)),
+                    )
+                    .push(
+                        PickList::new(SEASONS.to_vec(), Some(season), {
+                            let auto_pruning = auto_pruning.clone();
+                            move |season| {
+                                let mut auto_pruning = auto_pruning.clone();
+                                if let Some(auto_pruning) = &mut auto_pruning {
+                                    // a season the schedule never runs in is not taken
+                                    auto_pruning.set_season(season).ok();
+                                }
+                                Message::ParamUpdate(TreeParameter::AutoPruning(auto_pruning))
+                            }
+                        })
+                        .width(Length::Units(120)),
+                    ),
+            );
+
+        if let Some(auto_pruning) = auto_pruning {
+            for param in auto_pruning.strategy().parameters() {
+                let name = param.name;
+                let decimals = if param.step < 1. { 1 } else { 0 };
+                column = column
+                    .push(Text::new(format!("{}: {:.*}", param.name, decimals, param.value)))
+                    .push({
+                        let auto_pruning = auto_pruning.clone();
+                        Slider::new(param.min..=param.max, param.value, move |value| {
+                            let mut auto_pruning = auto_pruning.clone();
+                            // the slider stays inside the range
+                            let _ = auto_pruning.strategy_mut().set_parameter(name, value);
+                            Message::ParamUpdate(TreeParameter::AutoPruning(Some(auto_pruning)))
+                        })
+                        .step(param.step)
+                    });
+            }
+        }
+
+        column.into()
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::{Arc, Mutex};
+
+use iced_glow::{Alignment, Color, Length, Renderer};
+use iced_glutin::{
+    widget::{Button, Column, PickList, Row, Slider, Text},
+    Element,
+};
+
+use crate::controller::Controller;
+
+use super::Message;
+
+pub struct TimelineView {
+    controller: Arc<Mutex<Controller>>,
+}
+
+impl TimelineView {
+    pub fn new(controller: Arc<Mutex<Controller>>) -> Self {
+        Self { controller }
+    }
+
+    pub fn get_ui(&self) -> Element<Message, Renderer> {
+        let controller = self.controller.lock().unwrap();
+        let treedata = controller.treedata().lock().unwrap();
+        let timeline = treedata.timeline();
+
+        let position = timeline.position() as u32;
+        let last = timeline.steps() as u32 - 1;
+
+        // buttons without a message are shown disabled
+        let mut undo = Button::new("Undo");
+        if timeline.can_undo() {
+            undo = undo.on_press(Message::Undo);
+        }
+        let mut redo = Button::new("Redo");
+        if timeline.can_redo() {
+            redo = redo.on_press(Message::Redo);
+        }
+
+        let mut column = Column::new()
+            .spacing(5)
+            .width(Length::Fill)
+            .align_items(Alignment::Center)
+            .push(
+                Text::new(format!(
+                    "Step {}/{}: iteration {} after {}",
+                    position,
+                    last,
+                    timeline.current().growth_iteration,
+                    timeline.current_action()
+                ))
+                .style(Color::WHITE),
+            )
+            .push(Row::new().spacing(5).push(undo).push(redo));
+
+        // a slider needs at least two values
+        if last > 0 {
+            column = column.push(Slider::new(0..=last, position, Message::TimelineChanged).step(1));
+        }
+
+        let branches = timeline.branches();
+        if branches.len() > 1 {
+            column = column.push(
+                PickList::new(branches, timeline.current_branch(), Message::BranchSelected)
+                    .width(Length::Units(250)),
+            );
+        }
+
+        column.into()
+    }
+}
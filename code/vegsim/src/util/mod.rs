@@ -1,16 +1,59 @@
 
 mod boundingvolume;
 pub use boundingvolume::*;
-mod meshcreate;
-pub use meshcreate::*;
+pub mod json;
 pub mod random;
 
+use std::{fs, io, path::Path};
+
 use patutil::{Matf4, Vecf3};
 
 pub fn meter_to_real_length(length_in_meters: f32) -> f32{
     length_in_meters*5.
 }
 
+// branch widths are stored at a smaller scale than positions
+pub fn width_to_radius(width: f32) -> f32{
+    width*100.
+}
+pub fn radius_to_width(radius: f32) -> f32{
+    radius/100.
+}
+
+// names of the files in a directory with the given extension, without extension and sorted
+pub fn list_files(dir: &Path, extension: &str) -> io::Result<Vec<String>> {
+    let mut names = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(extension) {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+// fraction along the segment from start to end of the point closest to a ray, e.g. a picking ray from the camera
+pub fn closest_fraction_to_ray(start: Vecf3, end: Vecf3, origin: Vecf3, dir: Vecf3) -> f32 {
+    let segment = end - start;
+    let offset = start - origin;
+    let (a, b, c) = (segment * segment, segment * dir, dir * dir);
+    let (d, e) = (segment * offset, dir * offset);
+    if a < 1.0e-12 {
+        return 0.;
+    }
+    let denominator = a * c - b * b;
+    let fraction = if denominator.abs() < 1.0e-12 {
+        // parallel, the closest point to the ray origin
+        -d / a
+    } else {
+        (b * e - c * d) / denominator
+    };
+    fraction.clamp(0., 1.)
+}
 
 pub fn scale_from_size(size: &Vecf3) -> Matf4{
     let mut scale = Matf4::new();
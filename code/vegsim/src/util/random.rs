@@ -1,35 +1,69 @@
-use std::sync::Mutex;
-
 use pcg_rand::Pcg32;
-use rand::{ seq::SliceRandom, Rng, SeedableRng};
-
-use crate::parameters;
-
-lazy_static::lazy_static! {
-    static ref RANDOM_DATA: Mutex<Random> = Mutex::new(Random::new());
-    // static ref SEED: u64 = std::time::SystemTime::now().elapsed().unwrap().as_secs();
-}
+use rand::{seq::SliceRandom, Rng, RngCore, SeedableRng};
 
+/**
+ * Random number generator of one simulation
+ */
 pub struct Random {
     rng: Pcg32,
+    seed: u64,
+    draws: u64,
 }
 
 impl Random {
-    pub fn new() -> Self {
-        let rng = Pcg32::seed_from_u64(parameters::SEED);
-        Self { rng }
+    pub fn new(seed: u64) -> Self {
+        Self::from_state(seed, 0)
+    }
+
+    // recreate a generator by replaying the amount of numbers drawn since seeding
+    pub fn from_state(seed: u64, draws: u64) -> Self {
+        let mut rng = Pcg32::seed_from_u64(seed);
+        for _ in 0..draws {
+            rng.next_u32();
+        }
+        Self { rng, seed, draws }
+    }
+
+    pub fn rand(&mut self) -> f32 {
+        self.gen_range(0f32..1f32)
+    }
+
+    pub fn choose<'a, T>(&mut self, list: &'a [T]) -> &'a T {
+        list.choose(self).unwrap()
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // @returns the amount of numbers drawn since seeding
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+}
+
+// every number is drawn through next_u32 so the draw count fully describes the state
+impl RngCore for Random {
+    fn next_u32(&mut self) -> u32 {
+        self.draws += 1;
+        self.rng.next_u32()
     }
 
-    pub fn rand() -> f32 {
-        // rand::thread_rng().gen_range(0f32..1f32)
-        RANDOM_DATA.lock().unwrap().rng.gen_range(0f32..1f32)
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        let high = self.next_u32() as u64;
+        (high << 32) | low
     }
 
-    pub fn choose<T>(list: &[T]) -> &T {
-        list.choose(&mut RANDOM_DATA.lock().unwrap().rng).unwrap()
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
     }
 
-    pub fn reset(){
-        RANDOM_DATA.lock().unwrap().rng = Pcg32::seed_from_u64(parameters::SEED);
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
     }
 }